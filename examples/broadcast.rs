@@ -1,26 +1,16 @@
 use ethers::core::{rand::thread_rng, types::transaction::eip2718::TypedTransaction};
 use ethers::prelude::*;
+use ethers_flashbots::builders;
 use ethers_flashbots::*;
 use eyre::Result;
 use std::convert::TryFrom;
-use url::Url;
 
-// See https://www.mev.to/builders for up to date builder URLs
-static BUILDER_URLS: &[&str] = &[
-    "https://builder0x69.io",
-    "https://rpc.beaverbuild.org",
-    "https://relay.flashbots.net",
-    "https://rsync-builder.xyz",
-    "https://rpc.titanbuilder.xyz",
-    "https://api.blocknative.com/v1/auction",
-    "https://mev.api.blxrbdn.com",
-    "https://eth-builder.com",
-    "https://builder.gmbit.co/rpc",
-    "https://buildai.net",
-    "https://rpc.payload.de",
-    "https://rpc.lightspeedbuilder.info",
-    "https://rpc.nfactorial.xyz",
-    "https://rpc.lokibuilder.xyz",
+// See https://www.mev.to/builders for other builders
+static BUILDERS: &[builders::BuilderEndpoint] = &[
+    builders::mainnet::FLASHBOTS,
+    builders::mainnet::BEAVERBUILD,
+    builders::mainnet::RSYNC,
+    builders::mainnet::TITAN,
 ];
 
 #[tokio::main]
@@ -35,13 +25,10 @@ async fn main() -> Result<()> {
 
     // Add signer and Flashbots middleware
     let client = SignerMiddleware::new(
-        BroadcasterMiddleware::new(
+        BroadcasterMiddleware::from_endpoints(
             provider,
-            BUILDER_URLS
-                .iter()
-                .map(|url| Url::parse(url).unwrap())
-                .collect(),
-            Url::parse("https://relay.flashbots.net")?,
+            BUILDERS,
+            builders::mainnet::FLASHBOTS.url(),
             bundle_signer,
         ),
         wallet,
@@ -58,7 +45,7 @@ async fn main() -> Result<()> {
     };
     let signature = client.signer().sign_transaction(&tx).await?;
     let bundle = BundleRequest::new()
-        .push_transaction(tx.rlp_signed(&signature))
+        .push_typed_transaction(&tx, &signature)
         .set_block(block_number + 1)
         .set_simulation_block(block_number)
         .set_simulation_timestamp(0);
@@ -0,0 +1,156 @@
+use ethers::{
+    core::types::U256,
+    middleware::{gas_escalator::GasEscalator, gas_oracle::GasOracleError},
+};
+use std::{error::Error as StdError, sync::Arc};
+use thiserror::Error;
+
+/// The maximum change of the base fee between blocks, as a denominator.
+const BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+/// The ratio of the gas limit to the gas target of a block.
+const ELASTICITY_MULTIPLIER: u64 = 2;
+
+/// Computes the base fee of the next block from the gas used, gas limit
+/// and base fee of its parent block, as specified in [EIP-1559][eip_1559].
+///
+/// ```
+/// use ethers_flashbots::next_block_basefee;
+///
+/// // A full block increases the base fee by 12.5%
+/// let basefee = next_block_basefee(30_000_000.into(), 30_000_000.into(), 800.into());
+/// assert_eq!(basefee, 900.into());
+/// ```
+///
+/// [eip_1559]: https://eips.ethereum.org/EIPS/eip-1559
+pub fn next_block_basefee(gas_used: U256, gas_limit: U256, basefee: U256) -> U256 {
+    let gas_target = gas_limit / ELASTICITY_MULTIPLIER;
+    if gas_target.is_zero() || gas_used == gas_target {
+        return basefee;
+    }
+
+    if gas_used > gas_target {
+        let delta = basefee.saturating_mul(gas_used - gas_target)
+            / gas_target
+            / BASE_FEE_MAX_CHANGE_DENOMINATOR;
+        basefee.saturating_add(delta.max(U256::one()))
+    } else {
+        let delta = basefee.saturating_mul(gas_target - gas_used)
+            / gas_target
+            / BASE_FEE_MAX_CHANGE_DENOMINATOR;
+        basefee.saturating_sub(delta)
+    }
+}
+
+/// The number of seconds between blocks, used to drive [`GasEscalator`]s.
+const SECONDS_PER_BLOCK: u64 = 12;
+
+/// How the predicted base fee of the next block is escalated for bundles
+/// that target a later block.
+///
+/// See [`BundleRequest::set_simulation_basefee_from_provider`](crate::BundleRequest::set_simulation_basefee_from_provider).
+#[derive(Debug, Clone, Default)]
+pub enum BasefeeEscalation {
+    /// Use the predicted base fee of the next block, regardless of the
+    /// target block.
+    #[default]
+    None,
+    /// Assume the base fee rises by the maximum of 12.5% in every block
+    /// between the next block and the target block.
+    Maximum,
+    /// Escalate the base fee using a [`GasEscalator`], such as
+    /// [`GeometricGasPrice`](ethers::middleware::gas_escalator::GeometricGasPrice),
+    /// assuming 12 seconds elapse per block.
+    Escalator(Arc<dyn GasEscalator>),
+}
+
+impl BasefeeEscalation {
+    /// Escalates the base fee of the next block to the base fee of the
+    /// block `blocks` blocks after it.
+    pub fn escalate(&self, basefee: U256, blocks: u64) -> U256 {
+        match self {
+            BasefeeEscalation::None => basefee,
+            BasefeeEscalation::Maximum => (0..blocks).fold(basefee, |basefee, _| {
+                basefee.saturating_add(basefee / BASE_FEE_MAX_CHANGE_DENOMINATOR)
+            }),
+            BasefeeEscalation::Escalator(escalator) => {
+                escalator.get_gas_price(basefee, blocks.saturating_mul(SECONDS_PER_BLOCK))
+            }
+        }
+    }
+}
+
+/// Errors for setting the simulation base fee of a bundle from the chain
+/// or from a gas oracle.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum SimulationBasefeeError {
+    /// The provider failed to return the latest block.
+    #[error("Provider error: {0}")]
+    ProviderError(Box<dyn StdError + Send + Sync>),
+    /// The gas oracle failed to return a gas price.
+    #[error(transparent)]
+    OracleError(#[from] GasOracleError),
+    /// The latest block is not available.
+    #[error("The latest block is not available")]
+    MissingBlock,
+    /// The latest block does not have a base fee, i.e. the chain does not
+    /// implement EIP-1559.
+    #[error("The latest block has no base fee")]
+    MissingBasefee,
+    /// The base fee does not fit in 64 bits.
+    #[error("The base fee {0} is too large")]
+    BasefeeOverflow(U256),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::middleware::gas_escalator::GeometricGasPrice;
+
+    #[test]
+    fn next_basefee() {
+        let limit = U256::from(30_000_000);
+
+        // Target usage
+        assert_eq!(
+            next_block_basefee(15_000_000.into(), limit, 1_000_000_000.into()),
+            1_000_000_000.into()
+        );
+        // Empty block
+        assert_eq!(
+            next_block_basefee(0.into(), limit, 1_000_000_000.into()),
+            875_000_000.into()
+        );
+        // Slightly above the target increases the base fee by at least 1 wei
+        assert_eq!(
+            next_block_basefee(15_000_001.into(), limit, 7.into()),
+            8.into()
+        );
+        // Below the target
+        assert_eq!(
+            next_block_basefee(
+                12_200_479.into(),
+                30_000_000.into(),
+                21_139_869_792u64.into()
+            ),
+            20_646_690_547u64.into()
+        );
+        assert_eq!(next_block_basefee(0.into(), 0.into(), 7.into()), 7.into());
+    }
+
+    #[test]
+    fn escalate_basefee() {
+        let basefee = U256::from(800);
+
+        assert_eq!(BasefeeEscalation::None.escalate(basefee, 2), basefee);
+        assert_eq!(BasefeeEscalation::Maximum.escalate(basefee, 0), basefee);
+        assert_eq!(BasefeeEscalation::Maximum.escalate(basefee, 2), 1012.into());
+
+        let escalator = GeometricGasPrice::new(2.0, SECONDS_PER_BLOCK, None::<u64>);
+        assert_eq!(
+            BasefeeEscalation::Escalator(Arc::new(escalator)).escalate(basefee, 2),
+            3200.into()
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! A command line tool to simulate and send bundles, and to query stats.
+//!
+//! Requires the `cli` feature:
+//!
+//! ```text
+//! cargo install ethers-flashbots --features cli
+//! flashbots send bundle.json --relay https://relay.flashbots.net --key-env FLASHBOTS_KEY
+//! ```
+use ethers::{
+    core::types::{BlockNumber, H256, U64},
+    providers::{Http, Middleware, Provider},
+    signers::LocalWallet,
+};
+use ethers_flashbots::{BundleRequest, FlashbotsMiddleware};
+use std::{collections::HashMap, convert::TryFrom, env, error::Error, process};
+use url::Url;
+
+const USAGE: &str = "\
+Usage:
+  flashbots send <bundle.json> [options] [--simulate-only] [--allow-reverts] [--wait]
+  flashbots bundle-stats <bundle hash> <block> [options]
+  flashbots user-stats [options]
+
+Bundles are read from JSON files written by `BundleRequest::to_file`.
+Bundles without a target block target the next block.
+
+Options:
+  --relay <url>     The URL of the relay [default: https://relay.flashbots.net]
+  --key-env <name>  The environment variable holding the private key used to
+                    sign requests [default: FLASHBOTS_KEY]
+  --rpc <url>       The URL of the Ethereum RPC endpoint [default: $ETH_RPC_URL]
+  --simulate-only   Simulate the bundle without sending it
+  --allow-reverts   Send the bundle even if transactions that are not allowed
+                    to revert failed or reverted in simulation
+  --wait            Wait for the target block and report whether the bundle
+                    was included";
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// The parsed command line arguments.
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Args {
+            positional: Vec::new(),
+            options: HashMap::new(),
+            flags: Vec::new(),
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name @ ("simulate-only" | "allow-reverts" | "wait" | "help")) => {
+                    parsed.flags.push(name.to_string())
+                }
+                Some(name) => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("missing value for --{}", name))?;
+                    parsed.options.insert(name.to_string(), value);
+                }
+                None => parsed.positional.push(arg),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    fn positional(&self, index: usize, name: &str) -> Result<&str> {
+        self.positional
+            .get(index)
+            .map(String::as_str)
+            .ok_or_else(|| format!("missing argument <{}>", name).into())
+    }
+}
+
+fn client(args: &Args) -> Result<FlashbotsMiddleware<Provider<Http>, LocalWallet>> {
+    let rpc = match args.option("rpc") {
+        Some(rpc) => rpc.to_string(),
+        None => env::var("ETH_RPC_URL").map_err(|_| "missing --rpc or $ETH_RPC_URL")?,
+    };
+    let provider = Provider::<Http>::try_from(rpc)?;
+
+    let relay = Url::parse(
+        args.option("relay")
+            .unwrap_or("https://relay.flashbots.net"),
+    )?;
+    let key_env = args.option("key-env").unwrap_or("FLASHBOTS_KEY");
+    let signer: LocalWallet = env::var(key_env)
+        .map_err(|_| format!("missing signing key in ${}", key_env))?
+        .parse()?;
+
+    Ok(FlashbotsMiddleware::new(provider, relay, signer))
+}
+
+async fn send(args: &Args) -> Result<()> {
+    let client = client(args)?;
+    let mut bundle = BundleRequest::from_file(args.positional(1, "bundle.json")?)?;
+
+    let latest = client.get_block(BlockNumber::Latest).await?;
+    let latest = latest.ok_or("the latest block is not available")?;
+    let latest_number = latest.number.ok_or("the latest block is pending")?;
+    if bundle.block().is_none() {
+        bundle = bundle.set_block(latest_number + 1);
+    }
+    if bundle.simulation_state().is_none() {
+        bundle = bundle.set_simulation_block(latest_number);
+    }
+    if bundle.simulation_timestamp().is_none() {
+        bundle = bundle.set_simulation_timestamp(latest.timestamp.as_u64());
+    }
+
+    let simulation = client.simulate_bundle(&bundle).await?;
+    println!("{}", serde_json::to_string_pretty(&simulation)?);
+    if args.flag("simulate-only") {
+        return Ok(());
+    }
+    if !args.flag("allow-reverts") && !simulation.is_success_for(&bundle) {
+        let tx = simulation
+            .first_error()
+            .ok_or("the simulation of the bundle failed")?;
+        return Err(format!(
+            "transaction {:?} reverted in simulation, pass --allow-reverts to send the bundle anyway",
+            tx.hash
+        )
+        .into());
+    }
+
+    let pending = client.send_bundle(&bundle).await?;
+    match pending.bundle_hash {
+        Some(bundle_hash) => println!("Sent bundle {:?} for block {}", bundle_hash, pending.block),
+        None => println!("Sent bundle for block {}", pending.block),
+    }
+    if args.flag("wait") {
+        pending.await?;
+        println!("Bundle was included");
+    }
+
+    Ok(())
+}
+
+async fn bundle_stats(args: &Args) -> Result<()> {
+    let client = client(args)?;
+    let bundle_hash: H256 = args.positional(1, "bundle hash")?.parse()?;
+    let block = U64::from_dec_str(args.positional(2, "block")?)?;
+
+    let stats = client.get_bundle_stats(bundle_hash, block).await?;
+    println!("{:#?}", stats);
+    Ok(())
+}
+
+async fn user_stats(args: &Args) -> Result<()> {
+    let client = client(args)?;
+
+    let stats = client.get_user_stats().await?;
+    println!("{:#?}", stats);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let res = match Args::parse(env::args().skip(1)) {
+        Ok(args) if args.flag("help") => {
+            println!("{}", USAGE);
+            return;
+        }
+        Ok(args) => match args.positional.first().map(String::as_str) {
+            Some("send") => send(&args).await,
+            Some("bundle-stats") => bundle_stats(&args).await,
+            Some("user-stats") => user_stats(&args).await,
+            Some(command) => Err(format!("unknown command {}", command).into()),
+            None => Err("missing command".into()),
+        },
+        Err(err) => Err(err),
+    };
+
+    if let Err(err) = res {
+        eprintln!("error: {}\n\n{}", err, USAGE);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parse_args() {
+        let args = parse(&[
+            "send",
+            "bundle.json",
+            "--relay",
+            "https://relay.example.com",
+            "--allow-reverts",
+            "--wait",
+        ])
+        .unwrap();
+        assert_eq!(args.positional(0, "command").unwrap(), "send");
+        assert_eq!(args.positional(1, "bundle.json").unwrap(), "bundle.json");
+        assert!(args.positional(2, "block").is_err());
+        assert_eq!(args.option("relay"), Some("https://relay.example.com"));
+        assert_eq!(args.option("rpc"), None);
+        assert!(args.flag("allow-reverts"));
+        assert!(args.flag("wait"));
+        assert!(!args.flag("simulate-only"));
+    }
+
+    #[test]
+    fn parse_args_missing_value() {
+        let err = parse(&["user-stats", "--key-env"]).err().unwrap();
+        assert_eq!(err.to_string(), "missing value for --key-env");
+    }
+}
@@ -0,0 +1,336 @@
+//! Well-known builder endpoints, grouped by network.
+//!
+//! Each endpoint carries the API dialect and capabilities of the builder,
+//! and can be turned into a relay using [`Relay::from_endpoint`](crate::Relay::from_endpoint),
+//! or used directly with [`BroadcasterMiddleware::from_endpoints`](crate::BroadcasterMiddleware::from_endpoints).
+//! Some builders require additional configuration, such as an
+//! authorization header for bloXroute.
+//!
+//! Builder endpoints change over time, so the endpoints in this module
+//! are updated with new releases of this crate. See
+//! [mev.to](https://www.mev.to/builders) for a community-maintained list
+//! of builders.
+//!
+//! ```
+//! use ethers_flashbots::builders;
+//!
+//! let endpoints = builders::for_chain(1);
+//! assert!(endpoints.contains(&builders::mainnet::FLASHBOTS));
+//! ```
+use crate::{
+    capabilities::BuilderCapabilities,
+    dialect::{BloxrouteNetwork, RelayDialect},
+    network::Network,
+};
+use url::Url;
+
+/// A well-known builder endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuilderEndpoint {
+    name: &'static str,
+    url: &'static str,
+    network: Network,
+    dialect: RelayDialect,
+    capabilities: BuilderCapabilities,
+}
+
+impl BuilderEndpoint {
+    const fn new(
+        name: &'static str,
+        url: &'static str,
+        network: Network,
+        dialect: RelayDialect,
+        capabilities: BuilderCapabilities,
+    ) -> Self {
+        Self {
+            name,
+            url,
+            network,
+            dialect,
+            capabilities,
+        }
+    }
+
+    /// Get the name of the builder.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get the URL of the endpoint.
+    pub fn url(&self) -> Url {
+        Url::parse(self.url).expect("builder endpoint URLs are valid")
+    }
+
+    /// Get the network the builder builds blocks for.
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Get the ID of the chain the builder builds blocks for.
+    pub fn chain_id(&self) -> u64 {
+        self.network.chain_id()
+    }
+
+    /// Get the API dialect of the endpoint.
+    pub fn dialect(&self) -> RelayDialect {
+        self.dialect
+    }
+
+    /// Get the capabilities of the builder.
+    pub fn capabilities(&self) -> BuilderCapabilities {
+        self.capabilities
+    }
+}
+
+/// Builders and relays on Ethereum mainnet.
+pub mod mainnet {
+    use super::*;
+
+    /// The ID of Ethereum mainnet.
+    pub const CHAIN_ID: u64 = Network::Mainnet.chain_id();
+
+    /// The network of the builders.
+    pub const NETWORK: Network = Network::Mainnet;
+
+    /// The Flashbots builder.
+    pub const FLASHBOTS: BuilderEndpoint = BuilderEndpoint::new(
+        "flashbots",
+        "https://relay.flashbots.net",
+        NETWORK,
+        RelayDialect::Flashbots,
+        BuilderCapabilities::FLASHBOTS,
+    );
+
+    /// The beaverbuild builder.
+    pub const BEAVERBUILD: BuilderEndpoint = BuilderEndpoint::new(
+        "beaverbuild",
+        "https://rpc.beaverbuild.org",
+        NETWORK,
+        RelayDialect::Flashbots,
+        BuilderCapabilities {
+            cancellation: true,
+            refunds: true,
+            blob_transactions: true,
+            ..BuilderCapabilities::BASIC
+        },
+    );
+
+    /// The rsync builder.
+    pub const RSYNC: BuilderEndpoint = BuilderEndpoint::new(
+        "rsync",
+        "https://rsync-builder.xyz",
+        NETWORK,
+        RelayDialect::Flashbots,
+        BuilderCapabilities {
+            cancellation: true,
+            ..BuilderCapabilities::BASIC
+        },
+    );
+
+    /// The Eden Network relay.
+    pub const EDEN: BuilderEndpoint = BuilderEndpoint::new(
+        "eden",
+        "https://api.edennetwork.io/v1/bundle",
+        NETWORK,
+        RelayDialect::Eden,
+        BuilderCapabilities {
+            private_transactions: true,
+            ..BuilderCapabilities::BASIC
+        },
+    );
+
+    /// The bloXroute Cloud API.
+    ///
+    /// Requires an authorization header, see
+    /// [`Relay::set_auth_header`](crate::Relay::set_auth_header).
+    pub const BLOXROUTE: BuilderEndpoint = BuilderEndpoint::new(
+        "bloxroute",
+        "https://api.blxrbdn.com",
+        NETWORK,
+        RelayDialect::Bloxroute(BloxrouteNetwork::Mainnet),
+        BuilderCapabilities::BASIC,
+    );
+
+    /// The Titan builder.
+    ///
+    /// Titan supports extensions of the bundle API, which can be used
+    /// with [`BundleRequest::set_builder_field`](crate::BundleRequest::set_builder_field)
+    /// and the `titan` field profile.
+    pub const TITAN: BuilderEndpoint = BuilderEndpoint::new(
+        "titan",
+        "https://rpc.titanbuilder.xyz",
+        NETWORK,
+        RelayDialect::Flashbots,
+        BuilderCapabilities {
+            mev_share: false,
+            ..BuilderCapabilities::ALL
+        },
+    );
+
+    /// All known builders and relays on Ethereum mainnet.
+    pub const ALL: &[BuilderEndpoint] = &[FLASHBOTS, BEAVERBUILD, RSYNC, TITAN, EDEN, BLOXROUTE];
+}
+
+/// Builders on the Sepolia testnet.
+pub mod sepolia {
+    use super::*;
+
+    /// The ID of the Sepolia testnet.
+    pub const CHAIN_ID: u64 = Network::Sepolia.chain_id();
+
+    /// The network of the builders.
+    pub const NETWORK: Network = Network::Sepolia;
+
+    /// The Flashbots builder.
+    pub const FLASHBOTS: BuilderEndpoint = BuilderEndpoint::new(
+        "flashbots",
+        "https://relay-sepolia.flashbots.net",
+        NETWORK,
+        RelayDialect::Flashbots,
+        BuilderCapabilities::FLASHBOTS,
+    );
+
+    /// All known builders on the Sepolia testnet.
+    pub const ALL: &[BuilderEndpoint] = &[FLASHBOTS];
+}
+
+/// Builders on the Holesky testnet.
+pub mod holesky {
+    use super::*;
+
+    /// The ID of the Holesky testnet.
+    pub const CHAIN_ID: u64 = Network::Holesky.chain_id();
+
+    /// The network of the builders.
+    pub const NETWORK: Network = Network::Holesky;
+
+    /// The Flashbots builder.
+    pub const FLASHBOTS: BuilderEndpoint = BuilderEndpoint::new(
+        "flashbots",
+        "https://relay-holesky.flashbots.net",
+        NETWORK,
+        RelayDialect::Flashbots,
+        BuilderCapabilities::FLASHBOTS,
+    );
+
+    /// All known builders on the Holesky testnet.
+    pub const ALL: &[BuilderEndpoint] = &[FLASHBOTS];
+}
+
+/// Builders on BNB Smart Chain.
+pub mod bsc {
+    use super::*;
+
+    /// The ID of BNB Smart Chain.
+    pub const CHAIN_ID: u64 = Network::Bsc.chain_id();
+
+    /// The network of the builders.
+    pub const NETWORK: Network = Network::Bsc;
+
+    /// The 48 Club builder.
+    pub const CLUB48: BuilderEndpoint = BuilderEndpoint::new(
+        "48club",
+        "https://puissant-builder.48.club",
+        NETWORK,
+        RelayDialect::Bsc,
+        BuilderCapabilities::BASIC,
+    );
+
+    /// The legacy 48 Club Puissant API.
+    pub const CLUB48_PUISSANT: BuilderEndpoint = BuilderEndpoint::new(
+        "48club-puissant",
+        "https://puissant-bsc.48.club",
+        NETWORK,
+        RelayDialect::Puissant,
+        BuilderCapabilities::BASIC,
+    );
+
+    /// The bloXroute Cloud API.
+    ///
+    /// Requires an authorization header, see
+    /// [`Relay::set_auth_header`](crate::Relay::set_auth_header).
+    pub const BLOXROUTE: BuilderEndpoint = BuilderEndpoint::new(
+        "bloxroute",
+        "https://api.blxrbdn.com",
+        NETWORK,
+        RelayDialect::Bloxroute(BloxrouteNetwork::Bsc),
+        BuilderCapabilities::BASIC,
+    );
+
+    /// All known builders on BNB Smart Chain.
+    pub const ALL: &[BuilderEndpoint] = &[CLUB48, CLUB48_PUISSANT, BLOXROUTE];
+}
+
+/// Builders and relays on Polygon.
+pub mod polygon {
+    use super::*;
+
+    /// The ID of Polygon.
+    pub const CHAIN_ID: u64 = Network::Polygon.chain_id();
+
+    /// The network of the builders.
+    pub const NETWORK: Network = Network::Polygon;
+
+    /// The Marlin relay.
+    pub const MARLIN: BuilderEndpoint = BuilderEndpoint::new(
+        "marlin",
+        "https://bor.txrelay.marlin.org",
+        NETWORK,
+        RelayDialect::MevBor,
+        BuilderCapabilities::BASIC,
+    );
+
+    /// The bloXroute Cloud API.
+    ///
+    /// Requires an authorization header, see
+    /// [`Relay::set_auth_header`](crate::Relay::set_auth_header).
+    pub const BLOXROUTE: BuilderEndpoint = BuilderEndpoint::new(
+        "bloxroute",
+        "https://api.blxrbdn.com",
+        NETWORK,
+        RelayDialect::Bloxroute(BloxrouteNetwork::Polygon),
+        BuilderCapabilities::BASIC,
+    );
+
+    /// All known builders and relays on Polygon.
+    pub const ALL: &[BuilderEndpoint] = &[MARLIN, BLOXROUTE];
+}
+
+/// Get all known builders on the given network.
+pub fn for_network(network: Network) -> &'static [BuilderEndpoint] {
+    match network {
+        Network::Mainnet => mainnet::ALL,
+        Network::Sepolia => sepolia::ALL,
+        Network::Holesky => holesky::ALL,
+        Network::Bsc => bsc::ALL,
+        Network::Polygon => polygon::ALL,
+    }
+}
+
+/// Get all known builders on the chain with the given ID.
+///
+/// Returns an empty slice for unknown chains.
+pub fn for_chain(chain_id: u64) -> &'static [BuilderEndpoint] {
+    Network::from_chain_id(chain_id).map_or(&[], for_network)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_endpoints() {
+        for network in Network::ALL {
+            let endpoints = for_chain(network.chain_id());
+            assert!(!endpoints.is_empty());
+
+            for (i, endpoint) in endpoints.iter().enumerate() {
+                assert_eq!(endpoint.url().scheme(), "https");
+                assert_eq!(endpoint.network(), *network);
+                // Names are used as field profiles, so they must be unique
+                assert!(endpoints[..i].iter().all(|e| e.name() != endpoint.name()));
+            }
+        }
+        assert!(for_chain(0).is_empty());
+    }
+}
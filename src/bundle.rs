@@ -1,10 +1,30 @@
-use crate::utils::{deserialize_optional_h160, deserialize_u256, deserialize_u64};
+use crate::{
+    basefee::{next_block_basefee, BasefeeEscalation, SimulationBasefeeError},
+    scheduler::SLOT_DURATION,
+    utils::{
+        deserialize_optional_h160, deserialize_u256, deserialize_u64, serialize_optional_h160,
+    },
+};
 use chrono::{DateTime, Utc};
-use ethers::core::{
-    types::{transaction::response::Transaction, Address, Bytes, TxHash, H256, U256, U64},
-    utils::keccak256,
+use ethers::{
+    core::{
+        types::{
+            transaction::{
+                eip2718::TypedTransaction,
+                response::{Transaction, TransactionReceipt},
+            },
+            Address, Block, BlockNumber, Bytes, FeeHistory, Signature, SignatureError, TxHash,
+            H256, I256, U256, U64,
+        },
+        utils::{get_contract_address, keccak256, rlp},
+    },
+    middleware::gas_oracle::GasOracle,
+    providers::Middleware,
 };
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::{borrow::Cow, collections::BTreeMap, convert::TryFrom, fmt, time::Duration};
+use thiserror::Error;
 use uuid::Uuid;
 
 /// A bundle hash.
@@ -30,14 +50,96 @@ impl From<Bytes> for BundleTransaction {
         Self::Raw(tx)
     }
 }
+
+/// Errors for decoding bundle transactions.
+#[derive(Error, Debug)]
+pub enum TransactionDecodeError {
+    /// The transaction is not valid RLP.
+    #[error(transparent)]
+    Rlp(#[from] rlp::DecoderError),
+    /// The sender of the transaction could not be recovered.
+    #[error(transparent)]
+    Signature(#[from] SignatureError),
+}
+
+impl BundleTransaction {
+    /// The RLP encoding of the signed transaction.
+    pub(crate) fn rlp(&self) -> Bytes {
+        match self {
+            BundleTransaction::Signed(inner) => inner.rlp(),
+            BundleTransaction::Raw(inner) => inner.clone(),
+        }
+    }
+
+    /// Whether the transaction is an [EIP-4844][eip_4844] blob transaction.
+    ///
+    /// [eip_4844]: https://eips.ethereum.org/EIPS/eip-4844
+    pub(crate) fn is_blob(&self) -> bool {
+        match self {
+            BundleTransaction::Signed(inner) => inner.transaction_type == Some(U64::from(3)),
+            BundleTransaction::Raw(inner) => inner.first() == Some(&0x03),
+        }
+    }
+
+    /// Decode the transaction.
+    ///
+    /// For [`BundleTransaction::Raw`] transactions, the RLP is decoded and
+    /// the sender is recovered from the signature. Pre-signed transactions
+    /// are returned as-is.
+    pub fn decode(&self) -> Result<Transaction, TransactionDecodeError> {
+        match self {
+            BundleTransaction::Signed(inner) => Ok(*inner.clone()),
+            BundleTransaction::Raw(inner) => {
+                let mut tx: Transaction = rlp::decode(inner)?;
+                tx.recover_from_mut()?;
+                Ok(tx)
+            }
+        }
+    }
+}
+
+/// Options for a transaction added to a bundle.
+///
+/// See [`BundleRequest::push_transaction_with_options`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxOptions {
+    /// Whether the bundle is still valid if the transaction reverts.
+    pub revertible: bool,
+    /// Whether the transaction may be dropped from the bundle, e.g. if it
+    /// is no longer valid, instead of the whole bundle being dropped.
+    ///
+    /// This is not supported by all builders.
+    pub can_drop: bool,
+    /// The percentage of the payment of this transaction that should be
+    /// refunded.
+    ///
+    /// This sets the refund percentage of the whole bundle, and marks the
+    /// transaction as subject to the refund, so all transactions subject
+    /// to the refund must use the same percentage. This is not supported
+    /// by all builders.
+    pub refund_percent: Option<u8>,
+}
+
+/// Errors for adding transactions with [`TxOptions`] to a bundle.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TxOptionsError {
+    /// The refund percentage is above 100.
+    #[error("Refund percentage {0} is above 100")]
+    InvalidRefundPercent(u8),
+    /// The refund percentage differs from the refund percentage already
+    /// set for the bundle.
+    #[error("Refund percentage {requested} conflicts with the refund percentage {current} of the bundle")]
+    RefundPercentConflict { current: u8, requested: u8 },
+}
 /// A bundle that can be submitted to a Flashbots relay.
 ///
 /// The bundle can include your own transactions and transactions from
 /// the mempool.
 ///
 /// Additionally, this bundle can be simulated through a relay if simulation
-/// parameters are provided using [`BundleRequest::set_simulation_block`] and
-/// [`BundleRequest::set_simulation_timestamp`].
+/// parameters are provided using [`BundleRequest::set_simulation_timestamp`]
+/// and optionally [`BundleRequest::set_simulation_block`] (which defaults to
+/// the latest block).
 ///
 /// Please note that some parameters are required, and submitting a bundle
 /// without them will get it rejected pre-flight. The required parameters
@@ -45,16 +147,30 @@ impl From<Bytes> for BundleTransaction {
 ///
 /// - At least one transaction ([`BundleRequest::push_transaction`])
 /// - A target block ([`BundleRequest::set_block`])
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[serde(default)]
 pub struct BundleRequest {
     #[serde(rename = "txs")]
-    #[serde(serialize_with = "serialize_txs")]
-    transactions: Vec<BundleTransaction>,
+    transactions: BundleTransactions,
     #[serde(rename = "revertingTxHashes")]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     revertible_transaction_hashes: Vec<H256>,
 
+    #[serde(rename = "droppingTxHashes")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    droppable_transaction_hashes: Vec<H256>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refund_percent: Option<u8>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refund_recipient: Option<Address>,
+
+    #[serde(rename = "refundTxHashes")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    refund_transaction_hashes: Vec<H256>,
+
     #[serde(rename = "blockNumber")]
     #[serde(skip_serializing_if = "Option::is_none")]
     target_block: Option<U64>,
@@ -68,11 +184,12 @@ pub struct BundleRequest {
     #[serde(rename = "replacementUuid")]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(serialize_with = "serialize_uuid_as_string")]
+    #[serde(deserialize_with = "deserialize_uuid_from_string")]
     uuid: Option<Uuid>,
 
     #[serde(rename = "stateBlockNumber")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    simulation_block: Option<U64>,
+    simulation_block: Option<BlockNumber>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "timestamp")]
@@ -81,9 +198,26 @@ pub struct BundleRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "baseFee")]
     simulation_basefee: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "timeout")]
+    simulation_timeout: Option<u64>,
+
+    #[serde(flatten)]
+    extra: BTreeMap<String, Value>,
+
+    #[serde(skip)]
+    builder_fields: BTreeMap<String, BTreeMap<String, Value>>,
+
+    #[serde(skip)]
+    tracking_uuid: Option<Uuid>,
+
+    /// The number of transactions before each split point, in order.
+    #[serde(skip)]
+    split_points: Vec<usize>,
 }
 
-fn serialize_uuid_as_string<S>(x: &Option<Uuid>, s: S) -> Result<S::Ok, S::Error>
+pub(crate) fn serialize_uuid_as_string<S>(x: &Option<Uuid>, s: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
@@ -92,19 +226,93 @@ where
     s.serialize_str(&x.unwrap().to_string())
 }
 
-pub fn serialize_txs<S>(txs: &[BundleTransaction], s: S) -> Result<S::Ok, S::Error>
+pub(crate) fn deserialize_uuid_from_string<'de, D>(d: D) -> Result<Option<Uuid>, D::Error>
 where
-    S: Serializer,
+    D: Deserializer<'de>,
 {
-    let raw_txs: Vec<Bytes> = txs
-        .iter()
-        .map(|tx| match tx {
-            BundleTransaction::Signed(inner) => inner.rlp(),
-            BundleTransaction::Raw(inner) => inner.clone(),
-        })
-        .collect();
+    Option::<String>::deserialize(d)?
+        .map(|uuid| Uuid::parse_str(&uuid).map_err(de::Error::custom))
+        .transpose()
+}
+
+/// The transactions of a bundle, along with their RLP encoding.
+///
+/// Transactions are encoded once when they are added to the bundle, so
+/// bundles can be serialized repeatedly (e.g. for simulation, broadcasts
+/// and resubmissions) without re-encoding every transaction.
+#[derive(Debug, Clone, Default)]
+struct BundleTransactions {
+    txs: Vec<BundleTransaction>,
+    encoded: Vec<Bytes>,
+}
+
+impl BundleTransactions {
+    fn encode(tx: &BundleTransaction) -> Bytes {
+        tx.rlp()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.txs.is_empty()
+    }
+
+    fn push(&mut self, tx: BundleTransaction) -> TxHash {
+        let encoded = Self::encode(&tx);
+        let tx_hash = keccak256(&encoded).into();
+        self.txs.push(tx);
+        self.encoded.push(encoded);
+        tx_hash
+    }
+
+    fn insert(&mut self, index: usize, tx: BundleTransaction) {
+        self.encoded.insert(index, Self::encode(&tx));
+        self.txs.insert(index, tx);
+    }
+
+    fn remove(&mut self, index: usize) -> (BundleTransaction, TxHash) {
+        let encoded = self.encoded.remove(index);
+        (self.txs.remove(index), keccak256(encoded).into())
+    }
+
+    fn replace(&mut self, index: usize, tx: BundleTransaction) -> (BundleTransaction, TxHash) {
+        let encoded = std::mem::replace(&mut self.encoded[index], Self::encode(&tx));
+        (
+            std::mem::replace(&mut self.txs[index], tx),
+            keccak256(encoded).into(),
+        )
+    }
+
+    fn hash(&self, index: usize) -> TxHash {
+        keccak256(&self.encoded[index]).into()
+    }
+
+    fn hashes(&self) -> Vec<TxHash> {
+        self.encoded
+            .iter()
+            .map(|encoded| keccak256(encoded).into())
+            .collect()
+    }
+}
+
+impl Serialize for BundleTransactions {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.encoded.serialize(s)
+    }
+}
 
-    raw_txs.serialize(s)
+impl<'de> Deserialize<'de> for BundleTransactions {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        // Signed transactions are serialized as RLP, so all transactions
+        // are deserialized as raw transactions.
+        let encoded = Vec::<Bytes>::deserialize(d)?;
+        Ok(Self {
+            txs: encoded
+                .iter()
+                .cloned()
+                .map(BundleTransaction::Raw)
+                .collect(),
+            encoded,
+        })
+    }
 }
 
 impl BundleRequest {
@@ -113,6 +321,23 @@ impl BundleRequest {
         Default::default()
     }
 
+    /// Creates a bundle request that cancels a previously submitted bundle.
+    ///
+    /// The cancellation has no transactions and the replacement uuid of
+    /// the bundle to cancel. As with other bundles, a target block must be
+    /// set using [`BundleRequest::set_block`] before it can be sent.
+    ///
+    /// See [`FlashbotsMiddleware::send_cancellation`](crate::FlashbotsMiddleware::send_cancellation).
+    pub fn cancellation(uuid: Uuid) -> Self {
+        Self::new().set_uuid(uuid)
+    }
+
+    /// Whether this bundle request is a cancellation, i.e. it has a
+    /// replacement uuid and no transactions.
+    pub fn is_cancellation(&self) -> bool {
+        self.uuid.is_some() && self.transactions.is_empty()
+    }
+
     /// Adds a transaction to the bundle request.
     ///
     /// Transactions added to the bundle can either be novel transactions,
@@ -133,18 +358,33 @@ impl BundleRequest {
         self.transactions.push(tx.into());
     }
 
+    /// Signs a typed transaction with the given signature and adds it to
+    /// the bundle request.
+    ///
+    /// See [`BundleRequest::add_typed_transaction`] to also get the hash of
+    /// the transaction.
+    pub fn push_typed_transaction(mut self, tx: &TypedTransaction, signature: &Signature) -> Self {
+        self.add_typed_transaction(tx, signature);
+        self
+    }
+
+    /// Signs a typed transaction with the given signature and adds it to
+    /// the bundle request, returning the hash of the signed transaction.
+    pub fn add_typed_transaction(
+        &mut self,
+        tx: &TypedTransaction,
+        signature: &Signature,
+    ) -> TxHash {
+        self.transactions
+            .push(BundleTransaction::Raw(tx.rlp_signed(signature)))
+    }
+
     /// Adds a revertible transaction to the bundle request.
     ///
     /// This differs from [`BundleRequest::push_transaction`] in that the bundle will still be
     /// considered valid if the transaction reverts.
     pub fn push_revertible_transaction<T: Into<BundleTransaction>>(mut self, tx: T) -> Self {
-        let tx = tx.into();
-        self.transactions.push(tx.clone());
-
-        let tx_hash: H256 = match tx {
-            BundleTransaction::Signed(inner) => inner.hash(),
-            BundleTransaction::Raw(inner) => keccak256(inner).into(),
-        };
+        let tx_hash = self.transactions.push(tx.into());
         self.revertible_transaction_hashes.push(tx_hash);
 
         self
@@ -158,30 +398,302 @@ impl BundleRequest {
     /// mempool APIs. Unlike the `push_transaction` method, the bundle will still be considered
     /// valid even if the added transaction reverts.
     pub fn add_revertible_transaction<T: Into<BundleTransaction>>(&mut self, tx: T) {
-        let tx = tx.into();
-        self.transactions.push(tx.clone());
-
-        let tx_hash: H256 = match tx {
-            BundleTransaction::Signed(inner) => inner.hash(),
-            BundleTransaction::Raw(inner) => keccak256(inner).into(),
-        };
+        let tx_hash = self.transactions.push(tx.into());
         self.revertible_transaction_hashes.push(tx_hash);
     }
 
+    /// Adds a droppable transaction to the bundle request.
+    ///
+    /// This differs from [`BundleRequest::push_transaction`] in that the transaction
+    /// may be left out of the bundle if it is invalid, e.g. because it was already
+    /// included, instead of the whole bundle being dropped. This is useful for
+    /// opportunistically including transactions from the mempool.
+    ///
+    /// Note that this is not supported by all builders.
+    pub fn push_droppable_transaction<T: Into<BundleTransaction>>(mut self, tx: T) -> Self {
+        self.add_droppable_transaction(tx);
+        self
+    }
+
+    /// Adds a droppable transaction to the bundle request.
+    ///
+    /// This function takes a mutable reference to `self`, see
+    /// [`BundleRequest::push_droppable_transaction`].
+    pub fn add_droppable_transaction<T: Into<BundleTransaction>>(&mut self, tx: T) {
+        let tx_hash = self.transactions.push(tx.into());
+        self.droppable_transaction_hashes.push(tx_hash);
+    }
+
+    /// Adds a transaction to the bundle request with the given options.
+    ///
+    /// This is a more general version of [`BundleRequest::push_transaction`]
+    /// and [`BundleRequest::push_revertible_transaction`] that allows
+    /// specifying all per-transaction options at once.
+    ///
+    /// Returns an error if the refund percentage of the options is above
+    /// 100, or differs from the refund percentage of the bundle. The
+    /// transaction is not added in that case.
+    pub fn push_transaction_with_options<T: Into<BundleTransaction>>(
+        mut self,
+        tx: T,
+        options: TxOptions,
+    ) -> Result<Self, TxOptionsError> {
+        self.add_transaction_with_options(tx, options)?;
+        Ok(self)
+    }
+
+    /// Adds a transaction to the bundle request with the given options.
+    ///
+    /// This function takes a mutable reference to `self`, see
+    /// [`BundleRequest::push_transaction_with_options`].
+    pub fn add_transaction_with_options<T: Into<BundleTransaction>>(
+        &mut self,
+        tx: T,
+        options: TxOptions,
+    ) -> Result<(), TxOptionsError> {
+        if let Some(requested) = options.refund_percent {
+            if requested > 100 {
+                return Err(TxOptionsError::InvalidRefundPercent(requested));
+            }
+            match self.refund_percent {
+                Some(current) if current != requested => {
+                    return Err(TxOptionsError::RefundPercentConflict { current, requested })
+                }
+                _ => {}
+            }
+        }
+
+        let tx_hash = self.transactions.push(tx.into());
+
+        if options.revertible {
+            self.revertible_transaction_hashes.push(tx_hash);
+        }
+        if options.can_drop {
+            self.droppable_transaction_hashes.push(tx_hash);
+        }
+        if let Some(percent) = options.refund_percent {
+            self.refund_percent = Some(percent);
+            self.refund_transaction_hashes.push(tx_hash);
+        }
+
+        Ok(())
+    }
+
+    /// Inserts a transaction into the bundle request at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of transactions.
+    pub fn insert_transaction<T: Into<BundleTransaction>>(&mut self, index: usize, tx: T) {
+        self.transactions.insert(index, tx.into());
+        for point in self.split_points.iter_mut().filter(|point| **point > index) {
+            *point += 1;
+        }
+    }
+
+    /// Removes the transaction at the given index from the bundle request,
+    /// returning it.
+    ///
+    /// The hash of the transaction is also removed from the reverting,
+    /// dropping and refund transaction hashes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_transaction(&mut self, index: usize) -> BundleTransaction {
+        let (tx, tx_hash) = self.transactions.remove(index);
+        self.forget_transaction_hash(tx_hash);
+        for point in self.split_points.iter_mut().filter(|point| **point > index) {
+            *point -= 1;
+        }
+        self.split_points.dedup();
+        tx
+    }
+
+    /// Replaces the transaction at the given index in the bundle request,
+    /// returning the old transaction.
+    ///
+    /// If the old transaction was revertible, droppable or subject to the
+    /// refund, the new transaction takes its place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn replace_transaction<T: Into<BundleTransaction>>(
+        &mut self,
+        index: usize,
+        tx: T,
+    ) -> BundleTransaction {
+        let (old_tx, old_tx_hash) = self.transactions.replace(index, tx.into());
+        let tx_hash = self.transactions.hash(index);
+
+        for hashes in self.transaction_hash_lists() {
+            if hashes.contains(&old_tx_hash) && !hashes.contains(&tx_hash) {
+                hashes.push(tx_hash);
+            }
+        }
+        self.forget_transaction_hash(old_tx_hash);
+
+        old_tx
+    }
+
+    /// Removes a transaction hash from the reverting, dropping and refund
+    /// transaction hashes, unless the transaction is still in the bundle.
+    fn forget_transaction_hash(&mut self, tx_hash: H256) {
+        if self.transactions.hashes().contains(&tx_hash) {
+            return;
+        }
+
+        for hashes in self.transaction_hash_lists() {
+            hashes.retain(|hash| *hash != tx_hash);
+        }
+    }
+
+    fn transaction_hash_lists(&mut self) -> [&mut Vec<H256>; 3] {
+        [
+            &mut self.revertible_transaction_hashes,
+            &mut self.droppable_transaction_hashes,
+            &mut self.refund_transaction_hashes,
+        ]
+    }
+
     /// Get a reference to the transactions currently in the bundle request.
     pub fn transactions(&self) -> &Vec<BundleTransaction> {
-        &self.transactions
+        &self.transactions.txs
+    }
+
+    /// Get a reference to the hashes of the transactions that are allowed
+    /// to revert.
+    pub fn revertible_transaction_hashes(&self) -> &Vec<H256> {
+        &self.revertible_transaction_hashes
+    }
+
+    /// Set the hashes of the transactions that are allowed to revert.
+    ///
+    /// This replaces any hashes previously added using
+    /// [`BundleRequest::push_revertible_transaction`] or
+    /// [`BundleRequest::add_revertible_transaction`], and is useful if
+    /// the transactions were added without marking them as revertible,
+    /// e.g. transactions from the mempool.
+    pub fn set_revertible_transaction_hashes(mut self, hashes: Vec<H256>) -> Self {
+        self.revertible_transaction_hashes = hashes;
+        self
+    }
+
+    /// Get a reference to the hashes of the transactions that may be dropped
+    /// from the bundle.
+    pub fn droppable_transaction_hashes(&self) -> &Vec<H256> {
+        &self.droppable_transaction_hashes
+    }
+
+    /// Set the hashes of the transactions that may be dropped from the bundle.
+    ///
+    /// This replaces any hashes previously added using
+    /// [`BundleRequest::push_droppable_transaction`] or transaction options.
+    pub fn set_droppable_transaction_hashes(mut self, hashes: Vec<H256>) -> Self {
+        self.droppable_transaction_hashes = hashes;
+        self
     }
 
     /// Get a list of transaction hashes in the bundle request.
     pub fn transaction_hashes(&self) -> Vec<TxHash> {
-        self.transactions
-            .iter()
-            .map(|tx| match tx {
-                BundleTransaction::Signed(inner) => keccak256(inner.rlp()).into(),
-                BundleTransaction::Raw(inner) => keccak256(inner).into(),
-            })
-            .collect()
+        self.transactions.hashes()
+    }
+
+    /// Marks a split point after the transactions added so far.
+    ///
+    /// Split points delimit the groups of transactions that must stay
+    /// together when the bundle is split using [`BundleRequest::split`].
+    pub fn push_split_point(mut self) -> Self {
+        self.add_split_point();
+        self
+    }
+
+    /// Marks a split point after the transactions added so far.
+    ///
+    /// This function takes a mutable reference to `self`, see
+    /// [`BundleRequest::push_split_point`].
+    pub fn add_split_point(&mut self) {
+        let len = self.transactions.txs.len();
+        if len > 0 && self.split_points.last() != Some(&len) {
+            self.split_points.push(len);
+        }
+    }
+
+    /// Get the split points of the bundle request, as the number of
+    /// transactions before each split point.
+    pub fn split_points(&self) -> &[usize] {
+        &self.split_points
+    }
+
+    /// Set the split points of the bundle request, e.g. when restoring a
+    /// bundle from a record.
+    ///
+    /// Split points that are not within the transactions of the bundle are
+    /// ignored.
+    pub(crate) fn set_split_points(mut self, mut split_points: Vec<usize>) -> Self {
+        let len = self.transactions.txs.len();
+        split_points.retain(|point| *point > 0 && *point <= len);
+        split_points.sort_unstable();
+        split_points.dedup();
+        self.split_points = split_points;
+        self
+    }
+
+    /// Splits the bundle request into bundles of at most the given number
+    /// of transactions, targeting the same block.
+    ///
+    /// The bundle is only split at split points (see
+    /// [`BundleRequest::push_split_point`]), and as few bundles as possible
+    /// are created while keeping the transactions in order. Each bundle
+    /// keeps the other parameters of the bundle request, except for the
+    /// replacement uuid, so the bundles do not replace each other.
+    ///
+    /// Note that the bundles are independent, so builders may include
+    /// any of them, in any order.
+    ///
+    /// Returns `None` if the transactions between two split points do not
+    /// fit in a single bundle.
+    pub fn split(&self, max_transactions: usize) -> Option<Vec<BundleRequest>> {
+        let len = self.transactions.txs.len();
+        if len <= max_transactions {
+            let mut bundle = self.clone();
+            bundle.split_points.clear();
+            return Some(vec![bundle]);
+        }
+
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        let mut end = 0;
+        for point in self.split_points.iter().copied().chain([len]) {
+            if point - end > max_transactions {
+                return None;
+            }
+            if point - start > max_transactions {
+                chunks.push(self.chunk(start..end));
+                start = end;
+            }
+            end = point;
+        }
+        chunks.push(self.chunk(start..len));
+
+        Some(chunks)
+    }
+
+    /// Creates a bundle request with the given range of transactions.
+    fn chunk(&self, range: std::ops::Range<usize>) -> BundleRequest {
+        let mut bundle = self.clone();
+        bundle.transactions = BundleTransactions {
+            txs: self.transactions.txs[range.clone()].to_vec(),
+            encoded: self.transactions.encoded[range].to_vec(),
+        };
+        let hashes = bundle.transactions.hashes();
+        for list in bundle.transaction_hash_lists() {
+            list.retain(|hash| hashes.contains(hash));
+        }
+        bundle.split_points.clear();
+        bundle.clear_uuid();
+        bundle
     }
 
     /// Get a reference to the replacement uuid (if any).
@@ -196,6 +708,121 @@ impl BundleRequest {
         self
     }
 
+    /// Whether any of the refund fields are set.
+    pub(crate) fn has_refund(&self) -> bool {
+        self.refund_percent.is_some()
+            || self.refund_recipient.is_some()
+            || !self.refund_transaction_hashes.is_empty()
+    }
+
+    /// Removes the refund fields from the bundle request.
+    pub(crate) fn clear_refund(&mut self) {
+        self.refund_percent = None;
+        self.refund_recipient = None;
+        self.refund_transaction_hashes.clear();
+    }
+
+    /// Removes the replacement uuid from the bundle request.
+    pub(crate) fn clear_uuid(&mut self) {
+        self.uuid = None;
+    }
+
+    /// Get a reference to the extra fields of the bundle request.
+    pub fn extra_fields(&self) -> &BTreeMap<String, Value> {
+        &self.extra
+    }
+
+    /// Set an extra field on the bundle request.
+    ///
+    /// Extra fields are added to the bundle request as-is when it is
+    /// serialized, which allows passing builder-specific fields that are
+    /// not otherwise supported. Extra fields should not have the same name
+    /// as any of the other fields of the bundle request.
+    pub fn set_extra_field(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.extra.insert(key.into(), value.into());
+        self
+    }
+
+    /// Get a reference to the builder-specific fields of the bundle
+    /// request for the given field profile (if any).
+    pub fn builder_fields(&self, profile: &str) -> Option<&BTreeMap<String, Value>> {
+        self.builder_fields.get(profile)
+    }
+
+    /// Get the builder-specific fields of the bundle request for all field
+    /// profiles.
+    pub(crate) fn all_builder_fields(&self) -> &BTreeMap<String, BTreeMap<String, Value>> {
+        &self.builder_fields
+    }
+
+    /// Set the builder-specific fields of the bundle request for all field
+    /// profiles, replacing any existing builder fields.
+    pub(crate) fn set_all_builder_fields(
+        mut self,
+        builder_fields: BTreeMap<String, BTreeMap<String, Value>>,
+    ) -> Self {
+        self.builder_fields = builder_fields;
+        self
+    }
+
+    /// Set a builder-specific field on the bundle request.
+    ///
+    /// Unlike [extra fields](BundleRequest::set_extra_field), builder
+    /// fields are only sent to relays with the given field profile (see
+    /// [`Relay::set_field_profile`](crate::Relay::set_field_profile)),
+    /// e.g. to use extensions of the bundle API that only one builder
+    /// supports when broadcasting a bundle.
+    ///
+    /// ```
+    /// # use ethers_flashbots::BundleRequest;
+    /// // Only refund the payment of the first transaction on Titan
+    /// let bundle = BundleRequest::new().set_builder_field("titan", "refundIndex", 0);
+    /// ```
+    pub fn set_builder_field(
+        mut self,
+        profile: impl Into<String>,
+        key: impl Into<String>,
+        value: impl Into<Value>,
+    ) -> Self {
+        self.builder_fields
+            .entry(profile.into())
+            .or_default()
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Get the bundle request as sent to a relay with the given field
+    /// profile, i.e. with the builder fields of the profile added as
+    /// extra fields.
+    pub(crate) fn for_profile(&self, profile: Option<&str>) -> Cow<'_, BundleRequest> {
+        match profile.and_then(|profile| self.builder_fields.get(profile)) {
+            Some(fields) => {
+                let mut bundle = self.clone();
+                bundle
+                    .extra
+                    .extend(fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+                Cow::Owned(bundle)
+            }
+            None => Cow::Borrowed(self),
+        }
+    }
+
+    /// Get the client-side tracking uuid of the bundle (if any).
+    pub fn tracking_uuid(&self) -> Option<Uuid> {
+        self.tracking_uuid
+    }
+
+    /// Set a client-side tracking uuid for the bundle.
+    ///
+    /// Unlike the replacement uuid, the tracking uuid is never sent to
+    /// relays. It is carried over to the [`PendingBundle`](crate::PendingBundle)s
+    /// and stats of the bundle, so a bundle can be correlated across
+    /// relays, retries and target blocks.
+    pub fn set_tracking_uuid(mut self, uuid: Uuid) -> Self {
+        self.tracking_uuid = Some(uuid);
+        self
+    }
+
     /// Get the target block (if any).
     pub fn block(&self) -> Option<U64> {
         self.target_block
@@ -207,19 +834,80 @@ impl BundleRequest {
         self
     }
 
+    /// Returns a copy of the bundle targeting the given block.
+    ///
+    /// If the bundle already has a target block, the simulation block and
+    /// simulation timestamp (if set) are shifted by the same number of
+    /// blocks, assuming a block time of 12 seconds, so they stay consistent
+    /// with the new target. Otherwise only the target block is set.
+    pub fn clone_for_block(&self, block: U64) -> Self {
+        let mut bundle = self.clone();
+        bundle.target_block = Some(block);
+
+        if let Some(target) = self.target_block {
+            let shift = |value: u64, blocks: u64| {
+                if block >= target {
+                    value.saturating_add(blocks * (block - target).as_u64())
+                } else {
+                    value.saturating_sub(blocks * (target - block).as_u64())
+                }
+            };
+
+            // Block tags such as `latest` are relative to the chain already
+            if let Some(BlockNumber::Number(simulation_block)) = self.simulation_block {
+                bundle.simulation_block = Some(BlockNumber::Number(
+                    shift(simulation_block.as_u64(), 1).into(),
+                ));
+            }
+            bundle.simulation_timestamp = self
+                .simulation_timestamp
+                .map(|timestamp| shift(timestamp, SLOT_DURATION.as_secs()));
+        }
+
+        bundle
+    }
+
+    /// Re-target the bundle to the block after its current target block.
+    ///
+    /// See [`BundleRequest::clone_for_block`]. If the bundle has no target
+    /// block, it is returned unchanged.
+    pub fn bump_block(self) -> Self {
+        match self.target_block {
+            Some(block) => self.clone_for_block(block + 1),
+            None => self,
+        }
+    }
+
     /// Get the block that determines the state for bundle simulation (if any).
     ///
     /// See [`eth_callBundle`][fb_call_bundle] in the Flashbots documentation
     /// for more information on bundle simulations.
     ///
     /// [fb_call_bundle]: https://docs.flashbots.net/flashbots-auction/searchers/advanced/rpc-endpoint#eth_callbundle
+    ///
+    /// Returns `None` if the simulation state is set to a block tag, see
+    /// [`BundleRequest::simulation_state`].
     pub fn simulation_block(&self) -> Option<U64> {
-        self.simulation_block
+        self.simulation_block.and_then(|block| block.as_number())
     }
 
     /// Set the block that determines the state for bundle simulation.
     pub fn set_simulation_block(mut self, block: U64) -> Self {
-        self.simulation_block = Some(block);
+        self.simulation_block = Some(BlockNumber::Number(block));
+        self
+    }
+
+    /// Get the block number or tag that determines the state for bundle
+    /// simulation (if any).
+    pub fn simulation_state(&self) -> Option<BlockNumber> {
+        self.simulation_block
+    }
+
+    /// Set the block number or tag that determines the state for bundle
+    /// simulation, e.g. [`BlockNumber::Pending`] to simulate against the
+    /// freshest state of the node.
+    pub fn set_simulation_state(mut self, block: impl Into<BlockNumber>) -> Self {
+        self.simulation_block = Some(block.into());
         self
     }
 
@@ -256,6 +944,153 @@ impl BundleRequest {
         self
     }
 
+    /// Set the base gas fee for bundle simulation from the latest block
+    /// known to a provider.
+    ///
+    /// The base fee of the block after the latest block is predicted using
+    /// [`next_block_basefee`](crate::next_block_basefee). If the bundle
+    /// targets a later block, the prediction is escalated for every block
+    /// in between using the given [`BasefeeEscalation`].
+    pub async fn set_simulation_basefee_from_provider<M: Middleware>(
+        self,
+        provider: &M,
+        escalation: &BasefeeEscalation,
+    ) -> Result<Self, SimulationBasefeeError>
+    where
+        M::Error: 'static,
+    {
+        let parent = provider
+            .get_block(BlockNumber::Latest)
+            .await
+            .map_err(|err| SimulationBasefeeError::ProviderError(Box::new(err)))?
+            .ok_or(SimulationBasefeeError::MissingBlock)?;
+        let basefee = parent
+            .base_fee_per_gas
+            .ok_or(SimulationBasefeeError::MissingBasefee)?;
+        let basefee = next_block_basefee(parent.gas_used, parent.gas_limit, basefee);
+
+        let next_block = parent.number.unwrap_or_default() + 1;
+        let blocks_ahead = self
+            .block()
+            .map_or(0, |block| block.saturating_sub(next_block).as_u64());
+        let basefee = escalation.escalate(basefee, blocks_ahead);
+
+        let basefee =
+            u64::try_from(basefee).map_err(|_| SimulationBasefeeError::BasefeeOverflow(basefee))?;
+        Ok(self.set_simulation_basefee(basefee))
+    }
+
+    /// Set the base gas fee for bundle simulation from a gas oracle, such
+    /// as an ethers [`ProviderOracle`](ethers::middleware::gas_oracle::ProviderOracle).
+    ///
+    /// The gas price reported by the oracle is used as the base fee. This
+    /// is a conservative estimate, since the gas price of the oracle
+    /// includes a priority fee on top of the base fee of the next block.
+    pub async fn set_simulation_basefee_from_oracle<G: GasOracle + ?Sized>(
+        self,
+        oracle: &G,
+    ) -> Result<Self, SimulationBasefeeError> {
+        let basefee = oracle.fetch().await?;
+
+        let basefee =
+            u64::try_from(basefee).map_err(|_| SimulationBasefeeError::BasefeeOverflow(basefee))?;
+        Ok(self.set_simulation_basefee(basefee))
+    }
+
+    /// Set the base gas fee for bundle simulation from a fee history.
+    ///
+    /// The fee history returned by `eth_feeHistory` includes the base fee
+    /// of the block after the newest block in the history, which is the
+    /// base fee used here. If the history is empty, the bundle is returned
+    /// unchanged.
+    pub fn set_simulation_basefee_from_fee_history(self, history: &FeeHistory) -> Self {
+        match history.base_fee_per_gas.last() {
+            Some(basefee) => {
+                self.set_simulation_basefee(u64::try_from(*basefee).unwrap_or(u64::MAX))
+            }
+            None => self,
+        }
+    }
+
+    /// Set the base gas fee for bundle simulation to the base fee of the
+    /// block after the given parent block.
+    ///
+    /// See [`next_block_basefee`](crate::next_block_basefee). If the
+    /// parent block has no base fee, the bundle is returned unchanged.
+    pub fn set_simulation_basefee_from_parent<TX>(self, parent: &Block<TX>) -> Self {
+        match parent.base_fee_per_gas {
+            Some(basefee) => {
+                let basefee = next_block_basefee(parent.gas_used, parent.gas_limit, basefee);
+                self.set_simulation_basefee(u64::try_from(basefee).unwrap_or(u64::MAX))
+            }
+            None => self,
+        }
+    }
+
+    /// Get the maximum duration of bundle simulation (if any).
+    pub fn simulation_timeout(&self) -> Option<Duration> {
+        self.simulation_timeout.map(Duration::from_millis)
+    }
+
+    /// Set the maximum duration of bundle simulation, e.g. to bound long
+    /// simulations against archive state.
+    /// Optional: will default to a value chosen by the node if not specified.
+    ///
+    /// The timeout is sent with millisecond precision.
+    pub fn set_simulation_timeout(mut self, timeout: Duration) -> Self {
+        self.simulation_timeout = Some(u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX));
+        self
+    }
+
+    /// Get the percentage of the bundle's payment that should be refunded (if any).
+    pub fn refund_percent(&self) -> Option<u8> {
+        self.refund_percent
+    }
+
+    /// Set the percentage of the bundle's payment that should be refunded.
+    ///
+    /// Builders that support refunds rebate this percentage of the payment
+    /// to the refund recipient. Note that this is not supported by all builders.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `percent` is above 100.
+    pub fn set_refund_percent(mut self, percent: u8) -> Self {
+        assert!(percent <= 100, "refund percentage must be at most 100");
+        self.refund_percent = Some(percent);
+        self
+    }
+
+    /// Get a reference to the hashes of the transactions whose payments are
+    /// subject to the refund.
+    pub fn refund_transaction_hashes(&self) -> &Vec<H256> {
+        &self.refund_transaction_hashes
+    }
+
+    /// Set the hashes of the transactions whose payments are subject to the
+    /// refund.
+    ///
+    /// This replaces any hashes previously added using transaction options.
+    /// Note that this is not supported by all builders.
+    pub fn set_refund_transaction_hashes(mut self, hashes: Vec<H256>) -> Self {
+        self.refund_transaction_hashes = hashes;
+        self
+    }
+
+    /// Get the recipient of the bundle's refund (if any).
+    pub fn refund_recipient(&self) -> Option<Address> {
+        self.refund_recipient
+    }
+
+    /// Set the recipient of the bundle's refund.
+    ///
+    /// If this is not set, builders default to the sender of the first
+    /// transaction in the bundle.
+    pub fn set_refund_recipient(mut self, recipient: Address) -> Self {
+        self.refund_recipient = Some(recipient);
+        self
+    }
+
     /// Get the minimum timestamp for which this bundle is valid (if any),
     /// in seconds since the UNIX epoch.
     pub fn min_timestamp(&self) -> Option<u64> {
@@ -283,11 +1118,23 @@ impl BundleRequest {
     }
 }
 
-/// Details of a simulated transaction.
-///
+/// Converts a `U256` into an `I256`, saturating at `I256::MAX`.
+fn to_i256(value: U256) -> I256 {
+    I256::try_from(value).unwrap_or(I256::MAX)
+}
+
+/// The net payment to coinbase above gas fees, minus a cost basis.
+fn net_profit(coinbase_diff: U256, gas_fees: U256, cost_basis: U256) -> I256 {
+    to_i256(coinbase_diff)
+        .saturating_sub(to_i256(gas_fees))
+        .saturating_sub(to_i256(cost_basis))
+}
+
+/// Details of a simulated transaction.
+///
 /// Details for a transaction that has been simulated as part of
 /// a bundle.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulatedTransaction {
     /// The transaction hash
     #[serde(rename = "txHash")]
@@ -322,6 +1169,7 @@ pub struct SimulatedTransaction {
     /// If this is `None`, then the transaction was to a newly
     /// deployed contract.
     #[serde(rename = "toAddress")]
+    #[serde(serialize_with = "serialize_optional_h160")]
     #[serde(deserialize_with = "deserialize_optional_h160")]
     pub to: Option<Address>,
     /// The return value of the transaction.
@@ -335,15 +1183,64 @@ pub struct SimulatedTransaction {
 impl SimulatedTransaction {
     /// The effective gas price of the transaction,
     /// i.e. `coinbase_diff / gas_used`.
-    pub fn effective_gas_price(&self) -> U256 {
-        self.coinbase_diff / self.gas_used
+    ///
+    /// Returns `None` if the transaction used no gas.
+    pub fn effective_gas_price(&self) -> Option<U256> {
+        self.coinbase_diff.checked_div(self.gas_used)
+    }
+
+    /// The effective priority fee of the transaction given the base fee,
+    /// i.e. the part of the effective gas price above the base fee.
+    ///
+    /// Returns `None` if the transaction used no gas.
+    pub fn effective_priority_fee(&self, basefee: U256) -> Option<U256> {
+        self.effective_gas_price()
+            .map(|price| price.saturating_sub(basefee))
+    }
+
+    /// The profit of the transaction, i.e. the payment to coinbase on top
+    /// of gas fees, minus the given cost basis.
+    ///
+    /// The cost basis can be used to account for costs not visible in the
+    /// simulation, e.g. the cost of the inputs of an arbitrage. The result
+    /// is negative if the transaction is unprofitable.
+    pub fn profit(&self, cost_basis: U256) -> I256 {
+        net_profit(self.coinbase_diff, self.gas_fees, cost_basis)
+    }
+
+    /// Whether the transaction failed or reverted in simulation.
+    pub fn is_reverted(&self) -> bool {
+        self.error.is_some() || self.revert.is_some()
+    }
+
+    /// Convert the simulated transaction into a transaction receipt.
+    ///
+    /// Simulations do not include logs, and the transaction was not
+    /// included in a block, so the logs and block fields of the receipt
+    /// are empty. If the transaction created a contract, the contract
+    /// address is derived from the given nonce of the sender (if any).
+    pub fn to_receipt(&self, nonce: Option<U256>) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: self.hash,
+            from: self.from,
+            to: self.to,
+            cumulative_gas_used: self.gas_used,
+            gas_used: Some(self.gas_used),
+            contract_address: match (self.to, nonce) {
+                (None, Some(nonce)) => Some(get_contract_address(self.from, nonce)),
+                _ => None,
+            },
+            status: Some(if self.is_reverted() { 0 } else { 1 }.into()),
+            effective_gas_price: Some(self.gas_price),
+            ..Default::default()
+        }
     }
 }
 
 /// Details of a simulated bundle.
 ///
 /// The details of a bundle that has been simulated.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulatedBundle {
     /// The bundle's hash.
     #[serde(rename = "bundleHash")]
@@ -375,18 +1272,211 @@ pub struct SimulatedBundle {
     #[serde(deserialize_with = "deserialize_u64")]
     pub simulation_block: U64,
     /// The simulated transactions in this bundle.
+    ///
+    /// The simulated transactions are in the same order as the
+    /// transactions in the simulated [`BundleRequest`], i.e.
+    /// `transactions[i]` is the result of the `i`th transaction.
     #[serde(rename = "results")]
     pub transactions: Vec<SimulatedTransaction>,
 }
 
 impl SimulatedBundle {
-    /// The effective gas price of the transaction,
+    /// The effective gas price of the bundle,
     /// i.e. `coinbase_diff / gas_used`.
     ///
     /// Note that this is also an approximation of the
-    /// bundle's score.
-    pub fn effective_gas_price(&self) -> U256 {
-        self.coinbase_diff / self.gas_used
+    /// bundle's score. Returns `None` if the bundle used no gas.
+    pub fn effective_gas_price(&self) -> Option<U256> {
+        self.coinbase_diff.checked_div(self.gas_used)
+    }
+
+    /// The effective priority fee of the bundle given the base fee,
+    /// i.e. the part of the effective gas price above the base fee.
+    ///
+    /// Returns `None` if the bundle used no gas.
+    pub fn effective_priority_fee(&self, basefee: U256) -> Option<U256> {
+        self.effective_gas_price()
+            .map(|price| price.saturating_sub(basefee))
+    }
+
+    /// The profit of the bundle, i.e. the payment to coinbase on top of
+    /// gas fees, minus the given cost basis.
+    ///
+    /// See [`SimulatedTransaction::profit`].
+    pub fn profit(&self, cost_basis: U256) -> I256 {
+        net_profit(self.coinbase_diff, self.gas_fees, cost_basis)
+    }
+
+    /// Get the transactions that failed or reverted in simulation.
+    pub fn reverted_transactions(&self) -> Vec<&SimulatedTransaction> {
+        self.transactions
+            .iter()
+            .filter(|tx| tx.is_reverted())
+            .collect()
+    }
+
+    /// Whether any of the transactions failed or reverted in simulation.
+    pub fn has_errors(&self) -> bool {
+        self.transactions
+            .iter()
+            .any(SimulatedTransaction::is_reverted)
+    }
+
+    /// Get the first transaction that failed or reverted in simulation
+    /// (if any).
+    pub fn first_error(&self) -> Option<&SimulatedTransaction> {
+        self.transactions.iter().find(|tx| tx.is_reverted())
+    }
+
+    /// Whether the simulation of the given bundle succeeded, i.e. every
+    /// transaction that failed or reverted is allowed to revert (see
+    /// [`BundleRequest::revertible_transaction_hashes`]).
+    pub fn is_success_for(&self, bundle: &BundleRequest) -> bool {
+        self.transactions.iter().all(|tx| {
+            !tx.is_reverted() || bundle.revertible_transaction_hashes().contains(&tx.hash)
+        })
+    }
+
+    /// Convert the simulated transactions into transaction receipts.
+    ///
+    /// The transaction index and cumulative gas used of each receipt
+    /// reflect the position of the transaction in the bundle. See
+    /// [`SimulatedTransaction::to_receipt`].
+    pub fn to_receipts(&self) -> Vec<TransactionReceipt> {
+        let mut cumulative_gas_used = U256::zero();
+        self.transactions
+            .iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                cumulative_gas_used += tx.gas_used;
+                TransactionReceipt {
+                    transaction_index: (index as u64).into(),
+                    cumulative_gas_used,
+                    ..tx.to_receipt(None)
+                }
+            })
+            .collect()
+    }
+
+    /// Get the index of the transaction with the given hash in the bundle
+    /// (if any).
+    pub fn index_of(&self, tx_hash: &TxHash) -> Option<usize> {
+        self.transactions.iter().position(|tx| tx.hash == *tx_hash)
+    }
+
+    /// Get the simulation result of the transaction with the given hash
+    /// (if any).
+    pub fn result_for(&self, tx_hash: &TxHash) -> Option<&SimulatedTransaction> {
+        self.transactions.iter().find(|tx| tx.hash == *tx_hash)
+    }
+
+    /// Compare the gas used by each transaction in simulation with the
+    /// gas limit of the transaction in the given bundle.
+    ///
+    /// Gas limits that are much higher than the gas used lower the score
+    /// of the bundle with some builders. Transactions that cannot be
+    /// decoded, or are not part of the simulation, are skipped.
+    pub fn gas_report(&self, bundle: &BundleRequest) -> Vec<TransactionGasReport> {
+        bundle
+            .transactions()
+            .iter()
+            .filter_map(|tx| tx.decode().ok())
+            .filter_map(|tx| {
+                self.result_for(&tx.hash)
+                    .map(|result| TransactionGasReport {
+                        hash: tx.hash,
+                        gas_used: result.gas_used,
+                        gas_limit: tx.gas,
+                    })
+            })
+            .collect()
+    }
+}
+
+/// The gas used by a simulated transaction compared to its gas limit.
+///
+/// See [`SimulatedBundle::gas_report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionGasReport {
+    /// The transaction hash.
+    pub hash: TxHash,
+    /// The amount of gas used in simulation.
+    pub gas_used: U256,
+    /// The gas limit of the transaction.
+    pub gas_limit: U256,
+}
+
+impl TransactionGasReport {
+    /// The amount of gas that was not used.
+    pub fn unused_gas(&self) -> U256 {
+        self.gas_limit.saturating_sub(self.gas_used)
+    }
+
+    /// The fraction of the gas limit that was used.
+    ///
+    /// Returns `None` if the gas limit is zero.
+    pub fn utilization(&self) -> Option<f64> {
+        if self.gas_limit.is_zero() {
+            return None;
+        }
+        Some(gas_to_f64(self.gas_used) / gas_to_f64(self.gas_limit))
+    }
+
+    /// Whether the gas limit is more than the given factor times the gas
+    /// used, e.g. `1.5` for a margin of 50%.
+    pub fn is_over_provisioned(&self, factor: f64) -> bool {
+        gas_to_f64(self.gas_limit) > gas_to_f64(self.gas_used) * factor
+    }
+}
+
+/// Converts an amount of gas to a float, saturating amounts that do not fit
+/// in a `u64`.
+fn gas_to_f64(value: U256) -> f64 {
+    if value > U256::from(u64::MAX) {
+        return f64::MAX;
+    }
+    value.as_u64() as f64
+}
+
+impl fmt::Display for SimulatedBundle {
+    /// Renders the simulation as a table with a row per transaction.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Bundle {:?} (simulated at block {})",
+            self.hash, self.simulation_block
+        )?;
+        writeln!(
+            f,
+            "Gas used: {}, gas price: {}, coinbase diff: {}, coinbase tip: {}",
+            self.gas_used, self.gas_price, self.coinbase_diff, self.coinbase_tip
+        )?;
+        writeln!(
+            f,
+            "{:>3}  {:<66}  {:>10}  {:>24}  {:>24}  Status",
+            "#", "Transaction", "Gas used", "Coinbase diff", "Coinbase tip"
+        )?;
+
+        for (index, tx) in self.transactions.iter().enumerate() {
+            let status = match (&tx.error, &tx.revert) {
+                (Some(error), Some(revert)) => format!("error: {} ({})", error, revert),
+                (Some(error), None) => format!("error: {}", error),
+                (None, Some(revert)) => format!("reverted: {}", revert),
+                (None, None) => "ok".into(),
+            };
+            writeln!(
+                f,
+                "{:>3}  {:<66}  {:>10}  {:>24}  {:>24}  {}",
+                index,
+                format!("{:?}", tx.hash),
+                tx.gas_used.to_string(),
+                tx.coinbase_diff.to_string(),
+                tx.coinbase_tip.to_string(),
+                status
+            )?;
+        }
+
+        Ok(())
     }
 }
 
@@ -412,6 +1502,12 @@ pub struct BundleStats {
     /// A list of times at which builders sealed a block containing the bundle.
     #[serde(default = "Vec::new")]
     pub sealed_by_builders_at: Vec<BuilderEntry>,
+    /// The client-side tracking uuid of the bundle (if any).
+    ///
+    /// This is not returned by the relay, but is set when the stats are
+    /// fetched using [`FlashbotsMiddleware::get_pending_bundle_stats`](crate::FlashbotsMiddleware::get_pending_bundle_stats).
+    #[serde(skip)]
+    pub tracking_uuid: Option<Uuid>,
 }
 
 /// A builder log entry is a pairing of a builder's public key and a timestamp at which they
@@ -427,6 +1523,13 @@ pub struct BuilderEntry {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::{simulated_bundle, simulated_transaction, wallet, SIMULATED_BUNDLE};
+    use ethers::{
+        core::types::{Eip1559TransactionRequest, TransactionRequest},
+        middleware::gas_oracle::ProviderOracle,
+        providers::Provider,
+        signers::Signer,
+    };
     use std::str::FromStr;
     use uuid::uuid;
 
@@ -448,6 +1551,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn bundle_simulation_timeout() {
+        let bundle = BundleRequest::new()
+            .set_block(2.into())
+            .set_simulation_timeout(Duration::from_secs(30));
+        assert_eq!(bundle.simulation_timeout(), Some(Duration::from_secs(30)));
+        assert_eq!(
+            serde_json::to_value(&bundle).unwrap()["timeout"],
+            serde_json::json!(30000)
+        );
+    }
+
+    #[test]
+    fn bundle_simulation_state() {
+        let bundle = BundleRequest::new()
+            .set_block(2.into())
+            .set_simulation_state(BlockNumber::Pending);
+        assert_eq!(bundle.simulation_block(), None);
+        assert_eq!(bundle.simulation_state(), Some(BlockNumber::Pending));
+        assert_eq!(
+            serde_json::to_value(&bundle).unwrap()["stateBlockNumber"],
+            "pending"
+        );
+
+        // Block tags are kept when re-targeting the bundle
+        assert_eq!(
+            bundle.clone().bump_block().simulation_state(),
+            Some(BlockNumber::Pending)
+        );
+
+        let bundle = bundle.set_simulation_state(1u64);
+        assert_eq!(bundle.simulation_block(), Some(1.into()));
+        assert_eq!(
+            serde_json::to_value(&bundle).unwrap()["stateBlockNumber"],
+            "0x1"
+        );
+    }
+
+    #[test]
+    fn bundle_split() {
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .push_revertible_transaction(Bytes::from(vec![0x2]))
+            .push_split_point()
+            .push_transaction(Bytes::from(vec![0x3]))
+            .push_split_point()
+            .push_transaction(Bytes::from(vec![0x4]))
+            .push_revertible_transaction(Bytes::from(vec![0x5]))
+            .set_block(2.into())
+            .set_uuid(Uuid::nil());
+        assert_eq!(bundle.split_points(), &[2, 3]);
+
+        let chunks = bundle.split(3).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].transactions().len(), 3);
+        assert_eq!(chunks[1].transactions().len(), 2);
+        assert_eq!(
+            chunks[0].revertible_transaction_hashes(),
+            &vec![bundle.transaction_hashes()[1]]
+        );
+        assert_eq!(
+            chunks[1].revertible_transaction_hashes(),
+            &vec![bundle.transaction_hashes()[4]]
+        );
+        for chunk in &chunks {
+            assert_eq!(chunk.block(), Some(2.into()));
+            assert_eq!(chunk.uuid(), &None);
+        }
+
+        // Groups are never split
+        assert!(bundle.split(1).is_none());
+        assert_eq!(bundle.split(5).unwrap().len(), 1);
+
+        // Split points follow removed transactions
+        let mut bundle = bundle;
+        bundle.remove_transaction(0);
+        assert_eq!(bundle.split_points(), &[1, 2]);
+    }
+
     #[test]
     fn bundle_serialize_add_transactions() {
         let mut bundle = BundleRequest::new()
@@ -470,6 +1652,276 @@ mod tests {
         );
     }
 
+    #[test]
+    fn bundle_tracking_uuid_not_serialized() {
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(2.into());
+        let tracked = bundle
+            .clone()
+            .set_tracking_uuid(uuid!("2ab14fc2-8e5a-4b2e-9d4a-6a0f1e0c9d11"));
+
+        assert_eq!(
+            tracked.tracking_uuid(),
+            Some(uuid!("2ab14fc2-8e5a-4b2e-9d4a-6a0f1e0c9d11"))
+        );
+        assert_eq!(
+            serde_json::to_string(&tracked).unwrap(),
+            serde_json::to_string(&bundle).unwrap()
+        );
+    }
+
+    #[test]
+    fn bundle_set_revertible_transaction_hashes() {
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .push_revertible_transaction(Bytes::from(vec![0x2]))
+            .set_revertible_transaction_hashes(vec![keccak256([0x1]).into()]);
+
+        assert_eq!(
+            bundle.revertible_transaction_hashes(),
+            &vec![H256::from(keccak256([0x1]))]
+        );
+    }
+
+    #[test]
+    fn bundle_serialize_transaction_options() {
+        let bundle = BundleRequest::new()
+            .push_transaction_with_options(
+                Bytes::from(vec![0x1]),
+                TxOptions {
+                    revertible: true,
+                    can_drop: true,
+                    refund_percent: None,
+                },
+            )
+            .unwrap()
+            .push_transaction_with_options(
+                Bytes::from(vec![0x2]),
+                TxOptions {
+                    refund_percent: Some(90),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .set_block(2.into());
+
+        assert_eq!(
+            &serde_json::to_string(&bundle).unwrap(),
+            r#"{"txs":["0x01","0x02"],"revertingTxHashes":["0x5fe7f977e71dba2ea1a68e21057beebb9be2ac30c6410aa38d4f3fbe41dcffd2"],"droppingTxHashes":["0x5fe7f977e71dba2ea1a68e21057beebb9be2ac30c6410aa38d4f3fbe41dcffd2"],"refundPercent":90,"refundTxHashes":["0xf2ee15ea639b73fa3db9b34a245bdfa015c260c598b211bf05a1ecc4b3e3b4f2"],"blockNumber":"0x2"}"#
+        );
+    }
+
+    #[test]
+    fn bundle_transaction_options_refund_percent() {
+        let options = |percent| TxOptions {
+            refund_percent: Some(percent),
+            ..Default::default()
+        };
+        let mut bundle = BundleRequest::new();
+
+        assert_eq!(
+            bundle.add_transaction_with_options(Bytes::from(vec![0x1]), options(101)),
+            Err(TxOptionsError::InvalidRefundPercent(101))
+        );
+        bundle
+            .add_transaction_with_options(Bytes::from(vec![0x1]), options(90))
+            .unwrap();
+        bundle
+            .add_transaction_with_options(Bytes::from(vec![0x2]), options(90))
+            .unwrap();
+        assert_eq!(
+            bundle.add_transaction_with_options(Bytes::from(vec![0x3]), options(50)),
+            Err(TxOptionsError::RefundPercentConflict {
+                current: 90,
+                requested: 50
+            })
+        );
+
+        assert_eq!(bundle.transactions().len(), 2);
+        assert_eq!(bundle.refund_transaction_hashes().len(), 2);
+    }
+
+    #[test]
+    fn bundle_serialize_droppable_transactions() {
+        let mut bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .push_droppable_transaction(Bytes::from(vec![0x2]))
+            .set_block(2.into());
+        bundle.add_droppable_transaction(Bytes::from(vec![0x3]));
+
+        assert_eq!(bundle.droppable_transaction_hashes().len(), 2);
+        assert_eq!(
+            &serde_json::to_string(&bundle).unwrap(),
+            r#"{"txs":["0x01","0x02","0x03"],"droppingTxHashes":["0xf2ee15ea639b73fa3db9b34a245bdfa015c260c598b211bf05a1ecc4b3e3b4f2","0x69c322e3248a5dfc29d73c5b0553b0185a35cd5bb6386747517ef7e53b15e287"],"blockNumber":"0x2"}"#
+        );
+    }
+
+    #[test]
+    fn bundle_serialize_refund() {
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(2.into())
+            .set_refund_percent(50)
+            .set_refund_transaction_hashes(vec![keccak256([0x1]).into()])
+            .set_refund_recipient(
+                Address::from_str("0x02A727155aeF8609c9f7F2179b2a1f560B39F5A0").unwrap(),
+            );
+
+        assert_eq!(
+            &serde_json::to_string(&bundle).unwrap(),
+            r#"{"txs":["0x01"],"refundPercent":50,"refundRecipient":"0x02a727155aef8609c9f7f2179b2a1f560b39f5a0","refundTxHashes":["0x5fe7f977e71dba2ea1a68e21057beebb9be2ac30c6410aa38d4f3fbe41dcffd2"],"blockNumber":"0x2"}"#
+        );
+    }
+
+    #[test]
+    fn bundle_serialize_extra_fields() {
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(2.into())
+            .set_extra_field("builders", vec!["flashbots", "beaverbuild.org"])
+            .set_extra_field("minBlockNumber", "0x2");
+
+        assert_eq!(
+            bundle.extra_fields().get("minBlockNumber"),
+            Some(&Value::from("0x2"))
+        );
+
+        let serialized = serde_json::to_string(&bundle).unwrap();
+        assert_eq!(
+            &serialized,
+            r#"{"txs":["0x01"],"blockNumber":"0x2","builders":["flashbots","beaverbuild.org"],"minBlockNumber":"0x2"}"#
+        );
+
+        let deserialized: BundleRequest = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.extra_fields(), bundle.extra_fields());
+        assert_eq!(deserialized.block(), Some(2.into()));
+    }
+
+    #[test]
+    fn bundle_builder_fields() {
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(2.into())
+            .set_builder_field("titan", "refundIndex", 0);
+
+        assert_eq!(
+            &serde_json::to_string(&bundle).unwrap(),
+            r#"{"txs":["0x01"],"blockNumber":"0x2"}"#
+        );
+        assert!(matches!(bundle.for_profile(None), Cow::Borrowed(_)));
+        assert!(matches!(
+            bundle.for_profile(Some("rsync")),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(
+            &serde_json::to_string(&bundle.for_profile(Some("titan"))).unwrap(),
+            r#"{"txs":["0x01"],"blockNumber":"0x2","refundIndex":0}"#
+        );
+    }
+
+    #[test]
+    fn bundle_serialize_cancellation() {
+        let bundle = BundleRequest::cancellation(uuid!("67e55044-10b1-426f-9247-bb680e5fe0c8"))
+            .set_block(2.into());
+
+        assert!(bundle.is_cancellation());
+        assert_eq!(
+            &serde_json::to_string(&bundle).unwrap(),
+            r#"{"txs":[],"blockNumber":"0x2","replacementUuid":"67e55044-10b1-426f-9247-bb680e5fe0c8"}"#
+        );
+    }
+
+    #[test]
+    fn bundle_basefee_from_fee_history() {
+        let history = FeeHistory {
+            base_fee_per_gas: vec![10.into(), 12.into()],
+            gas_used_ratio: vec![1.0],
+            oldest_block: 1.into(),
+            reward: vec![],
+        };
+
+        let bundle = BundleRequest::new().set_simulation_basefee_from_fee_history(&history);
+        assert_eq!(bundle.simulation_basefee(), Some(12));
+    }
+
+    #[test]
+    fn bundle_basefee_from_parent() {
+        let parent: Block<TxHash> = Block {
+            gas_used: 30_000_000.into(),
+            gas_limit: 30_000_000.into(),
+            base_fee_per_gas: Some(800.into()),
+            ..Default::default()
+        };
+
+        let bundle = BundleRequest::new().set_simulation_basefee_from_parent(&parent);
+        assert_eq!(bundle.simulation_basefee(), Some(900));
+
+        let legacy: Block<TxHash> = Block::default();
+        let bundle = BundleRequest::new().set_simulation_basefee_from_parent(&legacy);
+        assert_eq!(bundle.simulation_basefee(), None);
+    }
+
+    #[tokio::test]
+    async fn bundle_basefee_from_provider() {
+        let parent: Block<TxHash> = Block {
+            number: Some(10.into()),
+            gas_used: 30_000_000.into(),
+            gas_limit: 30_000_000.into(),
+            base_fee_per_gas: Some(800.into()),
+            ..Default::default()
+        };
+        let (provider, mock) = Provider::mocked();
+        mock.push(parent.clone()).unwrap();
+        mock.push(parent.clone()).unwrap();
+
+        // The next block uses the predicted base fee
+        let bundle = BundleRequest::new()
+            .set_block(11.into())
+            .set_simulation_basefee_from_provider(&provider, &BasefeeEscalation::Maximum)
+            .await
+            .unwrap();
+        assert_eq!(bundle.simulation_basefee(), Some(900));
+
+        // Later blocks are escalated
+        let bundle = BundleRequest::new()
+            .set_block(13.into())
+            .set_simulation_basefee_from_provider(&provider, &BasefeeEscalation::Maximum)
+            .await
+            .unwrap();
+        assert_eq!(bundle.simulation_basefee(), Some(1138));
+
+        // Blocks without a base fee are an error
+        mock.push(Block::<TxHash>::default()).unwrap();
+        let res = BundleRequest::new()
+            .set_simulation_basefee_from_provider(&provider, &BasefeeEscalation::None)
+            .await;
+        assert!(matches!(res, Err(SimulationBasefeeError::MissingBasefee)));
+    }
+
+    #[tokio::test]
+    async fn bundle_basefee_from_oracle() {
+        let (provider, mock) = Provider::mocked();
+        let oracle = ProviderOracle::new(provider);
+
+        mock.push(U256::from(1_000_000_000u64)).unwrap();
+        let bundle = BundleRequest::new()
+            .set_simulation_basefee_from_oracle(&oracle)
+            .await
+            .unwrap();
+        assert_eq!(bundle.simulation_basefee(), Some(1_000_000_000));
+        mock.assert_request("eth_gasPrice", ()).unwrap();
+
+        mock.push(U256::MAX).unwrap();
+        let res = BundleRequest::new()
+            .set_simulation_basefee_from_oracle(&oracle)
+            .await;
+        assert!(matches!(
+            res,
+            Err(SimulationBasefeeError::BasefeeOverflow(basefee)) if basefee == U256::MAX
+        ));
+    }
+
     #[test]
     fn simulated_bundle_deserialize() {
         let simulated_bundle: SimulatedBundle = serde_json::from_str(
@@ -555,6 +2007,153 @@ mod tests {
         assert_eq!(simulated_bundle.transactions[2].to, None);
     }
 
+    #[test]
+    fn bundle_typed_transaction() {
+        let wallet = wallet();
+        let tx: TypedTransaction = TransactionRequest::pay(Address::zero(), 100)
+            .from(wallet.address())
+            .nonce(0)
+            .gas(21000)
+            .gas_price(1)
+            .chain_id(1)
+            .into();
+        let signature = wallet.sign_transaction_sync(&tx).unwrap();
+
+        let mut bundle = BundleRequest::new();
+        let tx_hash = bundle.add_typed_transaction(&tx, &signature);
+        assert_eq!(tx_hash, tx.hash(&signature));
+        assert_eq!(bundle.transaction_hashes(), vec![tx_hash]);
+
+        let bundle = BundleRequest::new().push_typed_transaction(&tx, &signature);
+        assert_eq!(bundle.transaction_hashes(), vec![tx_hash]);
+    }
+
+    #[test]
+    fn bundle_transaction_decode() {
+        let wallet = wallet();
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(Address::repeat_byte(0x11))
+            .value(100)
+            .data(vec![0x12, 0x34])
+            .nonce(7)
+            .gas(21000)
+            .max_fee_per_gas(2)
+            .max_priority_fee_per_gas(1)
+            .chain_id(1)
+            .into();
+        let signature = wallet.sign_transaction_sync(&tx).unwrap();
+
+        let raw = BundleTransaction::Raw(tx.rlp_signed(&signature));
+        let decoded = raw.decode().unwrap();
+        assert_eq!(decoded.hash, tx.hash(&signature));
+        assert_eq!(decoded.from, wallet.address());
+        assert_eq!(decoded.to, Some(Address::repeat_byte(0x11)));
+        assert_eq!(decoded.nonce, 7.into());
+        assert_eq!(decoded.gas, 21000.into());
+        assert_eq!(decoded.value, 100.into());
+        assert_eq!(decoded.input, Bytes::from(vec![0x12, 0x34]));
+
+        let signed = BundleTransaction::from(decoded.clone());
+        assert_eq!(signed.decode().unwrap(), decoded);
+
+        assert!(BundleTransaction::Raw(Bytes::from(vec![0x1]))
+            .decode()
+            .is_err());
+    }
+
+    #[test]
+    fn bundle_edit_transactions() {
+        let hash = |byte: u8| H256::from(keccak256([byte]));
+        let mut bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .push_revertible_transaction(Bytes::from(vec![0x2]))
+            .push_droppable_transaction(Bytes::from(vec![0x3]));
+
+        bundle.insert_transaction(0, Bytes::from(vec![0x4]));
+        assert_eq!(
+            bundle.transaction_hashes(),
+            vec![hash(0x4), hash(0x1), hash(0x2), hash(0x3)]
+        );
+
+        let old_tx = bundle.replace_transaction(2, Bytes::from(vec![0x5]));
+        assert!(matches!(old_tx, BundleTransaction::Raw(tx) if tx.as_ref() == [0x2]));
+        assert_eq!(bundle.transaction_hashes()[2], hash(0x5));
+        assert_eq!(bundle.revertible_transaction_hashes(), &vec![hash(0x5)]);
+
+        let removed = bundle.remove_transaction(3);
+        assert!(matches!(removed, BundleTransaction::Raw(tx) if tx.as_ref() == [0x3]));
+        assert!(bundle.droppable_transaction_hashes().is_empty());
+        assert_eq!(
+            bundle.transaction_hashes(),
+            vec![hash(0x4), hash(0x1), hash(0x5)]
+        );
+    }
+
+    #[test]
+    fn bundle_transactions_encoded_once() {
+        let wallet = wallet();
+        let tx: TypedTransaction = TransactionRequest::pay(Address::zero(), 100)
+            .nonce(0)
+            .gas(21000)
+            .gas_price(1)
+            .chain_id(1)
+            .into();
+        let signature = wallet.sign_transaction_sync(&tx).unwrap();
+        let signed = BundleTransaction::Raw(tx.rlp_signed(&signature))
+            .decode()
+            .unwrap();
+        let bundle = BundleRequest::new()
+            .push_transaction(signed.clone())
+            .push_transaction(Bytes::from(vec![0x1]));
+
+        assert_eq!(
+            bundle.transactions.encoded,
+            vec![signed.rlp(), Bytes::from(vec![0x1])]
+        );
+        assert_eq!(
+            bundle.transaction_hashes(),
+            vec![signed.hash, keccak256([0x1]).into()]
+        );
+
+        let deserialized: BundleRequest =
+            serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap();
+        assert_eq!(
+            deserialized.transactions.encoded,
+            bundle.transactions.encoded
+        );
+        assert_eq!(deserialized.transactions().len(), 2);
+    }
+
+    #[test]
+    fn bundle_clone_for_block() {
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(10.into())
+            .set_simulation_block(9.into())
+            .set_simulation_timestamp(1000);
+
+        let bumped = bundle.clone_for_block(12.into());
+        assert_eq!(bumped.block(), Some(12.into()));
+        assert_eq!(bumped.simulation_block(), Some(11.into()));
+        assert_eq!(bumped.simulation_timestamp(), Some(1024));
+        assert_eq!(bumped.transaction_hashes(), bundle.transaction_hashes());
+
+        let earlier = bundle.clone_for_block(9.into());
+        assert_eq!(earlier.simulation_block(), Some(8.into()));
+        assert_eq!(earlier.simulation_timestamp(), Some(988));
+
+        let bumped = bundle.bump_block();
+        assert_eq!(bumped.block(), Some(11.into()));
+        assert_eq!(bumped.simulation_block(), Some(10.into()));
+        assert_eq!(bumped.simulation_timestamp(), Some(1012));
+
+        let untargeted = BundleRequest::new().set_simulation_block(5.into());
+        assert_eq!(untargeted.clone().bump_block().block(), None);
+        let targeted = untargeted.clone_for_block(3.into());
+        assert_eq!(targeted.block(), Some(3.into()));
+        assert_eq!(targeted.simulation_block(), Some(5.into()));
+    }
+
     #[test]
     fn simulated_transaction_deserialize() {
         let tx: SimulatedTransaction = serde_json::from_str(
@@ -593,6 +2192,187 @@ mod tests {
         assert_eq!(tx.revert, Some("transfer failed".into()));
     }
 
+    #[test]
+    fn simulated_bundle_serialize() {
+        let simulated_bundle: SimulatedBundle = serde_json::from_str(SIMULATED_BUNDLE).unwrap();
+
+        let serialized = serde_json::to_value(&simulated_bundle).unwrap();
+        assert_eq!(serialized["results"][2]["toAddress"], "0x");
+
+        let roundtrip: SimulatedBundle = serde_json::from_value(serialized).unwrap();
+        assert_eq!(roundtrip.hash, simulated_bundle.hash);
+        assert_eq!(roundtrip.coinbase_diff, simulated_bundle.coinbase_diff);
+        assert_eq!(roundtrip.gas_used, simulated_bundle.gas_used);
+        assert_eq!(
+            roundtrip.simulation_block,
+            simulated_bundle.simulation_block
+        );
+        assert_eq!(roundtrip.transactions[2].to, None);
+        assert_eq!(
+            roundtrip.transactions[0].error,
+            Some("execution reverted".into())
+        );
+        assert_eq!(
+            roundtrip.transactions[1].value,
+            Some(Bytes::from(vec![0x1]))
+        );
+    }
+
+    #[test]
+    fn simulated_bundle_display() {
+        let tx = |error: Option<&str>, revert: Option<&str>| SimulatedTransaction {
+            error: error.map(Into::into),
+            revert: revert.map(Into::into),
+            ..simulated_transaction(H256::repeat_byte(0x1))
+        };
+        let simulated_bundle = SimulatedBundle {
+            hash: H256::repeat_byte(0x2),
+            ..simulated_bundle(vec![
+                tx(None, None),
+                tx(Some("execution reverted"), Some("too late")),
+            ])
+        };
+
+        let table = simulated_bundle.to_string();
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].contains(&format!("{:?}", H256::repeat_byte(0x2))));
+        assert!(lines[0].ends_with("(simulated at block 10)"));
+        assert!(lines[3].contains("21000"));
+        assert!(lines[3].ends_with("ok"));
+        assert!(lines[4].ends_with("error: execution reverted (too late)"));
+    }
+
+    #[test]
+    fn simulated_bundle_errors() {
+        let reverted: TxHash = keccak256([0x1]).into();
+        let simulated_bundle = simulated_bundle(vec![
+            simulated_transaction(H256::repeat_byte(0x1)),
+            SimulatedTransaction {
+                revert: Some("too late".into()),
+                ..simulated_transaction(H256::repeat_byte(0x2))
+            },
+            SimulatedTransaction {
+                error: Some("execution reverted".into()),
+                ..simulated_transaction(reverted)
+            },
+        ]);
+
+        assert!(simulated_bundle.has_errors());
+        assert_eq!(simulated_bundle.reverted_transactions().len(), 2);
+        assert_eq!(
+            simulated_bundle.first_error().unwrap().revert,
+            Some("too late".into())
+        );
+
+        assert_eq!(simulated_bundle.index_of(&reverted), Some(2));
+        assert_eq!(
+            simulated_bundle.result_for(&reverted).unwrap().error,
+            Some("execution reverted".into())
+        );
+        assert_eq!(simulated_bundle.index_of(&TxHash::zero()), None);
+        assert!(simulated_bundle.result_for(&TxHash::zero()).is_none());
+
+        let mut clean = simulated_bundle.clone();
+        clean.transactions.truncate(1);
+        assert!(!clean.has_errors());
+        assert!(clean.first_error().is_none());
+        assert!(clean.reverted_transactions().is_empty());
+    }
+
+    #[test]
+    fn simulated_bundle_effective_gas_price() {
+        let mut simulated_bundle = simulated_bundle(vec![
+            simulated_transaction(H256::repeat_byte(0x1)),
+            simulated_transaction(H256::repeat_byte(0x2)),
+        ]);
+        assert_eq!(simulated_bundle.effective_gas_price(), Some(3.into()));
+        assert_eq!(
+            simulated_bundle.effective_priority_fee(1.into()),
+            Some(2.into())
+        );
+        assert_eq!(
+            simulated_bundle.effective_priority_fee(5.into()),
+            Some(0.into())
+        );
+
+        simulated_bundle.gas_used = 0.into();
+        assert_eq!(simulated_bundle.effective_gas_price(), None);
+        assert_eq!(simulated_bundle.effective_priority_fee(1.into()), None);
+    }
+
+    #[test]
+    fn simulated_bundle_profit() {
+        let simulated_bundle = SimulatedBundle {
+            coinbase_diff: 150000.into(),
+            coinbase_tip: 24000.into(),
+            ..simulated_bundle(vec![
+                simulated_transaction(H256::repeat_byte(0x1)),
+                simulated_transaction(H256::repeat_byte(0x2)),
+            ])
+        };
+        assert_eq!(simulated_bundle.profit(0.into()), I256::from(24000));
+        assert_eq!(simulated_bundle.profit(30000.into()), I256::from(-6000));
+        assert_eq!(simulated_bundle.profit(U256::MAX), I256::MIN + 24000 + 1);
+    }
+
+    #[test]
+    fn simulated_bundle_gas_report() {
+        let tx = |hash: u8, gas: u64| Transaction {
+            hash: H256::repeat_byte(hash),
+            gas: gas.into(),
+            ..Default::default()
+        };
+        let bundle = BundleRequest::new()
+            .push_transaction(tx(0x1, 30000))
+            .push_transaction(tx(0x2, 100000))
+            .push_transaction(tx(0x3, 21000));
+
+        let simulated = |hash: u8, gas_used: u64| SimulatedTransaction {
+            gas_used: gas_used.into(),
+            ..simulated_transaction(H256::repeat_byte(hash))
+        };
+        let simulation = simulated_bundle(vec![simulated(0x1, 21000), simulated(0x2, 40000)]);
+
+        let report = simulation.gas_report(&bundle);
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].unused_gas(), 9000.into());
+        assert_eq!(report[0].utilization(), Some(0.7));
+        assert!(!report[0].is_over_provisioned(1.5));
+        assert_eq!(report[1].hash, H256::repeat_byte(0x2));
+        assert!(report[1].is_over_provisioned(1.5));
+    }
+
+    #[test]
+    fn simulated_bundle_receipts() {
+        let tx = SimulatedTransaction {
+            from: Address::repeat_byte(0x2),
+            ..simulated_transaction(H256::repeat_byte(0x1))
+        };
+        let reverted = SimulatedTransaction {
+            to: Some(Address::repeat_byte(0x3)),
+            revert: Some("too late".into()),
+            ..tx.clone()
+        };
+
+        let receipt = tx.to_receipt(Some(1.into()));
+        assert_eq!(receipt.transaction_hash, tx.hash);
+        assert_eq!(receipt.status, Some(1.into()));
+        assert_eq!(receipt.gas_used, Some(21000.into()));
+        assert_eq!(
+            receipt.contract_address,
+            Some(get_contract_address(tx.from, 1))
+        );
+        assert_eq!(tx.to_receipt(None).contract_address, None);
+        assert_eq!(reverted.to_receipt(Some(1.into())).contract_address, None);
+
+        let simulated_bundle = simulated_bundle(vec![tx, reverted]);
+        let receipts = simulated_bundle.to_receipts();
+        assert_eq!(receipts[1].transaction_index, 1.into());
+        assert_eq!(receipts[1].cumulative_gas_used, 42000.into());
+        assert_eq!(receipts[1].status, Some(0.into()));
+    }
+
     #[test]
     fn bundle_stats_deserialize() {
         let bundle_stats: BundleStats = serde_json::from_str(
@@ -0,0 +1,187 @@
+use crate::bundle::BundleRequest;
+use std::borrow::Cow;
+
+/// The features supported by a builder.
+///
+/// Builders support different subsets of the bundle API. The capabilities
+/// of a relay can be set using [`Relay::set_capabilities`](crate::Relay::set_capabilities),
+/// in which case [`BroadcasterMiddleware`](crate::BroadcasterMiddleware)
+/// removes unsupported fields from bundles before sending them to the
+/// relay (logging a warning for each relay), and skips relays that cannot
+/// accept a bundle at all. [`FlashbotsMiddleware`](crate::FlashbotsMiddleware)
+/// rejects private transactions and MEV-Share requests if the relay does
+/// not support them.
+///
+/// By default, all features are assumed to be supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuilderCapabilities {
+    /// Whether the builder supports replacing and cancelling bundles
+    /// using a replacement uuid.
+    pub cancellation: bool,
+    /// Whether the builder supports refunds (`refundPercent`,
+    /// `refundRecipient` and `refundTxHashes`).
+    pub refunds: bool,
+    /// Whether the builder accepts private transactions.
+    pub private_transactions: bool,
+    /// Whether the builder accepts blob transactions in bundles.
+    pub blob_transactions: bool,
+    /// Whether the builder accepts MEV-Share bundles.
+    pub mev_share: bool,
+    /// The maximum number of transactions in a bundle (if any).
+    pub max_bundle_size: Option<usize>,
+}
+
+impl BuilderCapabilities {
+    /// A builder that supports all features.
+    pub const ALL: Self = Self {
+        cancellation: true,
+        refunds: true,
+        private_transactions: true,
+        blob_transactions: true,
+        mev_share: true,
+        max_bundle_size: None,
+    };
+
+    /// A builder that only supports plain bundles, i.e. `eth_sendBundle`
+    /// with transactions, a target block and timestamps.
+    pub const BASIC: Self = Self {
+        cancellation: false,
+        refunds: false,
+        private_transactions: false,
+        blob_transactions: false,
+        mev_share: false,
+        max_bundle_size: None,
+    };
+
+    /// The Flashbots builder.
+    pub const FLASHBOTS: Self = Self::ALL;
+
+    /// Shape a bundle for a builder with these capabilities.
+    ///
+    /// Unsupported refund fields and replacement uuids are removed from
+    /// the bundle (see [`BuilderCapabilities::unsupported_fields`]).
+    /// Returns `None` if the builder cannot accept the bundle at all, i.e.
+    /// if the bundle is a cancellation and cancellations are not supported,
+    /// if the bundle contains blob transactions and blob transactions are
+    /// not supported, or if the bundle has too many transactions.
+    ///
+    /// The bundle is only copied if it has to be changed.
+    pub fn shape<'a>(&self, bundle: &'a BundleRequest) -> Option<Cow<'a, BundleRequest>> {
+        if bundle.is_cancellation() && !self.cancellation {
+            return None;
+        }
+        if !self.blob_transactions && bundle.transactions().iter().any(|tx| tx.is_blob()) {
+            return None;
+        }
+        if matches!(self.max_bundle_size, Some(max) if bundle.transactions().len() > max) {
+            return None;
+        }
+
+        let mut bundle = Cow::Borrowed(bundle);
+        if !self.cancellation && bundle.uuid().is_some() {
+            bundle.to_mut().clear_uuid();
+        }
+        if !self.refunds && bundle.has_refund() {
+            bundle.to_mut().clear_refund();
+        }
+
+        Some(bundle)
+    }
+
+    /// Get the names of the fields of a bundle that are removed by
+    /// [`BuilderCapabilities::shape`] because the builder does not support
+    /// them.
+    pub fn unsupported_fields(&self, bundle: &BundleRequest) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        if !self.cancellation && bundle.uuid().is_some() {
+            fields.push("replacementUuid");
+        }
+        if !self.refunds {
+            if bundle.refund_percent().is_some() {
+                fields.push("refundPercent");
+            }
+            if bundle.refund_recipient().is_some() {
+                fields.push("refundRecipient");
+            }
+            if !bundle.refund_transaction_hashes().is_empty() {
+                fields.push("refundTxHashes");
+            }
+        }
+
+        fields
+    }
+}
+
+impl Default for BuilderCapabilities {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::types::{Address, Bytes};
+    use uuid::uuid;
+
+    #[test]
+    fn capabilities_shape() {
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .push_transaction_with_options(
+                Bytes::from(vec![0x2]),
+                crate::TxOptions {
+                    refund_percent: Some(50),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .set_refund_recipient(Address::repeat_byte(0x1))
+            .set_uuid(uuid!("67e55044-10b1-426f-9247-bb680e5fe0c8"))
+            .set_block(2.into());
+
+        assert!(matches!(
+            BuilderCapabilities::ALL.shape(&bundle),
+            Some(Cow::Borrowed(_))
+        ));
+
+        assert!(BuilderCapabilities::ALL
+            .unsupported_fields(&bundle)
+            .is_empty());
+        assert_eq!(
+            BuilderCapabilities::BASIC.unsupported_fields(&bundle),
+            vec![
+                "replacementUuid",
+                "refundPercent",
+                "refundRecipient",
+                "refundTxHashes"
+            ]
+        );
+        let shaped = BuilderCapabilities::BASIC.shape(&bundle).unwrap();
+        assert_eq!(
+            &serde_json::to_string(&shaped).unwrap(),
+            r#"{"txs":["0x01","0x02"],"blockNumber":"0x2"}"#
+        );
+
+        let small = BuilderCapabilities {
+            max_bundle_size: Some(1),
+            ..Default::default()
+        };
+        assert!(small.shape(&bundle).is_none());
+
+        let cancellation =
+            BundleRequest::cancellation(uuid!("67e55044-10b1-426f-9247-bb680e5fe0c8"));
+        assert!(BuilderCapabilities::BASIC.shape(&cancellation).is_none());
+        assert!(BuilderCapabilities::ALL.shape(&cancellation).is_some());
+    }
+
+    #[test]
+    fn capabilities_blob_transactions() {
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x03, 0x1]))
+            .set_block(2.into());
+
+        assert!(BuilderCapabilities::ALL.shape(&bundle).is_some());
+        assert!(BuilderCapabilities::BASIC.shape(&bundle).is_none());
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    collections::VecDeque,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// A circuit breaker for requests to a relay.
+///
+/// The circuit opens when the fraction of failed requests among the
+/// recent requests to the relay reaches the error rate (50% of the last
+/// 20 requests by default). Only errors that may succeed on retry (see
+/// [`ErrorKind::is_retryable`](crate::ErrorKind::is_retryable)) count as
+/// failures.
+///
+/// While the circuit is open, requests fail immediately with
+/// [`RelayError::CircuitOpen`](crate::RelayError::CircuitOpen). After a
+/// cooldown period (30 seconds by default), a single request is let
+/// through as a probe: if it succeeds the circuit closes, otherwise it
+/// stays open for another cooldown period.
+///
+/// ```
+/// use ethers_flashbots::CircuitBreaker;
+/// use std::time::Duration;
+///
+/// let breaker = CircuitBreaker::new()
+///     .set_error_rate(0.25)
+///     .set_cooldown(Duration::from_secs(10));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CircuitBreaker {
+    error_rate: f64,
+    window: usize,
+    cooldown: Duration,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            error_rate: 0.5,
+            window: 20,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// Creates a circuit breaker that opens when half of the last 20
+    /// requests failed.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get the fraction of failed requests at which the circuit opens.
+    pub fn error_rate(&self) -> f64 {
+        self.error_rate
+    }
+
+    /// Set the fraction of failed requests (between 0 and 1) at which the
+    /// circuit opens.
+    pub fn set_error_rate(mut self, error_rate: f64) -> Self {
+        self.error_rate = error_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Get the number of recent requests the error rate is computed
+    /// over.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Set the number of recent requests the error rate is computed over.
+    ///
+    /// The circuit does not open before this many requests were sent.
+    pub fn set_window(mut self, requests: usize) -> Self {
+        self.window = requests.max(1);
+        self
+    }
+
+    /// Get the time after which a probe request is let through.
+    pub fn cooldown(&self) -> Duration {
+        self.cooldown
+    }
+
+    /// Set the time after which a probe request is let through.
+    pub fn set_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+/// Enforces a [`CircuitBreaker`].
+#[derive(Debug)]
+pub(crate) struct CircuitBreakerState {
+    breaker: CircuitBreaker,
+    circuit: Mutex<Circuit>,
+}
+
+#[derive(Debug, Default)]
+struct Circuit {
+    /// Whether each of the recent requests failed.
+    outcomes: VecDeque<bool>,
+    opened_at: Option<Instant>,
+    probe_at: Option<Instant>,
+}
+
+impl CircuitBreakerState {
+    pub(crate) fn new(breaker: CircuitBreaker) -> Self {
+        Self {
+            breaker,
+            circuit: Default::default(),
+        }
+    }
+
+    pub(crate) fn breaker(&self) -> CircuitBreaker {
+        self.breaker
+    }
+
+    /// Whether a request may be sent.
+    ///
+    /// If the circuit is open and the cooldown has passed, the request is
+    /// the probe. Probes that are not reported (e.g. because the request
+    /// was dropped) are retried after another cooldown.
+    pub(crate) fn allow(&self) -> bool {
+        let mut circuit = self.circuit.lock().unwrap();
+        let opened_at = match circuit.opened_at {
+            Some(opened_at) => opened_at,
+            None => return true,
+        };
+        let last_attempt = circuit.probe_at.unwrap_or(opened_at);
+        if last_attempt.elapsed() < self.breaker.cooldown {
+            return false;
+        }
+        circuit.probe_at = Some(Instant::now());
+        true
+    }
+
+    /// Record whether a request failed.
+    pub(crate) fn report(&self, failed: bool) {
+        let mut circuit = self.circuit.lock().unwrap();
+        if circuit.opened_at.is_some() {
+            if failed {
+                circuit.opened_at = Some(Instant::now());
+                circuit.probe_at = None;
+            } else {
+                *circuit = Circuit::default();
+            }
+            return;
+        }
+
+        if circuit.outcomes.len() == self.breaker.window {
+            circuit.outcomes.pop_front();
+        }
+        circuit.outcomes.push_back(failed);
+
+        let failures = circuit.outcomes.iter().filter(|failed| **failed).count();
+        if circuit.outcomes.len() == self.breaker.window
+            && failures as f64 >= self.breaker.error_rate * self.breaker.window as f64
+        {
+            circuit.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circuit_breaker() {
+        let state = CircuitBreakerState::new(CircuitBreaker::new().set_window(4));
+        state.report(false);
+        state.report(false);
+        state.report(true);
+        state.report(false);
+        // Only 1 of the last 4 requests failed
+        assert!(state.allow());
+        state.report(true);
+        assert!(!state.allow());
+
+        // Probes are let through after the cooldown
+        let state = CircuitBreakerState::new(
+            CircuitBreaker::new()
+                .set_window(1)
+                .set_cooldown(Duration::ZERO),
+        );
+        state.report(true);
+        assert!(state.allow());
+        // The probe failed
+        state.report(true);
+        assert!(state.allow());
+        state.report(false);
+        assert!(state.circuit.lock().unwrap().opened_at.is_none());
+    }
+}
@@ -0,0 +1,112 @@
+use crate::bundle::BundleHash;
+use ethers::core::types::{H256, U64};
+use std::{collections::HashMap, sync::Mutex};
+
+/// The number of blocks submissions are remembered for after their
+/// target block.
+const PRUNE_DEPTH: u64 = 32;
+
+/// How a relay handles bundles that were already submitted to it.
+///
+/// Bundles are duplicates if their payload is identical to that of a
+/// previous submission to the same relay, including the target block.
+///
+/// See [`Relay::set_duplicate_policy`](crate::Relay::set_duplicate_policy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Skip duplicate submissions, and return the bundle hash of the
+    /// original submission instead.
+    Skip,
+    /// Reject duplicate submissions with
+    /// [`FlashbotsMiddlewareError::DuplicateSubmission`](crate::FlashbotsMiddlewareError::DuplicateSubmission).
+    Reject,
+}
+
+/// A cache of bundles submitted to a relay.
+#[derive(Debug)]
+pub(crate) struct SubmissionCache {
+    policy: DuplicatePolicy,
+    submissions: Mutex<HashMap<H256, Submission>>,
+}
+
+#[derive(Debug)]
+struct Submission {
+    block: U64,
+    bundle_hash: Option<BundleHash>,
+}
+
+impl SubmissionCache {
+    pub(crate) fn new(policy: DuplicatePolicy) -> Self {
+        Self {
+            policy,
+            submissions: Default::default(),
+        }
+    }
+
+    pub(crate) fn policy(&self) -> DuplicatePolicy {
+        self.policy
+    }
+
+    /// Reserve a submission, returning the bundle hash of the previous
+    /// submission if the payload was already submitted.
+    ///
+    /// The bundle hash is `None` if the previous submission is still in
+    /// flight, or the relay did not return a bundle hash.
+    pub(crate) fn reserve(&self, block: U64, content_hash: H256) -> Option<Option<BundleHash>> {
+        let mut submissions = self.submissions.lock().unwrap();
+        submissions.retain(|_, submission| submission.block + PRUNE_DEPTH >= block);
+
+        if let Some(submission) = submissions.get(&content_hash) {
+            return Some(submission.bundle_hash);
+        }
+        submissions.insert(
+            content_hash,
+            Submission {
+                block,
+                bundle_hash: None,
+            },
+        );
+        None
+    }
+
+    /// Record the bundle hash of a reserved submission.
+    pub(crate) fn complete(&self, content_hash: H256, bundle_hash: Option<BundleHash>) {
+        if let Some(submission) = self.submissions.lock().unwrap().get_mut(&content_hash) {
+            submission.bundle_hash = bundle_hash;
+        }
+    }
+
+    /// Release a reserved submission that failed, so it can be retried.
+    pub(crate) fn release(&self, content_hash: H256) {
+        self.submissions.lock().unwrap().remove(&content_hash);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn submission_cache() {
+        let cache = SubmissionCache::new(DuplicatePolicy::Skip);
+        let (a, b) = (H256::repeat_byte(0xa), H256::repeat_byte(0xb));
+
+        assert_eq!(cache.reserve(10.into(), a), None);
+        // In flight
+        assert_eq!(cache.reserve(10.into(), a), Some(None));
+        cache.complete(a, Some(H256::repeat_byte(0x1)));
+        assert_eq!(
+            cache.reserve(10.into(), a),
+            Some(Some(H256::repeat_byte(0x1)))
+        );
+
+        // Failed submissions can be retried
+        assert_eq!(cache.reserve(10.into(), b), None);
+        cache.release(b);
+        assert_eq!(cache.reserve(10.into(), b), None);
+
+        // Old submissions are forgotten
+        assert_eq!(cache.reserve(43.into(), H256::zero()), None);
+        assert_eq!(cache.reserve(43.into(), a), None);
+    }
+}
@@ -0,0 +1,382 @@
+use crate::network::Network;
+use serde_json::{Map, Value};
+
+/// The bundle API dialect spoken by a relay.
+///
+/// Many builders implement a variant of the Flashbots bundle API with
+/// different method names, parameters or response formats. The dialect
+/// of a relay can be set using [`Relay::set_dialect`](crate::Relay::set_dialect),
+/// in which case requests are translated from the Flashbots API before
+/// they are sent, and responses are normalized to the Flashbots API.
+///
+/// Methods without an equivalent in a dialect are sent unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RelayDialect {
+    /// The Flashbots bundle API.
+    #[default]
+    Flashbots,
+    /// The bundle API of BSC builders implementing [BEP-322][bep_322],
+    /// e.g. 48 Club.
+    ///
+    /// Bundles can be included in any block up to the target block, and
+    /// only support reverting transaction hashes and timestamps.
+    ///
+    /// BSC builders do not authenticate bundles: the signature header is
+    /// still sent, but ignored. Builders that require an API key expect it
+    /// in the authorization header, see
+    /// [`Relay::set_auth_header`](crate::Relay::set_auth_header).
+    ///
+    /// [bep_322]: https://github.com/bnb-chain/BEPs/blob/master/BEPs/BEP322.md
+    Bsc,
+    /// The legacy Puissant bundle API of 48 Club on BSC
+    /// (`eth_sendPuissant`).
+    ///
+    /// Like [`RelayDialect::Bsc`], requests are not authenticated.
+    Puissant,
+    /// The bundle API of Polygon relays running [mev-bor][mev_bor], e.g.
+    /// Marlin.
+    ///
+    /// This is the original Flashbots bundle API, so the only bundle
+    /// fields sent are the transactions, the target block, timestamps and
+    /// reverting transaction hashes.
+    ///
+    /// [mev_bor]: https://github.com/marlinprotocol/mev-bor
+    MevBor,
+    /// The bundle and private transaction API of [Eden Network][eden].
+    ///
+    /// Bundles are sent unchanged, and private transactions are sent as
+    /// raw transactions (`eth_sendRawTransaction`), so
+    /// [`PrivateTransaction`](crate::mev_share::PrivateTransaction)
+    /// preferences are not supported.
+    ///
+    /// Eden authenticates requests the same way as Flashbots, using the
+    /// signature header of the relay's signer, and uses the signing address
+    /// to identify the searcher (e.g. for staking priority). No other
+    /// credentials are required, so no auth header has to be set.
+    ///
+    /// [eden]: https://docs.edennetwork.io/
+    Eden,
+    /// The [bloXroute Cloud API][bloxroute] (`blxr_submit_bundle`) on the
+    /// given network.
+    ///
+    /// bloXroute authenticates requests using an authorization header,
+    /// which can be set using [`Relay::set_auth_header`](crate::Relay::set_auth_header).
+    /// Bundles are sent to all builders, unless builders are selected by
+    /// setting the `mev_builders` field of the bundle using
+    /// [`BundleRequest::set_extra_field`](crate::BundleRequest::set_extra_field),
+    /// e.g. to `{"flashbots": "", "beaverbuild": ""}`.
+    ///
+    /// [bloxroute]: https://docs.bloxroute.com/apis/mev-solution/bundle-submission
+    Bloxroute(BloxrouteNetwork),
+}
+
+/// A network supported by the bloXroute Cloud API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BloxrouteNetwork {
+    /// Ethereum mainnet.
+    Mainnet,
+    /// BNB Smart Chain.
+    Bsc,
+    /// Polygon.
+    Polygon,
+}
+
+impl BloxrouteNetwork {
+    /// Get the network.
+    pub fn network(&self) -> Network {
+        match self {
+            BloxrouteNetwork::Mainnet => Network::Mainnet,
+            BloxrouteNetwork::Bsc => Network::Bsc,
+            BloxrouteNetwork::Polygon => Network::Polygon,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            BloxrouteNetwork::Mainnet => "Mainnet",
+            BloxrouteNetwork::Bsc => "BSC-Mainnet",
+            BloxrouteNetwork::Polygon => "Polygon-Mainnet",
+        }
+    }
+}
+
+impl RelayDialect {
+    /// Translate a Flashbots API request into this dialect.
+    pub(crate) fn translate_request(&self, method: &str, params: Value) -> (String, Value) {
+        match (self, method) {
+            (RelayDialect::Bsc, "eth_sendBundle") => {
+                (method.to_string(), map_first_param(params, bsc_bundle))
+            }
+            (RelayDialect::MevBor, "eth_sendBundle" | "eth_callBundle") => {
+                (method.to_string(), map_first_param(params, mev_bor_bundle))
+            }
+            (RelayDialect::Eden, "eth_sendPrivateTransaction") => (
+                "eth_sendRawTransaction".to_string(),
+                map_first_param(params, eden_private_transaction),
+            ),
+            (RelayDialect::Bloxroute(network), "eth_sendBundle") => (
+                "blxr_submit_bundle".to_string(),
+                match map_first_param(params, |bundle| bloxroute_bundle(bundle, *network)) {
+                    // bloXroute expects the parameters as an object
+                    Value::Array(mut params) if params.len() == 1 => params.remove(0),
+                    params => params,
+                },
+            ),
+            (RelayDialect::Puissant, "eth_sendBundle") => (
+                "eth_sendPuissant".to_string(),
+                map_first_param(params, puissant_bundle),
+            ),
+            _ => (method.to_string(), params),
+        }
+    }
+
+    /// Normalize a response of this dialect to the Flashbots API.
+    pub(crate) fn translate_response(&self, method: &str, result: Value) -> Value {
+        match (self, method) {
+            // Some builders return the bundle hash directly
+            (RelayDialect::Bsc | RelayDialect::Puissant | RelayDialect::Eden, "eth_sendBundle") => {
+                wrap_bundle_hash(result)
+            }
+            _ => result,
+        }
+    }
+}
+
+/// Applies `f` to the parameter object of a single-parameter request.
+fn map_first_param(params: Value, f: impl FnOnce(Map<String, Value>) -> Value) -> Value {
+    match params {
+        Value::Array(mut params) if matches!(params.first(), Some(Value::Object(_))) => {
+            if let Value::Object(param) = params.remove(0) {
+                params.insert(0, f(param));
+            }
+            Value::Array(params)
+        }
+        params => params,
+    }
+}
+
+/// Moves the given fields from one object to another, renaming them.
+fn move_fields(from: &mut Map<String, Value>, fields: &[(&str, &str)]) -> Map<String, Value> {
+    let mut to = Map::new();
+    for (old, new) in fields {
+        if let Some(value) = from.remove(*old) {
+            to.insert(new.to_string(), value);
+        }
+    }
+    to
+}
+
+/// Converts a hex quantity to a JSON number, as expected by some builders.
+fn quantity_to_number(value: Value) -> Value {
+    match value.as_str().and_then(|s| s.strip_prefix("0x")) {
+        Some(hex) => u64::from_str_radix(hex, 16)
+            .map(Value::from)
+            .unwrap_or(value),
+        None => value,
+    }
+}
+
+fn bsc_bundle(mut bundle: Map<String, Value>) -> Value {
+    let mut translated = move_fields(
+        &mut bundle,
+        &[
+            ("txs", "txs"),
+            ("minTimestamp", "minTimestamp"),
+            ("maxTimestamp", "maxTimestamp"),
+            ("revertingTxHashes", "revertingTxHashes"),
+        ],
+    );
+    if let Some(block) = bundle.remove("blockNumber") {
+        translated.insert("maxBlockNumber".to_string(), quantity_to_number(block));
+    }
+    Value::Object(translated)
+}
+
+fn mev_bor_bundle(mut bundle: Map<String, Value>) -> Value {
+    Value::Object(move_fields(
+        &mut bundle,
+        &[
+            ("txs", "txs"),
+            ("blockNumber", "blockNumber"),
+            ("stateBlockNumber", "stateBlockNumber"),
+            ("timestamp", "timestamp"),
+            ("timeout", "timeout"),
+            ("minTimestamp", "minTimestamp"),
+            ("maxTimestamp", "maxTimestamp"),
+            ("revertingTxHashes", "revertingTxHashes"),
+        ],
+    ))
+}
+
+fn puissant_bundle(mut bundle: Map<String, Value>) -> Value {
+    Value::Object(move_fields(
+        &mut bundle,
+        &[
+            ("txs", "txs"),
+            ("maxTimestamp", "maxTimestamp"),
+            ("revertingTxHashes", "acceptReverting"),
+        ],
+    ))
+}
+
+fn bloxroute_bundle(mut bundle: Map<String, Value>, network: BloxrouteNetwork) -> Value {
+    let mut translated = move_fields(
+        &mut bundle,
+        &[
+            ("blockNumber", "block_number"),
+            ("minTimestamp", "min_timestamp"),
+            ("maxTimestamp", "max_timestamp"),
+            ("revertingTxHashes", "reverting_hashes"),
+        ],
+    );
+
+    // Transactions are sent without the `0x` prefix
+    let txs = match bundle.remove("txs") {
+        Some(Value::Array(txs)) => txs
+            .into_iter()
+            .map(|tx| match tx.as_str() {
+                Some(tx) => Value::from(tx.trim_start_matches("0x")),
+                None => tx,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    translated.insert("transaction".to_string(), Value::Array(txs));
+    translated.insert(
+        "mev_builders".to_string(),
+        bundle
+            .remove("mev_builders")
+            .unwrap_or_else(|| serde_json::json!({ "all": "" })),
+    );
+    translated.insert("blockchain_network".to_string(), network.name().into());
+
+    Value::Object(translated)
+}
+
+fn eden_private_transaction(mut tx: Map<String, Value>) -> Value {
+    tx.remove("tx").unwrap_or(Value::Null)
+}
+
+fn wrap_bundle_hash(result: Value) -> Value {
+    match result {
+        Value::String(hash) => serde_json::json!({ "bundleHash": hash }),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn bsc_dialect() {
+        let params = json!([{
+            "txs": ["0x01"],
+            "blockNumber": "0xa",
+            "maxTimestamp": 100,
+            "revertingTxHashes": ["0x02"],
+            "replacementUuid": "00000000-0000-0000-0000-000000000000"
+        }]);
+
+        assert_eq!(
+            RelayDialect::Bsc.translate_request("eth_sendBundle", params.clone()),
+            (
+                "eth_sendBundle".to_string(),
+                json!([{
+                    "txs": ["0x01"],
+                    "maxBlockNumber": 10,
+                    "maxTimestamp": 100,
+                    "revertingTxHashes": ["0x02"]
+                }])
+            )
+        );
+        assert_eq!(
+            RelayDialect::Puissant.translate_request("eth_sendBundle", params.clone()),
+            (
+                "eth_sendPuissant".to_string(),
+                json!([{
+                    "txs": ["0x01"],
+                    "maxTimestamp": 100,
+                    "acceptReverting": ["0x02"]
+                }])
+            )
+        );
+        assert_eq!(
+            RelayDialect::Flashbots.translate_request("eth_sendBundle", params.clone()),
+            ("eth_sendBundle".to_string(), params)
+        );
+
+        assert_eq!(
+            RelayDialect::Bsc.translate_response("eth_sendBundle", json!("0x03")),
+            json!({ "bundleHash": "0x03" })
+        );
+        assert_eq!(
+            RelayDialect::Bsc.translate_response("eth_callBundle", json!("0x03")),
+            json!("0x03")
+        );
+    }
+
+    #[test]
+    fn bloxroute_dialect() {
+        let params = json!([{
+            "txs": ["0x01", "0x02"],
+            "blockNumber": "0xa",
+            "revertingTxHashes": ["0x03"],
+            "mev_builders": { "flashbots": "" }
+        }]);
+
+        assert_eq!(
+            RelayDialect::Bloxroute(BloxrouteNetwork::Bsc)
+                .translate_request("eth_sendBundle", params),
+            (
+                "blxr_submit_bundle".to_string(),
+                json!({
+                    "transaction": ["01", "02"],
+                    "block_number": "0xa",
+                    "reverting_hashes": ["0x03"],
+                    "mev_builders": { "flashbots": "" },
+                    "blockchain_network": "BSC-Mainnet"
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn eden_dialect() {
+        assert_eq!(
+            RelayDialect::Eden.translate_request(
+                "eth_sendPrivateTransaction",
+                json!([{ "tx": "0x01", "maxBlockNumber": "0xa" }])
+            ),
+            ("eth_sendRawTransaction".to_string(), json!(["0x01"]))
+        );
+        assert_eq!(
+            RelayDialect::Eden.translate_response("eth_sendBundle", json!("0x03")),
+            json!({ "bundleHash": "0x03" })
+        );
+        assert_eq!(
+            RelayDialect::Eden
+                .translate_response("eth_sendBundle", json!({ "bundleHash": "0x03" })),
+            json!({ "bundleHash": "0x03" })
+        );
+    }
+
+    #[test]
+    fn mev_bor_dialect() {
+        let params = json!([{
+            "txs": ["0x01"],
+            "blockNumber": "0xa",
+            "refundPercent": 50,
+            "replacementUuid": "00000000-0000-0000-0000-000000000000"
+        }]);
+
+        assert_eq!(
+            RelayDialect::MevBor.translate_request("eth_sendBundle", params),
+            (
+                "eth_sendBundle".to_string(),
+                json!([{ "txs": ["0x01"], "blockNumber": "0xa" }])
+            )
+        );
+    }
+}
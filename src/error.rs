@@ -0,0 +1,56 @@
+/// A coarse classification of errors returned by this crate.
+///
+/// Error enums in this crate are non-exhaustive, as new variants are added
+/// over time. Matching on the kind of an error instead of its variants
+/// keeps error handling working as the enums grow.
+///
+/// See [`RelayError::kind`](crate::RelayError::kind),
+/// [`FlashbotsMiddlewareError::kind`](crate::FlashbotsMiddlewareError::kind)
+/// and [`PendingBundleError::kind`](crate::PendingBundleError::kind).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The request could not be sent, or the response could not be read.
+    Network,
+    /// The relay returned a server error.
+    Server,
+    /// The relay returned a JSON-RPC error.
+    Relay,
+    /// The request was invalid, e.g. because parameters were missing or
+    /// the relay rejected them.
+    InvalidRequest,
+    /// The response of the relay could not be understood.
+    InvalidResponse,
+    /// The request could not be signed.
+    Signer,
+    /// The relay or the middleware is misconfigured, e.g. it is on the
+    /// wrong chain.
+    Configuration,
+    /// The requested data is not available (yet).
+    Unavailable,
+    /// The relay rejected the request because it exceeded a limit, e.g. a
+    /// rate limit.
+    LimitExceeded,
+    /// The bundle was not included in the target block.
+    NotIncluded,
+    /// An error occurred in the inner middleware or provider.
+    Provider,
+    /// The operation was cancelled.
+    Cancelled,
+}
+
+impl ErrorKind {
+    /// Whether retrying the same request may succeed.
+    ///
+    /// This is the case for network errors, server errors, exceeded
+    /// limits and data that is not available yet.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ErrorKind::Network
+                | ErrorKind::Server
+                | ErrorKind::Unavailable
+                | ErrorKind::LimitExceeded
+        )
+    }
+}
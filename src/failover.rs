@@ -0,0 +1,352 @@
+use crate::{
+    relay::Relay,
+    transport::{ResponseTooLarge, TransportError},
+};
+#[cfg(feature = "reqwest-transport")]
+use reqwest::Error as ReqwestError;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use url::Url;
+
+/// The default time a failed endpoint is skipped for.
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// The default number of consecutive errors after which simulations fail
+/// over.
+const DEFAULT_SIMULATION_ERROR_THRESHOLD: u32 = 3;
+
+/// Fallback endpoints for a relay.
+///
+/// If a request to the relay fails because the endpoint could not be
+/// reached (e.g. on connection errors or timeouts), it is retried on the
+/// fallback endpoints in order. Failed endpoints are skipped for a
+/// cooldown period (30 seconds by default), after which they are tried
+/// again, so requests return to the primary endpoint once it recovers.
+///
+/// Fallback endpoints must speak the same dialect as the relay, and
+/// accept the same signer.
+///
+/// ```
+/// use ethers_flashbots::Failover;
+/// use std::time::Duration;
+/// use url::Url;
+///
+/// let failover = Failover::new(vec![Url::parse("https://relay-backup.example.com").unwrap()])
+///     .set_cooldown(Duration::from_secs(10));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failover {
+    fallbacks: Vec<Url>,
+    cooldown: Duration,
+}
+
+impl Failover {
+    /// Creates a failover to the given endpoints, in order.
+    pub fn new(fallbacks: Vec<Url>) -> Self {
+        Self {
+            fallbacks,
+            cooldown: DEFAULT_COOLDOWN,
+        }
+    }
+
+    /// Get the fallback endpoints.
+    pub fn fallbacks(&self) -> &[Url] {
+        &self.fallbacks
+    }
+
+    /// Get the time a failed endpoint is skipped for.
+    pub fn cooldown(&self) -> Duration {
+        self.cooldown
+    }
+
+    /// Set the time a failed endpoint is skipped for.
+    pub fn set_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+/// Tracks the health of the endpoints of a [`Failover`].
+#[derive(Debug)]
+pub(crate) struct FailoverState {
+    failover: Failover,
+    /// The time each endpoint last failed at, starting with the primary
+    /// endpoint.
+    failures: Mutex<Vec<Option<Instant>>>,
+}
+
+impl FailoverState {
+    pub(crate) fn new(failover: Failover) -> Self {
+        let failures = Mutex::new(vec![None; failover.fallbacks.len() + 1]);
+        Self { failover, failures }
+    }
+
+    pub(crate) fn failover(&self) -> &Failover {
+        &self.failover
+    }
+
+    /// Get the endpoints to try in order, as the index and URL of each
+    /// endpoint.
+    ///
+    /// Healthy endpoints are tried first, followed by endpoints that
+    /// failed within the cooldown period.
+    pub(crate) fn endpoints<'a>(&'a self, primary: &'a Url) -> Vec<(usize, &'a Url)> {
+        let failures = self.failures.lock().unwrap();
+        let (healthy, failed): (Vec<_>, Vec<_>) = std::iter::once(primary)
+            .chain(&self.failover.fallbacks)
+            .enumerate()
+            .partition(|(i, _)| match failures[*i] {
+                Some(failed_at) => failed_at.elapsed() >= self.failover.cooldown,
+                None => true,
+            });
+        healthy.into_iter().chain(failed).collect()
+    }
+
+    /// Record the result of a request to the endpoint with the given
+    /// index.
+    pub(crate) fn report(&self, index: usize, healthy: bool) {
+        self.failures.lock().unwrap()[index] = if healthy { None } else { Some(Instant::now()) };
+    }
+}
+
+/// Failover of bundle simulations when the simulation relay fails.
+///
+/// After the simulation relay of a
+/// [`FlashbotsMiddleware`](crate::FlashbotsMiddleware) fails with a
+/// retryable error (see [`ErrorKind::is_retryable`](crate::ErrorKind::is_retryable))
+/// a number of times in a row (3 by default), `eth_callBundle` requests are
+/// sent to the fallback relays in order, or to the main relay if there are
+/// no fallbacks. The simulation relay is tried again after a cooldown
+/// period (30 seconds by default).
+///
+/// See [`FlashbotsMiddleware::set_simulation_failover`](crate::FlashbotsMiddleware::set_simulation_failover).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationFailover {
+    threshold: u32,
+    fallbacks: Vec<Url>,
+    cooldown: Duration,
+}
+
+impl Default for SimulationFailover {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_SIMULATION_ERROR_THRESHOLD,
+            fallbacks: Vec::new(),
+            cooldown: DEFAULT_COOLDOWN,
+        }
+    }
+}
+
+impl SimulationFailover {
+    /// Creates a failover to the main relay after 3 consecutive errors.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get the number of consecutive errors after which simulations fail
+    /// over.
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    /// Set the number of consecutive errors after which simulations fail
+    /// over.
+    pub fn set_threshold(mut self, errors: u32) -> Self {
+        self.threshold = errors.max(1);
+        self
+    }
+
+    /// Get the fallback simulation relays.
+    pub fn fallbacks(&self) -> &[Url] {
+        &self.fallbacks
+    }
+
+    /// Set the relays to simulate bundles on, in order, instead of the
+    /// main relay.
+    pub fn set_fallbacks(mut self, fallbacks: Vec<Url>) -> Self {
+        self.fallbacks = fallbacks;
+        self
+    }
+
+    /// Get the time after which the simulation relay is tried again.
+    pub fn cooldown(&self) -> Duration {
+        self.cooldown
+    }
+
+    /// Set the time after which the simulation relay is tried again.
+    pub fn set_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+/// A change in the relay used to simulate bundles.
+///
+/// See [`FlashbotsMiddleware::on_simulation_failover`](crate::FlashbotsMiddleware::on_simulation_failover).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SimulationFailoverEvent {
+    /// The simulation relay failed the given number of times in a row,
+    /// and bundles are simulated on the fallback relays.
+    FailedOver {
+        /// The number of consecutive errors.
+        errors: u32,
+    },
+    /// The simulation relay recovered, and bundles are simulated on it
+    /// again.
+    Recovered,
+}
+
+/// A callback for [`SimulationFailoverEvent`]s.
+#[derive(Clone)]
+pub(crate) struct SimulationFailoverHook(
+    pub(crate) Arc<dyn Fn(SimulationFailoverEvent) + Send + Sync>,
+);
+
+impl std::fmt::Debug for SimulationFailoverHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SimulationFailoverHook")
+    }
+}
+
+/// Tracks the health of the simulation relay for a [`SimulationFailover`].
+#[derive(Debug)]
+pub(crate) struct SimulationFailoverState<S> {
+    failover: SimulationFailover,
+    fallbacks: Vec<Relay<S>>,
+    health: Mutex<SimulationHealth>,
+}
+
+#[derive(Debug, Default)]
+struct SimulationHealth {
+    errors: u32,
+    failed_over_at: Option<Instant>,
+}
+
+impl<S> SimulationFailoverState<S> {
+    pub(crate) fn new(failover: SimulationFailover, fallbacks: Vec<Relay<S>>) -> Self {
+        Self {
+            failover,
+            fallbacks,
+            health: Default::default(),
+        }
+    }
+
+    pub(crate) fn failover(&self) -> &SimulationFailover {
+        &self.failover
+    }
+
+    /// Get the fallback relays, which are empty if simulations fail over
+    /// to the main relay.
+    pub(crate) fn fallbacks(&self) -> &[Relay<S>] {
+        &self.fallbacks
+    }
+
+    /// Whether the simulation relay should be used, i.e. it has not
+    /// failed over, or the cooldown has passed.
+    pub(crate) fn use_simulation_relay(&self) -> bool {
+        match self.health.lock().unwrap().failed_over_at {
+            Some(failed_over_at) => failed_over_at.elapsed() >= self.failover.cooldown,
+            None => true,
+        }
+    }
+
+    /// Record the result of a request to the simulation relay, returning
+    /// the resulting event (if any).
+    pub(crate) fn report(&self, healthy: bool) -> Option<SimulationFailoverEvent> {
+        let mut health = self.health.lock().unwrap();
+        if healthy {
+            health.errors = 0;
+            return health
+                .failed_over_at
+                .take()
+                .map(|_| SimulationFailoverEvent::Recovered);
+        }
+
+        health.errors += 1;
+        if health.failed_over_at.is_some() {
+            // The simulation relay is still failing after the cooldown
+            health.failed_over_at = Some(Instant::now());
+            None
+        } else if health.errors >= self.failover.threshold {
+            health.failed_over_at = Some(Instant::now());
+            Some(SimulationFailoverEvent::FailedOver {
+                errors: health.errors,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether a transport error means the endpoint could not be reached.
+///
+/// Errors of custom transports are always treated as such, except for
+/// oversized responses.
+pub(crate) fn is_unreachable(err: &TransportError) -> bool {
+    if err.is::<ResponseTooLarge>() {
+        return false;
+    }
+    #[cfg(feature = "reqwest-transport")]
+    if let Some(err) = err.downcast_ref::<ReqwestError>() {
+        return err.is_connect() || err.is_timeout();
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_order() {
+        let primary = Url::parse("http://primary").unwrap();
+        let fallback = Url::parse("http://fallback").unwrap();
+        let state = FailoverState::new(Failover::new(vec![fallback.clone()]));
+
+        assert_eq!(
+            state.endpoints(&primary),
+            vec![(0, &primary), (1, &fallback)]
+        );
+        state.report(0, false);
+        assert_eq!(
+            state.endpoints(&primary),
+            vec![(1, &fallback), (0, &primary)]
+        );
+        state.report(0, true);
+        assert_eq!(
+            state.endpoints(&primary),
+            vec![(0, &primary), (1, &fallback)]
+        );
+
+        // Failed endpoints are tried again after the cooldown
+        let state =
+            FailoverState::new(Failover::new(vec![fallback.clone()]).set_cooldown(Duration::ZERO));
+        state.report(0, false);
+        assert_eq!(
+            state.endpoints(&primary),
+            vec![(0, &primary), (1, &fallback)]
+        );
+    }
+
+    #[test]
+    fn simulation_failover() {
+        let state: SimulationFailoverState<()> =
+            SimulationFailoverState::new(SimulationFailover::new().set_threshold(2), Vec::new());
+
+        assert_eq!(state.report(false), None);
+        assert!(state.use_simulation_relay());
+        // Successful requests reset the error count
+        assert_eq!(state.report(true), None);
+        assert_eq!(state.report(false), None);
+        assert_eq!(
+            state.report(false),
+            Some(SimulationFailoverEvent::FailedOver { errors: 2 })
+        );
+        assert!(!state.use_simulation_relay());
+        assert_eq!(state.report(true), Some(SimulationFailoverEvent::Recovered));
+        assert!(state.use_simulation_relay());
+    }
+}
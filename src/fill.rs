@@ -0,0 +1,153 @@
+use ethers::{
+    core::types::{transaction::eip2718::TypedTransaction, Address, BlockNumber, U256},
+    providers::Middleware,
+};
+use std::collections::HashMap;
+
+/// Fill the missing fields of the transactions of a bundle using the given
+/// middleware, in the order of the bundle.
+///
+/// Unlike filling each transaction on its own, the fields are consistent
+/// across the bundle:
+///
+/// - All transactions pay the same fees, which are estimated once.
+/// - Transactions from the same sender get sequential nonces, starting at
+///   the pending nonce of the sender (or after the nonce of an earlier
+///   transaction in the bundle).
+///
+/// Transactions without a sender are sent from the default sender of the
+/// middleware (if any). Fields that are already set are kept as-is.
+///
+/// Note that gas is estimated against the latest state of the chain, so
+/// the gas of transactions that depend on earlier transactions in the
+/// bundle should be set beforehand.
+pub async fn fill_bundle<M: Middleware>(
+    middleware: &M,
+    txs: &mut [TypedTransaction],
+) -> Result<(), M::Error> {
+    let chain_id = if txs.iter().any(|tx| tx.chain_id().is_none()) {
+        Some(middleware.get_chainid().await?)
+    } else {
+        None
+    };
+    let eip1559_fees = if txs.iter().any(|tx| match tx {
+        TypedTransaction::Eip1559(tx) => {
+            tx.max_fee_per_gas.is_none() || tx.max_priority_fee_per_gas.is_none()
+        }
+        _ => false,
+    }) {
+        Some(middleware.estimate_eip1559_fees(None).await?)
+    } else {
+        None
+    };
+    let gas_price = if txs
+        .iter()
+        .any(|tx| !matches!(tx, TypedTransaction::Eip1559(_)) && tx.gas_price().is_none())
+    {
+        Some(middleware.get_gas_price().await?)
+    } else {
+        None
+    };
+
+    let mut nonces: HashMap<Address, U256> = HashMap::new();
+    for tx in txs.iter_mut() {
+        if tx.from().is_none() {
+            if let Some(sender) = middleware.default_sender() {
+                tx.set_from(sender);
+            }
+        }
+        if let Some(chain_id) = chain_id.filter(|_| tx.chain_id().is_none()) {
+            tx.set_chain_id(chain_id.as_u64());
+        }
+
+        match &mut *tx {
+            TypedTransaction::Eip1559(inner) => {
+                if let Some((max_fee, priority_fee)) = eip1559_fees {
+                    inner.max_fee_per_gas.get_or_insert(max_fee);
+                    inner.max_priority_fee_per_gas.get_or_insert(priority_fee);
+                }
+            }
+            _ => {
+                if let Some(gas_price) = gas_price.filter(|_| tx.gas_price().is_none()) {
+                    tx.set_gas_price(gas_price);
+                }
+            }
+        }
+
+        if let Some(sender) = tx.from().copied() {
+            let nonce = match tx.nonce().copied() {
+                Some(nonce) => nonce,
+                None => match nonces.get(&sender) {
+                    Some(next) => *next,
+                    None => {
+                        middleware
+                            .get_transaction_count(sender, Some(BlockNumber::Pending.into()))
+                            .await?
+                    }
+                },
+            };
+            tx.set_nonce(nonce);
+            nonces.insert(sender, nonce + 1);
+        }
+
+        if tx.gas().is_none() {
+            let gas = middleware.estimate_gas(tx, None).await?;
+            tx.set_gas(gas);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::{
+        core::types::{Eip1559TransactionRequest, TransactionRequest},
+        providers::Provider,
+    };
+
+    #[tokio::test]
+    async fn fill_bundle_nonces() {
+        let (provider, mock) = Provider::mocked();
+        let sender = Address::repeat_byte(0x1);
+        let tx = |nonce: Option<u64>| -> TypedTransaction {
+            let tx = TransactionRequest::new()
+                .from(sender)
+                .chain_id(1)
+                .gas(21000)
+                .gas_price(1);
+            match nonce {
+                Some(nonce) => tx.nonce(nonce).into(),
+                None => tx.into(),
+            }
+        };
+        let mut txs = vec![
+            tx(None),
+            tx(None),
+            tx(Some(10)),
+            tx(None),
+            Eip1559TransactionRequest::new()
+                .chain_id(1)
+                .gas(21000)
+                .max_fee_per_gas(2)
+                .max_priority_fee_per_gas(1)
+                .into(),
+        ];
+        // The pending nonce is fetched once
+        mock.push(U256::from(3)).unwrap();
+
+        fill_bundle(&provider, &mut txs).await.unwrap();
+        let nonces: Vec<_> = txs.iter().map(|tx| tx.nonce().copied()).collect();
+        assert_eq!(
+            nonces,
+            vec![
+                Some(3.into()),
+                Some(4.into()),
+                Some(10.into()),
+                Some(11.into()),
+                None
+            ]
+        );
+    }
+}
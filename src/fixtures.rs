@@ -0,0 +1,288 @@
+//! Fixtures shared by the unit tests of this crate.
+use crate::{
+    bundle::{BundleHash, SimulatedBundle, SimulatedTransaction},
+    transport::{HttpResponse, HttpTransport, TransportError},
+};
+use async_trait::async_trait;
+use ethers::{
+    core::types::{Address, TxHash},
+    signers::LocalWallet,
+};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "reqwest-transport")]
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    task::JoinHandle,
+};
+use url::Url;
+
+/// The wallet used to sign requests and transactions.
+pub(crate) fn wallet() -> LocalWallet {
+    "380eb0f3d505f087e438eca80bc4df9a7faa24f868e69fc0440261a0fc0567dc"
+        .parse()
+        .unwrap()
+}
+
+/// An `eth_callBundle` result with a transaction that failed, a transaction
+/// that returned a value and a transaction that created a contract.
+pub(crate) const SIMULATED_BUNDLE: &str = r#"{
+    "bundleGasPrice": "476190476193",
+    "bundleHash": "0x73b1e258c7a42fd0230b2fd05529c5d4b6fcb66c227783f8bece8aeacdd1db2e",
+    "coinbaseDiff": "20000000000126000",
+    "ethSentToCoinbase": "20000000000000000",
+    "gasFees": "126000",
+    "results": [
+      {
+        "coinbaseDiff": "10000000000063000",
+        "ethSentToCoinbase": "10000000000000000",
+        "fromAddress": "0x02A727155aeF8609c9f7F2179b2a1f560B39F5A0",
+        "gasFees": "63000",
+        "gasPrice": "476190476193",
+        "gasUsed": 21000,
+        "toAddress": "0x73625f59CAdc5009Cb458B751b3E7b6b48C06f2C",
+        "txHash": "0x669b4704a7d993a946cdd6e2f95233f308ce0c4649d2e04944e8299efcaa098a",
+        "value": "0x",
+        "error": "execution reverted",
+        "revert": "transfer failed"
+      },
+      {
+        "coinbaseDiff": "10000000000063000",
+        "ethSentToCoinbase": "10000000000000000",
+        "fromAddress": "0x02A727155aeF8609c9f7F2179b2a1f560B39F5A0",
+        "gasFees": "63000",
+        "gasPrice": "476190476193",
+        "gasUsed": 21000,
+        "toAddress": "0x73625f59CAdc5009Cb458B751b3E7b6b48C06f2C",
+        "txHash": "0xa839ee83465657cac01adc1d50d96c1b586ed498120a84a64749c0034b4f19fa",
+        "value": "0x01"
+      },
+      {
+        "coinbaseDiff": "0",
+        "ethSentToCoinbase": "0",
+        "fromAddress": "0x02A727155aeF8609c9f7F2179b2a1f560B39F5A0",
+        "gasFees": "0",
+        "gasPrice": "476190476193",
+        "gasUsed": 0,
+        "toAddress": "0x",
+        "txHash": "0x5fe7f977e71dba2ea1a68e21057beebb9be2ac30c6410aa38d4f3fbe41dcffd2",
+        "value": "0x"
+      }
+    ],
+    "stateBlockNumber": 5221585,
+    "totalGasUsed": 42000
+  }"#;
+
+/// A successful simulated transaction with the given hash, which used
+/// 21000 gas at a gas price of 3 wei.
+pub(crate) fn simulated_transaction(hash: TxHash) -> SimulatedTransaction {
+    SimulatedTransaction {
+        hash,
+        coinbase_diff: 63000.into(),
+        coinbase_tip: 0.into(),
+        gas_price: 3.into(),
+        gas_used: 21000.into(),
+        gas_fees: 63000.into(),
+        from: Address::zero(),
+        to: None,
+        value: None,
+        error: None,
+        revert: None,
+    }
+}
+
+/// A bundle of the given transactions simulated at block 10, with the
+/// totals of the transactions.
+pub(crate) fn simulated_bundle(transactions: Vec<SimulatedTransaction>) -> SimulatedBundle {
+    let sum = |field: fn(&SimulatedTransaction) -> ethers::core::types::U256| {
+        transactions.iter().map(field).fold(0.into(), |a, b| a + b)
+    };
+    let coinbase_diff = sum(|tx| tx.coinbase_diff);
+    let gas_used = sum(|tx| tx.gas_used);
+    SimulatedBundle {
+        hash: BundleHash::zero(),
+        coinbase_diff,
+        coinbase_tip: sum(|tx| tx.coinbase_tip),
+        gas_price: coinbase_diff.checked_div(gas_used).unwrap_or_default(),
+        gas_used,
+        gas_fees: sum(|tx| tx.gas_fees),
+        simulation_block: 10.into(),
+        transactions,
+    }
+}
+
+/// A transport that responds to every request with the given status and
+/// body.
+#[derive(Debug)]
+pub(crate) struct StaticTransport(pub(crate) u16, pub(crate) &'static str);
+
+#[async_trait]
+impl HttpTransport for StaticTransport {
+    async fn post(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+        _body: String,
+    ) -> Result<HttpResponse, TransportError> {
+        self.get(url, headers).await
+    }
+
+    async fn get(
+        &self,
+        _url: &Url,
+        _headers: &[(&str, &str)],
+    ) -> Result<HttpResponse, TransportError> {
+        Ok(HttpResponse {
+            status: self.0,
+            body: self.1.to_string(),
+        })
+    }
+}
+
+/// A request received by a [`MockTransport`].
+#[derive(Debug, Clone)]
+pub(crate) struct MockRequest {
+    pub(crate) url: Url,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: Value,
+}
+
+impl MockRequest {
+    /// Get the value of the header with the given name (if any).
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A transport that records the requests sent to it.
+///
+/// Requests to unreachable URLs fail with a transport error, and all other
+/// requests succeed with the result of the transport. `POST` requests are
+/// answered with a JSON-RPC response, and `GET` requests with the result
+/// itself.
+#[derive(Debug)]
+pub(crate) struct MockTransport {
+    result: Value,
+    unreachable: Mutex<Vec<Url>>,
+    requests: Mutex<Vec<MockRequest>>,
+}
+
+impl MockTransport {
+    pub(crate) fn new(result: Value) -> Arc<Self> {
+        Arc::new(Self {
+            result,
+            unreachable: Mutex::new(Vec::new()),
+            requests: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// A transport that responds to bundles with the bundle hash `0x..01`.
+    pub(crate) fn bundles() -> Arc<Self> {
+        Self::new(json!({ "bundleHash": BundleHash::from_low_u64_be(1) }))
+    }
+
+    /// Fail requests to the given URLs.
+    pub(crate) fn set_unreachable(&self, urls: Vec<Url>) {
+        *self.unreachable.lock().unwrap() = urls;
+    }
+
+    /// Get the requests received so far.
+    pub(crate) fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Get and clear the requests received so far.
+    pub(crate) fn take_requests(&self) -> Vec<MockRequest> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+
+    fn record(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+        body: Value,
+    ) -> Result<(), TransportError> {
+        self.requests.lock().unwrap().push(MockRequest {
+            url: url.clone(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            body,
+        });
+        if self.unreachable.lock().unwrap().contains(url) {
+            return Err(format!("{} is unreachable", url).into());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HttpTransport for MockTransport {
+    async fn post(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+        body: String,
+    ) -> Result<HttpResponse, TransportError> {
+        let body: Value = serde_json::from_str(&body)?;
+        let id = body["id"].clone();
+        self.record(url, headers, body)?;
+        Ok(HttpResponse {
+            status: 200,
+            body: json!({ "id": id, "jsonrpc": "2.0", "result": self.result }).to_string(),
+        })
+    }
+
+    async fn get(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+    ) -> Result<HttpResponse, TransportError> {
+        self.record(url, headers, Value::Null)?;
+        Ok(HttpResponse {
+            status: 200,
+            body: self.result.to_string(),
+        })
+    }
+}
+
+#[cfg(feature = "reqwest-transport")]
+/// An HTTP response with the given status line (e.g. `200 OK`) and JSON
+/// body.
+pub(crate) fn json_response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+#[cfg(feature = "reqwest-transport")]
+/// Serves the given raw HTTP responses to consecutive connections, closing
+/// each connection after the response.
+///
+/// Returns the URL of the server, and a handle that resolves to the
+/// requests the server received once all responses were sent.
+pub(crate) async fn serve(responses: Vec<String>) -> (Url, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+    let server = tokio::spawn(async move {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 8192];
+            let len = socket.read(&mut buf).await.unwrap_or(0);
+            requests.push(String::from_utf8_lossy(&buf[..len]).into_owned());
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+        requests
+    });
+
+    (url, server)
+}
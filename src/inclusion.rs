@@ -0,0 +1,162 @@
+use crate::{
+    bundle::{serialize_uuid_as_string, BundleHash},
+    transport::{default_transport, HttpTransport},
+};
+use ethers::core::types::{TransactionReceipt, TxHash, U64};
+use serde::Serialize;
+use std::{fmt, future::Future, pin::Pin, sync::Arc};
+use url::Url;
+use uuid::Uuid;
+
+/// Whether the transactions of a bundle were included in its target block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InclusionStatus {
+    /// The bundle was included.
+    Included,
+    /// Some, but not all, transactions of the bundle were included, e.g.
+    /// because they were also sent to the public mempool.
+    PartiallyIncluded,
+    /// None of the transactions of the bundle were included.
+    NotIncluded,
+}
+
+/// The outcome of a pending bundle, passed to [`InclusionHook`]s.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InclusionEvent {
+    /// Whether the bundle was included.
+    pub status: InclusionStatus,
+    /// The bundle hash returned by the relay (if any).
+    pub bundle_hash: Option<BundleHash>,
+    /// The client-side tracking uuid of the bundle (if any).
+    #[serde(serialize_with = "serialize_uuid_as_string")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking_uuid: Option<Uuid>,
+    /// The target block of the bundle.
+    pub block: U64,
+    /// The hashes of the transactions of the bundle.
+    pub transactions: Vec<TxHash>,
+    /// The receipts of the transactions of the bundle that were included
+    /// in the target block.
+    pub receipts: Vec<TransactionReceipt>,
+}
+
+type HookFn = dyn Fn(InclusionEvent) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync;
+
+/// An async callback that is called when a pending bundle resolves.
+///
+/// Hooks are called once the inclusion of the bundle has been checked,
+/// before the pending bundle resolves. They are not called if the pending
+/// bundle is cancelled, or if it resolves with a provider error.
+///
+/// ```no_run
+/// # use ethers::providers::{Provider, Http};
+/// # use ethers_flashbots::{InclusionHook, InclusionStatus, PendingBundle};
+/// # use url::Url;
+/// # async fn hooks(pending: PendingBundle<'_, Http>) {
+/// let pending = pending
+///     .on_inclusion(InclusionHook::new(|event| async move {
+///         if event.status != InclusionStatus::Included {
+///             println!("Bundle for block {} was not included", event.block);
+///         }
+///     }))
+///     .on_inclusion(InclusionHook::webhook(
+///         Url::parse("https://alerts.example.com/bundles").unwrap(),
+///     ));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct InclusionHook(Arc<HookFn>);
+
+impl InclusionHook {
+    /// Creates a hook that calls the given callback.
+    pub fn new<F, Fut>(hook: F) -> Self
+    where
+        F: Fn(InclusionEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        Self(Arc::new(move |event| Box::pin(hook(event))))
+    }
+
+    /// Creates a hook that posts the event as JSON to the given URL.
+    ///
+    /// Failed requests are logged, and not retried.
+    ///
+    /// Requests are sent using a new [`reqwest::Client`], unless the
+    /// `reqwest-transport` feature is disabled, in which case
+    /// [`InclusionHook::webhook_with_transport`] has to be used.
+    pub fn webhook(url: Url) -> Self {
+        Self::webhook_with_transport(url, default_transport())
+    }
+
+    /// Creates a hook that posts the event as JSON to the given URL using
+    /// the given HTTP transport.
+    ///
+    /// See [`InclusionHook::webhook`] and [`HttpTransport`].
+    pub fn webhook_with_transport(url: Url, transport: Arc<dyn HttpTransport>) -> Self {
+        Self::new(move |event| {
+            let url = url.clone();
+            let transport = transport.clone();
+            async move {
+                let res = match serde_json::to_string(&event) {
+                    Ok(body) => transport
+                        .post(&url, &[("content-type", "application/json")], body)
+                        .await
+                        .map_err(|err| err.to_string()),
+                    Err(err) => Err(err.to_string()),
+                };
+                let error = match res {
+                    Ok(res) if (200..300).contains(&res.status) => return,
+                    Ok(res) => format!("Webhook responded with status {}", res.status),
+                    Err(err) => err,
+                };
+                tracing::warn!(
+                    target: "ethers_flashbots::inclusion",
+                    error = %error,
+                    "Inclusion webhook failed"
+                );
+            }
+        })
+    }
+
+    pub(crate) async fn call(&self, event: InclusionEvent) {
+        (self.0)(event).await
+    }
+}
+
+impl fmt::Debug for InclusionHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("InclusionHook")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::MockTransport;
+
+    #[tokio::test]
+    async fn webhook_posts_event() {
+        let transport = MockTransport::new(serde_json::Value::Null);
+        let url = Url::parse("https://alerts.example.com/bundles").unwrap();
+        let hook = InclusionHook::webhook_with_transport(url.clone(), transport.clone());
+
+        hook.call(InclusionEvent {
+            status: InclusionStatus::NotIncluded,
+            bundle_hash: None,
+            tracking_uuid: None,
+            block: 10.into(),
+            transactions: vec![TxHash::repeat_byte(0x1)],
+            receipts: Vec::new(),
+        })
+        .await;
+
+        let requests = transport.take_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url, url);
+        assert_eq!(requests[0].header("content-type"), Some("application/json"));
+        assert_eq!(requests[0].body["status"], "notIncluded");
+        assert_eq!(requests[0].body["block"], "0xa");
+    }
+}
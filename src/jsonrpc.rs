@@ -1,14 +1,17 @@
 // Code adapted from: https://github.com/althea-net/guac_rs/tree/master/web3/src/jsonrpc
 // NOTE: This module only exists since there is no way to use the data structures
 // in the `ethers-providers/src/transports/common.rs` from another crate.
-use ethers::core::types::U256;
+use crate::error::ErrorKind;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fmt;
 use thiserror::Error;
 
-/// A JSON-RPC 2.0 error
-#[derive(Serialize, Deserialize, Debug, Clone, Error)]
+/// A JSON-RPC 2.0 error returned by a relay.
+///
+/// Well-known error codes are available as associated constants, e.g.
+/// [`JsonRpcError::LIMIT_EXCEEDED`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Error)]
 pub struct JsonRpcError {
     /// The error code
     pub code: i64,
@@ -18,6 +21,52 @@ pub struct JsonRpcError {
     pub data: Option<Value>,
 }
 
+impl JsonRpcError {
+    /// The request is not valid JSON.
+    pub const PARSE_ERROR: i64 = -32700;
+    /// The request is not a valid JSON-RPC request.
+    pub const INVALID_REQUEST: i64 = -32600;
+    /// The method does not exist or is not supported by the relay.
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    /// The parameters of the request are invalid, e.g. a bundle is
+    /// missing required fields.
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// An internal error occurred in the relay.
+    pub const INTERNAL_ERROR: i64 = -32603;
+    /// The request was rejected by the relay, e.g. because the bundle
+    /// failed validation or the signature header is invalid.
+    pub const SERVER_ERROR: i64 = -32000;
+    /// The requested resource was not found, e.g. an unknown bundle.
+    pub const RESOURCE_NOT_FOUND: i64 = -32001;
+    /// The requested resource is not available (yet).
+    pub const RESOURCE_UNAVAILABLE: i64 = -32002;
+    /// The transaction was rejected.
+    pub const TRANSACTION_REJECTED: i64 = -32003;
+    /// The method is not supported.
+    pub const METHOD_NOT_SUPPORTED: i64 = -32004;
+    /// The request exceeds a limit of the relay, e.g. a rate limit.
+    pub const LIMIT_EXCEEDED: i64 = -32005;
+
+    /// Whether the relay rejected the request because it exceeded a
+    /// limit, e.g. a rate limit.
+    pub fn is_limit_exceeded(&self) -> bool {
+        self.code == Self::LIMIT_EXCEEDED
+    }
+
+    /// Get the kind of the error.
+    ///
+    /// Exceeded limits are [`ErrorKind::LimitExceeded`], so requests
+    /// rejected by a rate limit are retryable. All other errors are
+    /// [`ErrorKind::Relay`].
+    pub fn kind(&self) -> ErrorKind {
+        if self.is_limit_exceeded() {
+            ErrorKind::LimitExceeded
+        } else {
+            ErrorKind::Relay
+        }
+    }
+}
+
 impl fmt::Display for JsonRpcError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -42,20 +91,6 @@ pub struct Request<'a, T> {
     params: T,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-/// A JSON-RPC Notifcation
-pub struct Notification<R> {
-    jsonrpc: String,
-    method: String,
-    pub params: Subscription<R>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Subscription<R> {
-    pub subscription: U256,
-    pub result: R,
-}
-
 impl<'a, T> Request<'a, T> {
     /// Creates a new JSON RPC request
     pub fn new(id: u64, method: &'a str, params: T) -> Self {
@@ -113,6 +148,21 @@ mod tests {
         assert_eq!(response.data.into_result().unwrap(), None);
     }
 
+    #[test]
+    fn deser_error_response() {
+        let response: Response<u64> = serde_json::from_str(
+            r#"{"jsonrpc": "2.0", "id": 1, "error": {"code": -32005, "message": "rate limited"}}"#,
+        )
+        .unwrap();
+        let err = response.data.into_result().unwrap_err();
+        assert_eq!(err.code, JsonRpcError::LIMIT_EXCEEDED);
+        assert_eq!(err.message, "rate limited");
+        assert_eq!(err.data, None);
+        assert!(err.is_limit_exceeded());
+        assert_eq!(err.kind(), ErrorKind::LimitExceeded);
+        assert!(err.kind().is_retryable());
+    }
+
     #[test]
     fn ser_request() {
         let request: Request<()> = Request::new(300, "method_name", ());
@@ -8,20 +8,128 @@
 mod bundle;
 pub use bundle::{
     BundleHash, BundleRequest, BundleStats, BundleTransaction, SimulatedBundle,
-    SimulatedTransaction,
+    SimulatedTransaction, TransactionDecodeError, TransactionGasReport, TxOptions, TxOptionsError,
 };
 
+mod basefee;
+pub use basefee::{next_block_basefee, BasefeeEscalation, SimulationBasefeeError};
+
+pub mod builders;
+
+mod capabilities;
+pub use capabilities::BuilderCapabilities;
+
+mod error;
+pub use error::ErrorKind;
+
+mod dedupe;
+pub use dedupe::DuplicatePolicy;
+
+mod dialect;
+pub use dialect::{BloxrouteNetwork, RelayDialect};
+
+mod inclusion;
+pub use inclusion::{InclusionEvent, InclusionHook, InclusionStatus};
+
+mod network;
+pub use network::Network;
+
+mod nonce;
+pub use nonce::BundleNonceManager;
+
+mod payment;
+pub use payment::{coinbase_payment, ProfitBid, COINBASE_PAYMENT_GAS};
+
 mod pending_bundle;
-pub use pending_bundle::{PendingBundle, PendingBundleError};
+pub use pending_bundle::{
+    CancelHandle, InclusionCheck, OwnedPendingBundle, PendingBundle, PendingBundleError,
+};
 
 mod user;
 pub use user::UserStats;
 
+mod user_tracker;
+pub use user_tracker::{UserStatsDelta, UserStatsTracker};
+
+pub mod mev_share;
+
 mod middleware;
 pub use middleware::{BroadcasterMiddleware, FlashbotsMiddleware, FlashbotsMiddlewareError};
 
+mod queue;
+pub use queue::BundleQueue;
+
+mod record;
+pub use record::{BundleRecord, BundleRecordError};
+
+mod relay_data;
+pub use relay_data::{
+    DeliveredPayload, RelayDataClient, RelayDataError, SignedValidatorRegistration,
+    ValidatorRegistration, ValidatorRegistrationMessage,
+};
+
+mod reputation;
+pub use reputation::{ReputationAlert, ReputationWatcher};
+
+mod resubmit;
+pub use resubmit::{BundleResubmitter, PayloadDelivery, ResubmissionError, ResubmissionOutcome};
+
+mod scheduler;
+pub use scheduler::{SlotScheduler, MAINNET_GENESIS_TIME, SLOT_DURATION};
+
+mod simulation;
+pub use simulation::SimulationResult;
+
+pub mod templates;
+
+mod signer;
+pub use signer::SharedSigner;
+
+mod spend;
+pub use spend::SpendLimit;
+
+mod tip;
+pub use tip::{IncludedBundle, TipError, TipSuggestion, FLASHBOTS_BLOCKS_API_URL};
+
+mod units;
+pub use units::WeiExt;
+
+mod fill;
+pub use fill::fill_bundle;
+
 mod jsonrpc;
+pub use jsonrpc::JsonRpcError;
+
+mod logging;
+pub use logging::{RequestLogging, TransactionRedaction};
+
+mod circuit_breaker;
+pub use circuit_breaker::CircuitBreaker;
+
+mod failover;
+pub use failover::{Failover, SimulationFailover, SimulationFailoverEvent};
+
+mod limits;
+pub use limits::BundleLimits;
+
+mod rate_limit;
+pub use rate_limit::RateLimit;
+
+mod relay_stats;
+pub use relay_stats::RelayStats;
+
 mod relay;
-pub use relay::{Relay, RelayError};
+pub use relay::{
+    Relay, RelayError, RelayResponse, RequestIdPolicy, SignatureScheme, SignedRequest,
+};
+
+mod transport;
+pub use transport::{HttpResponse, HttpTransport, NoTransport, ResponseTooLarge, TransportError};
+
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+
+#[cfg(test)]
+mod fixtures;
 
 mod utils;
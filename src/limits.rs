@@ -0,0 +1,50 @@
+/// Limits on bundles submitted to a relay.
+///
+/// Relays silently drop payloads that are too large, so bundles that
+/// exceed the limits of a relay are rejected before they are sent with
+/// [`FlashbotsMiddlewareError::BundleTooLarge`](crate::FlashbotsMiddlewareError::BundleTooLarge)
+/// or [`FlashbotsMiddlewareError::TooManyTransactions`](crate::FlashbotsMiddlewareError::TooManyTransactions).
+///
+/// See [`Relay::set_bundle_limits`](crate::Relay::set_bundle_limits).
+///
+/// ```
+/// use ethers_flashbots::BundleLimits;
+///
+/// let limits = BundleLimits::new()
+///     .set_max_size(512 * 1024)
+///     .set_max_transactions(100);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BundleLimits {
+    max_size: Option<usize>,
+    max_transactions: Option<usize>,
+}
+
+impl BundleLimits {
+    /// Creates bundle limits without any limits.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get the maximum size of the serialized request, in bytes (if any).
+    pub fn max_size(&self) -> Option<usize> {
+        self.max_size
+    }
+
+    /// Set the maximum size of the serialized request, in bytes.
+    pub fn set_max_size(mut self, bytes: usize) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Get the maximum number of transactions in a bundle (if any).
+    pub fn max_transactions(&self) -> Option<usize> {
+        self.max_transactions
+    }
+
+    /// Set the maximum number of transactions in a bundle.
+    pub fn set_max_transactions(mut self, transactions: usize) -> Self {
+        self.max_transactions = Some(transactions);
+        self
+    }
+}
@@ -0,0 +1,197 @@
+use crate::bundle::BundleTransaction;
+use ethers::core::{
+    types::{Bytes, H256},
+    utils::keccak256,
+};
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+/// How transactions are redacted in logged requests.
+///
+/// See [`RequestLogging`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransactionRedaction {
+    /// Log raw transactions in full.
+    Full,
+    /// Log the first bytes of raw transactions, up to the given length.
+    Truncate(usize),
+    /// Log the decoded transactions, without their calldata.
+    ///
+    /// Transactions that cannot be decoded are logged by hash.
+    #[default]
+    HideCalldata,
+    /// Only log the hashes of transactions.
+    Hash,
+}
+
+/// Logging of outgoing requests to a relay.
+///
+/// When enabled using [`Relay::set_logging`](crate::Relay::set_logging),
+/// each request is emitted as a [`tracing`](https://docs.rs/tracing) event
+/// at the `INFO` level with the target `ethers_flashbots::relay` before
+/// it is sent, with the transactions in the request redacted.
+///
+/// ```
+/// use ethers_flashbots::{RequestLogging, TransactionRedaction};
+///
+/// let logging = RequestLogging::new().set_transactions(TransactionRedaction::Truncate(8));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestLogging {
+    transactions: TransactionRedaction,
+}
+
+impl RequestLogging {
+    /// Creates a logging configuration that hides transaction calldata.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get how transactions are redacted.
+    pub fn transactions(&self) -> TransactionRedaction {
+        self.transactions
+    }
+
+    /// Set how transactions are redacted.
+    pub fn set_transactions(mut self, redaction: TransactionRedaction) -> Self {
+        self.transactions = redaction;
+        self
+    }
+
+    /// Redact the transactions in a serialized request.
+    pub(crate) fn redact(&self, method: &str, body: &str) -> Value {
+        let mut request: Value = match serde_json::from_str(body) {
+            Ok(request) => request,
+            Err(_) => return Value::from(body),
+        };
+        if self.transactions != TransactionRedaction::Full {
+            if let Some(params) = request.get_mut("params") {
+                if method == "eth_sendRawTransaction" {
+                    self.redact_transactions(params);
+                } else {
+                    self.redact_value(params);
+                }
+            }
+        }
+        request
+    }
+
+    /// Redacts transactions in fields of well-known names.
+    fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::Object(fields) => {
+                for (key, value) in fields.iter_mut() {
+                    match key.as_str() {
+                        "txs" | "tx" | "transaction" => self.redact_transactions(value),
+                        _ => self.redact_value(value),
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|value| self.redact_value(value)),
+            _ => {}
+        }
+    }
+
+    /// Redacts a raw transaction, or an array of raw transactions.
+    fn redact_transactions(&self, value: &mut Value) {
+        match value {
+            Value::Array(values) => values
+                .iter_mut()
+                .for_each(|value| self.redact_transactions(value)),
+            Value::String(tx) => {
+                if let Ok(tx) = Bytes::from_str(tx) {
+                    *value = self.redact_transaction(tx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn redact_transaction(&self, tx: Bytes) -> Value {
+        let hash = H256::from(keccak256(&tx));
+        match self.transactions {
+            TransactionRedaction::Full => Value::from(format!("{}", tx)),
+            TransactionRedaction::Truncate(len) if tx.len() <= len => {
+                Value::from(format!("{}", tx))
+            }
+            TransactionRedaction::Truncate(len) => Value::from(format!(
+                "{}... ({} bytes)",
+                Bytes::from(tx[..len].to_vec()),
+                tx.len()
+            )),
+            TransactionRedaction::HideCalldata => match BundleTransaction::Raw(tx).decode() {
+                Ok(tx) => json!({
+                    "hash": tx.hash,
+                    "from": tx.from,
+                    "to": tx.to,
+                    "nonce": tx.nonce,
+                    "value": tx.value,
+                    "gas": tx.gas,
+                    "inputLength": tx.input.len(),
+                }),
+                Err(_) => json!({ "hash": hash }),
+            },
+            TransactionRedaction::Hash => json!({ "hash": hash }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::wallet;
+    use ethers::core::types::{
+        transaction::eip2718::TypedTransaction, Address, Eip1559TransactionRequest,
+    };
+    use ethers::signers::Signer;
+
+    #[test]
+    fn redact_request() {
+        let wallet = wallet();
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(Address::repeat_byte(0x11))
+            .data(vec![0x12, 0x34, 0x56])
+            .nonce(7)
+            .chain_id(1)
+            .into();
+        let signature = wallet.sign_transaction_sync(&tx).unwrap();
+        let raw = tx.rlp_signed(&signature);
+        let hash = tx.hash(&signature);
+
+        let body = json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "eth_sendBundle",
+            "params": [{ "txs": [raw], "blockNumber": "0x1" }]
+        })
+        .to_string();
+
+        let full = RequestLogging::new().set_transactions(TransactionRedaction::Full);
+        assert_eq!(
+            full.redact("eth_sendBundle", &body),
+            serde_json::from_str::<Value>(&body).unwrap()
+        );
+
+        let truncated = RequestLogging::new().set_transactions(TransactionRedaction::Truncate(2));
+        assert_eq!(
+            truncated.redact("eth_sendBundle", &body)["params"][0]["txs"][0],
+            json!(format!(
+                "{}... ({} bytes)",
+                Bytes::from(raw[..2].to_vec()),
+                raw.len()
+            ))
+        );
+
+        let hidden = RequestLogging::new().redact("eth_sendBundle", &body);
+        let logged = &hidden["params"][0]["txs"][0];
+        assert_eq!(logged["hash"], json!(hash));
+        assert_eq!(logged["from"], json!(wallet.address()));
+        assert_eq!(logged["inputLength"], json!(3));
+        assert_eq!(hidden["params"][0]["blockNumber"], json!("0x1"));
+
+        let hashed = RequestLogging::new()
+            .set_transactions(TransactionRedaction::Hash)
+            .redact("eth_sendBundle", &body);
+        assert_eq!(hashed["params"][0]["txs"][0], json!({ "hash": hash }));
+    }
+}
@@ -0,0 +1,514 @@
+use crate::{bundle::BundleTransaction, mev_share::Hint, mev_share::Privacy};
+use ethers::core::types::{Address, Bytes, H256, U64};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// An item in the body of a [`MevShareBundle`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BundleItem {
+    /// A transaction referenced by its hash.
+    ///
+    /// This is used to backrun transactions and bundles that were shared
+    /// through the MEV-Share event stream, whose signed transactions are
+    /// not known to searchers.
+    Hash {
+        /// The hash of the transaction or bundle.
+        hash: H256,
+    },
+    /// A signed transaction.
+    #[serde(rename_all = "camelCase")]
+    Tx {
+        /// The RLP encoded signed transaction.
+        tx: Bytes,
+        /// Whether the bundle is still valid if the transaction reverts.
+        can_revert: bool,
+    },
+    /// A nested bundle.
+    ///
+    /// See [`MevShareBundle::push_bundle`].
+    Bundle {
+        /// The nested bundle.
+        bundle: Box<MevShareBundle>,
+    },
+}
+
+/// The maximum depth of nested bundles accepted by the matchmaker.
+///
+/// A bundle with a nested bundle has a depth of 1, and nested bundles
+/// can not have nested bundles of their own.
+pub const MAX_BUNDLE_DEPTH: usize = 1;
+
+/// A refund for the value extracted by a backrun of a body item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Refund {
+    /// The index of the body item the refund is for.
+    pub body_idx: u64,
+    /// The percentage of the extracted value that is refunded.
+    pub percent: u64,
+}
+
+/// How a refund is split between recipients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefundConfig {
+    /// The recipient of the refund.
+    pub address: Address,
+    /// The percentage of the refund that is paid to the recipient.
+    pub percent: u64,
+}
+
+/// Errors for invalid MEV-Share bundles.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MevShareBundleError {
+    /// A refund is for a body item that does not exist.
+    #[error("Refund for body item {0}, but the bundle only has {1} items")]
+    InvalidRefundIndex(u64, usize),
+    /// The refund percentages are above 100.
+    #[error("Refund percentages add up to more than 100")]
+    InvalidRefundPercent,
+    /// The bundle has too many levels of nested bundles.
+    #[error("Bundles can be nested at most {MAX_BUNDLE_DEPTH} levels deep")]
+    TooDeep,
+}
+
+/// A bundle that can be submitted to the MEV-Share matchmaker.
+///
+/// Unlike a [`BundleRequest`](crate::BundleRequest), a MEV-Share bundle
+/// can be included in a range of blocks, and parts of it can be shared
+/// with searchers using [privacy hints](Hint) in exchange for a refund of
+/// the value they extract.
+///
+/// See [`mev_sendBundle`][mev_send_bundle] for more information.
+///
+/// [mev_send_bundle]: https://docs.flashbots.net/flashbots-auction/advanced/rpc-endpoint#mev_sendbundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MevShareBundle {
+    version: String,
+    inclusion: Inclusion,
+    body: Vec<BundleItem>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Validity::is_empty")]
+    validity: Validity,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Privacy::is_empty")]
+    privacy: Privacy,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Validity {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    refund: Vec<Refund>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    refund_config: Vec<RefundConfig>,
+}
+
+impl Validity {
+    fn is_empty(&self) -> bool {
+        self.refund.is_empty() && self.refund_config.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Inclusion {
+    block: Option<U64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_block: Option<U64>,
+}
+
+impl Default for MevShareBundle {
+    fn default() -> Self {
+        Self {
+            version: "v0.1".into(),
+            inclusion: Default::default(),
+            body: Vec::new(),
+            validity: Default::default(),
+            privacy: Default::default(),
+        }
+    }
+}
+
+impl MevShareBundle {
+    /// Creates an empty MEV-Share bundle.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates a bundle that backruns the transaction or bundle with the
+    /// given hash in the given block.
+    ///
+    /// The hash is usually that of an [`Event`](crate::mev_share::Event)
+    /// from the MEV-Share event stream.
+    pub fn backrun<T: Into<BundleTransaction>>(target_hash: H256, tx: T, block: U64) -> Self {
+        Self::new()
+            .push_transaction_hash(target_hash)
+            .push_transaction(tx)
+            .set_block(block)
+    }
+
+    /// Get the version of the bundle API.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Adds a transaction to the bundle.
+    pub fn push_transaction<T: Into<BundleTransaction>>(self, tx: T) -> Self {
+        self.push_item(BundleItem::Tx {
+            tx: tx.into().rlp(),
+            can_revert: false,
+        })
+    }
+
+    /// Adds a transaction that is allowed to revert to the bundle.
+    pub fn push_revertible_transaction<T: Into<BundleTransaction>>(self, tx: T) -> Self {
+        self.push_item(BundleItem::Tx {
+            tx: tx.into().rlp(),
+            can_revert: true,
+        })
+    }
+
+    /// Adds a transaction or bundle from the MEV-Share event stream to the
+    /// bundle, referenced by its hash.
+    ///
+    /// A backrun is constructed by adding the hash of the event, followed
+    /// by the backrunning transactions.
+    pub fn push_transaction_hash(self, hash: H256) -> Self {
+        self.push_item(BundleItem::Hash { hash })
+    }
+
+    /// Adds a nested bundle to the bundle.
+    ///
+    /// Nested bundles allow composing bundles from multiple parties, e.g.
+    /// backrunning a bundle as a whole. The nested bundle has its own
+    /// refunds and privacy hints, but its inclusion is determined by the
+    /// outer bundle.
+    pub fn push_bundle(self, bundle: MevShareBundle) -> Self {
+        self.push_item(BundleItem::Bundle {
+            bundle: Box::new(bundle),
+        })
+    }
+
+    /// Adds an item to the body of the bundle.
+    pub fn push_item(mut self, item: BundleItem) -> Self {
+        self.body.push(item);
+        self
+    }
+
+    /// Get a reference to the body of the bundle.
+    pub fn body(&self) -> &Vec<BundleItem> {
+        &self.body
+    }
+
+    /// Get the first block the bundle can be included in (if any).
+    pub fn block(&self) -> Option<U64> {
+        self.inclusion.block
+    }
+
+    /// Set the first block the bundle can be included in.
+    pub fn set_block(mut self, block: U64) -> Self {
+        self.inclusion.block = Some(block);
+        self
+    }
+
+    /// Get the last block the bundle can be included in (if any).
+    pub fn max_block(&self) -> Option<U64> {
+        self.inclusion.max_block
+    }
+
+    /// Set the last block the bundle can be included in.
+    ///
+    /// If this is not set, the bundle is only valid for the first block.
+    pub fn set_max_block(mut self, block: U64) -> Self {
+        self.inclusion.max_block = Some(block);
+        self
+    }
+
+    /// Get a reference to the refunds of the bundle.
+    pub fn refunds(&self) -> &Vec<Refund> {
+        &self.validity.refund
+    }
+
+    /// Set the refunds of the bundle.
+    ///
+    /// Each refund specifies the percentage of the value extracted by
+    /// backruns of a body item that is refunded to the sender of that item.
+    pub fn set_refunds(mut self, refunds: Vec<Refund>) -> Self {
+        self.validity.refund = refunds;
+        self
+    }
+
+    /// Get a reference to the refund configuration of the bundle.
+    pub fn refund_config(&self) -> &Vec<RefundConfig> {
+        &self.validity.refund_config
+    }
+
+    /// Set how refunds for this bundle are split between recipients.
+    ///
+    /// If this is not set, refunds are paid to the sender of the first
+    /// transaction in the bundle.
+    pub fn set_refund_config(mut self, config: Vec<RefundConfig>) -> Self {
+        self.validity.refund_config = config;
+        self
+    }
+
+    /// Check that the bundle is valid.
+    ///
+    /// Refunds must be for items in the body of the bundle, and the refund
+    /// percentages (and the refund configuration percentages) must not add
+    /// up to more than 100. Bundles can not be nested deeper than
+    /// [`MAX_BUNDLE_DEPTH`].
+    pub fn validate(&self) -> Result<(), MevShareBundleError> {
+        if self.depth() > MAX_BUNDLE_DEPTH {
+            return Err(MevShareBundleError::TooDeep);
+        }
+
+        self.validate_refunds()?;
+        for item in &self.body {
+            if let BundleItem::Bundle { bundle } = item {
+                bundle.validate_refunds()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The depth of nested bundles in this bundle.
+    ///
+    /// A bundle without nested bundles has a depth of 0.
+    pub fn depth(&self) -> usize {
+        self.body
+            .iter()
+            .map(|item| match item {
+                BundleItem::Bundle { bundle } => bundle.depth() + 1,
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn validate_refunds(&self) -> Result<(), MevShareBundleError> {
+        for refund in &self.validity.refund {
+            if refund.body_idx >= self.body.len() as u64 {
+                return Err(MevShareBundleError::InvalidRefundIndex(
+                    refund.body_idx,
+                    self.body.len(),
+                ));
+            }
+        }
+
+        let refund_percent: u64 = self.validity.refund.iter().map(|r| r.percent).sum();
+        let config_percent: u64 = self.validity.refund_config.iter().map(|r| r.percent).sum();
+        if refund_percent > 100 || config_percent > 100 {
+            return Err(MevShareBundleError::InvalidRefundPercent);
+        }
+
+        Ok(())
+    }
+
+    /// Get a reference to the privacy hints of the bundle.
+    pub fn hints(&self) -> &Vec<Hint> {
+        &self.privacy.hints
+    }
+
+    /// Set the privacy hints of the bundle.
+    ///
+    /// If no hints are set, nothing about the bundle is shared with
+    /// searchers.
+    pub fn set_hints(mut self, hints: Vec<Hint>) -> Self {
+        self.privacy.hints = hints;
+        self
+    }
+
+    /// Get a reference to the builders the bundle is sent to.
+    pub fn builders(&self) -> &Vec<String> {
+        &self.privacy.builders
+    }
+
+    /// Set the builders the matchmaker sends the bundle to, by name (e.g.
+    /// `flashbots`).
+    ///
+    /// If no builders are set, the bundle is only sent to the Flashbots
+    /// builder.
+    pub fn set_builders(mut self, builders: Vec<String>) -> Self {
+        self.privacy.builders = builders;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backrun_bundle() {
+        let bundle =
+            MevShareBundle::backrun(H256::repeat_byte(0x1), Bytes::from(vec![0x2]), 10.into());
+
+        assert_eq!(bundle.block(), Some(10.into()));
+        assert_eq!(
+            bundle.body(),
+            &vec![
+                BundleItem::Hash {
+                    hash: H256::repeat_byte(0x1)
+                },
+                BundleItem::Tx {
+                    tx: Bytes::from(vec![0x2]),
+                    can_revert: false
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn mev_share_bundle_serialize() {
+        let bundle = MevShareBundle::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .push_revertible_transaction(Bytes::from(vec![0x2]))
+            .set_block(1.into())
+            .set_max_block(3.into())
+            .set_hints(vec![Hint::Calldata, Hint::ContractAddress, Hint::TxHash]);
+
+        assert_eq!(
+            &serde_json::to_string(&bundle).unwrap(),
+            r#"{"version":"v0.1","inclusion":{"block":"0x1","maxBlock":"0x3"},"body":[{"tx":"0x01","canRevert":false},{"tx":"0x02","canRevert":true}],"privacy":{"hints":["calldata","contract_address","tx_hash"]}}"#
+        );
+
+        let bundle = bundle
+            .set_hints(vec![])
+            .set_builders(vec!["flashbots".into(), "rsync".into()]);
+        assert_eq!(
+            serde_json::to_value(&bundle).unwrap()["privacy"],
+            serde_json::json!({ "builders": ["flashbots", "rsync"] })
+        );
+    }
+
+    #[test]
+    fn mev_share_bundle_serialize_refunds() {
+        let bundle = MevShareBundle::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .push_transaction(Bytes::from(vec![0x2]))
+            .set_block(1.into())
+            .set_refunds(vec![Refund {
+                body_idx: 0,
+                percent: 90,
+            }])
+            .set_refund_config(vec![
+                RefundConfig {
+                    address: Address::repeat_byte(0x1),
+                    percent: 80,
+                },
+                RefundConfig {
+                    address: Address::repeat_byte(0x2),
+                    percent: 20,
+                },
+            ]);
+
+        assert!(bundle.validate().is_ok());
+        assert_eq!(
+            &serde_json::to_string(&bundle).unwrap(),
+            r#"{"version":"v0.1","inclusion":{"block":"0x1"},"body":[{"tx":"0x01","canRevert":false},{"tx":"0x02","canRevert":false}],"validity":{"refund":[{"bodyIdx":0,"percent":90}],"refundConfig":[{"address":"0x0101010101010101010101010101010101010101","percent":80},{"address":"0x0202020202020202020202020202020202020202","percent":20}]}}"#
+        );
+    }
+
+    #[test]
+    fn mev_share_bundle_validate_refunds() {
+        let bundle = MevShareBundle::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(1.into());
+
+        let invalid_index = bundle.clone().set_refunds(vec![Refund {
+            body_idx: 1,
+            percent: 50,
+        }]);
+        assert_eq!(
+            invalid_index.validate(),
+            Err(MevShareBundleError::InvalidRefundIndex(1, 1))
+        );
+
+        let invalid_percent = bundle.set_refunds(vec![Refund {
+            body_idx: 0,
+            percent: 101,
+        }]);
+        assert_eq!(
+            invalid_percent.validate(),
+            Err(MevShareBundleError::InvalidRefundPercent)
+        );
+    }
+
+    #[test]
+    fn mev_share_bundle_nested() {
+        let inner = MevShareBundle::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(1.into())
+            .set_refunds(vec![Refund {
+                body_idx: 0,
+                percent: 50,
+            }]);
+        let bundle = MevShareBundle::new()
+            .push_bundle(inner.clone())
+            .push_transaction(Bytes::from(vec![0x2]))
+            .set_block(1.into());
+
+        assert_eq!(bundle.depth(), 1);
+        assert!(bundle.validate().is_ok());
+
+        let serialized = serde_json::to_string(&bundle).unwrap();
+        assert_eq!(
+            &serialized,
+            r#"{"version":"v0.1","inclusion":{"block":"0x1"},"body":[{"bundle":{"version":"v0.1","inclusion":{"block":"0x1"},"body":[{"tx":"0x01","canRevert":false}],"validity":{"refund":[{"bodyIdx":0,"percent":50}]}}},{"tx":"0x02","canRevert":false}]}"#
+        );
+        let deserialized: MevShareBundle = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.body(), bundle.body());
+
+        let too_deep = MevShareBundle::new().push_bundle(bundle);
+        assert_eq!(too_deep.depth(), 2);
+        assert_eq!(too_deep.validate(), Err(MevShareBundleError::TooDeep));
+
+        let invalid_inner = MevShareBundle::new().push_bundle(inner.set_refunds(vec![Refund {
+            body_idx: 3,
+            percent: 50,
+        }]));
+        assert_eq!(
+            invalid_inner.validate(),
+            Err(MevShareBundleError::InvalidRefundIndex(3, 1))
+        );
+    }
+
+    #[test]
+    fn mev_share_bundle_backrun() {
+        let hash = H256::repeat_byte(0x1);
+        let bundle = MevShareBundle::new()
+            .push_transaction_hash(hash)
+            .push_transaction(Bytes::from(vec![0x2]))
+            .set_block(1.into())
+            .set_max_block(5.into());
+
+        let serialized = serde_json::to_string(&bundle).unwrap();
+        assert_eq!(
+            &serialized,
+            r#"{"version":"v0.1","inclusion":{"block":"0x1","maxBlock":"0x5"},"body":[{"hash":"0x0101010101010101010101010101010101010101010101010101010101010101"},{"tx":"0x02","canRevert":false}]}"#
+        );
+
+        let deserialized: MevShareBundle = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.body()[0], BundleItem::Hash { hash });
+    }
+
+    #[test]
+    fn mev_share_bundle_serialize_without_hints() {
+        let bundle = MevShareBundle::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(1.into());
+
+        assert_eq!(
+            &serde_json::to_string(&bundle).unwrap(),
+            r#"{"version":"v0.1","inclusion":{"block":"0x1"},"body":[{"tx":"0x01","canRevert":false}]}"#
+        );
+    }
+}
@@ -0,0 +1,550 @@
+#[cfg(feature = "reqwest-transport")]
+use crate::mev_share::sse::{SseMessage, SseParser};
+use ethers::core::types::{Address, Bytes, H256, U256};
+#[cfg(feature = "reqwest-transport")]
+use futures_core::stream::Stream;
+#[cfg(feature = "reqwest-transport")]
+use futures_util::stream::{self, StreamExt};
+#[cfg(feature = "reqwest-transport")]
+use reqwest::{header::ACCEPT, Client, Error as ReqwestError, Response, StatusCode};
+use serde::{Deserialize, Deserializer, Serialize};
+#[cfg(feature = "reqwest-transport")]
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+#[cfg(feature = "reqwest-transport")]
+use thiserror::Error;
+#[cfg(feature = "reqwest-transport")]
+use url::Url;
+
+/// The URL of the Flashbots MEV-Share event stream on mainnet.
+pub const MEV_SHARE_EVENTS_URL: &str = "https://mev-share.flashbots.net";
+
+/// A transaction or bundle shared through the MEV-Share event stream.
+///
+/// Which fields are set depends on the privacy hints of the transaction
+/// or bundle. See [`Hint`](crate::mev_share::Hint).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    /// The hash of the transaction or bundle.
+    ///
+    /// This can be used to backrun the transaction or bundle using
+    /// [`MevShareBundle::push_transaction_hash`](crate::mev_share::MevShareBundle::push_transaction_hash).
+    pub hash: H256,
+    /// The logs emitted by the transactions (if shared).
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub logs: Vec<EventLog>,
+    /// The transactions (if shared).
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub txs: Vec<EventTransaction>,
+    /// The priority fee paid per unit of gas (if shared).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mev_gas_price: Option<U256>,
+    /// The gas used by the transactions (if shared).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<U256>,
+}
+
+/// A log shared through the MEV-Share event stream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventLog {
+    /// The address of the contract that emitted the log.
+    pub address: Address,
+    /// The topics of the log.
+    pub topics: Vec<H256>,
+    /// The data of the log.
+    pub data: Bytes,
+}
+
+/// A transaction shared through the MEV-Share event stream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventTransaction {
+    /// The destination of the transaction (if shared).
+    #[serde(default)]
+    pub to: Option<Address>,
+    /// The 4-byte function selector of the transaction (if shared).
+    #[serde(default)]
+    pub function_selector: Option<Bytes>,
+    /// The calldata of the transaction (if shared).
+    #[serde(default)]
+    pub call_data: Option<Bytes>,
+}
+
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// A client-side filter for MEV-Share events.
+///
+/// An event matches the filter if it matches every criterion that is set,
+/// and a criterion with several values matches if any of the values
+/// match. Events that do not share the data a criterion needs (e.g. the
+/// logs for a topic filter) never match that criterion.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    to: Vec<Address>,
+    topics: Vec<H256>,
+    function_selectors: Vec<[u8; 4]>,
+}
+
+impl EventFilter {
+    /// Creates a filter that matches all events.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Only match events with a transaction to the given address.
+    pub fn to(mut self, address: Address) -> Self {
+        self.to.push(address);
+        self
+    }
+
+    /// Only match events with a log with the given topic.
+    pub fn topic(mut self, topic: H256) -> Self {
+        self.topics.push(topic);
+        self
+    }
+
+    /// Only match events with a transaction calling the given function.
+    pub fn function_selector(mut self, selector: [u8; 4]) -> Self {
+        self.function_selectors.push(selector);
+        self
+    }
+
+    /// Whether the event matches the filter.
+    pub fn matches(&self, event: &Event) -> bool {
+        let to_matches = self.to.is_empty()
+            || event
+                .txs
+                .iter()
+                .any(|tx| matches!(tx.to, Some(to) if self.to.contains(&to)));
+        let topic_matches = self.topics.is_empty()
+            || event
+                .logs
+                .iter()
+                .any(|log| log.topics.iter().any(|topic| self.topics.contains(topic)));
+        let selector_matches = self.function_selectors.is_empty()
+            || event.txs.iter().any(|tx| {
+                let selector = tx.function_selector.as_ref().or(tx.call_data.as_ref());
+                matches!(
+                    selector,
+                    Some(selector) if selector.len() >= 4
+                        && self.function_selectors.iter().any(|s| s[..] == selector[..4])
+                )
+            });
+
+        to_matches && topic_matches && selector_matches
+    }
+}
+
+#[cfg(feature = "reqwest-transport")]
+/// Errors for the MEV-Share event stream.
+#[derive(Error, Debug)]
+pub enum EventStreamError {
+    /// The request failed.
+    ///
+    /// If the stream fails to reconnect, because the server rejected the
+    /// request (a 4xx status other than 429) or because the maximum number
+    /// of reconnection attempts was reached (see
+    /// [`EventClient::set_max_reconnect_attempts`]), the error of the last
+    /// attempt is yielded and the stream ends.
+    #[error(transparent)]
+    RequestError(#[from] ReqwestError),
+    /// An event could not be deserialized.
+    #[error("Deserialization error: {err}. Event: {text}")]
+    EventSerdeJson {
+        err: serde_json::Error,
+        text: String,
+    },
+    /// The connection dropped and the stream reconnected.
+    ///
+    /// The stream asks the server to resume from the last seen event id,
+    /// but events sent while the stream was disconnected may still have
+    /// been missed. If no event id was seen before the connection
+    /// dropped, the stream could not be resumed.
+    ///
+    /// The stream keeps yielding events after this error.
+    #[error("Event stream reconnected (last event id: {last_event_id:?})")]
+    Reconnected { last_event_id: Option<String> },
+}
+
+#[cfg(feature = "reqwest-transport")]
+/// The default delay before the first reconnection attempt.
+const DEFAULT_MIN_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+
+#[cfg(feature = "reqwest-transport")]
+/// The default maximum delay between reconnection attempts.
+const DEFAULT_MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+#[cfg(feature = "reqwest-transport")]
+/// A client for the MEV-Share event stream.
+#[derive(Debug, Clone)]
+pub struct EventClient {
+    pub(crate) client: Client,
+    pub(crate) url: Url,
+    min_reconnect_delay: Duration,
+    max_reconnect_delay: Duration,
+    max_reconnect_attempts: Option<u32>,
+}
+
+#[cfg(feature = "reqwest-transport")]
+impl EventClient {
+    /// Initializes a new event stream client.
+    ///
+    /// For mainnet, the URL is [`MEV_SHARE_EVENTS_URL`].
+    pub fn new(url: impl Into<Url>) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.into(),
+            min_reconnect_delay: DEFAULT_MIN_RECONNECT_DELAY,
+            max_reconnect_delay: DEFAULT_MAX_RECONNECT_DELAY,
+            max_reconnect_attempts: None,
+        }
+    }
+
+    /// Get the URL of the event stream.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Set the delays used when reconnecting a dropped stream.
+    ///
+    /// The first attempt waits `min`, and the delay doubles after every
+    /// failed attempt up to `max`. Defaults to 500 milliseconds and 30
+    /// seconds.
+    pub fn set_reconnect_delay(mut self, min: Duration, max: Duration) -> Self {
+        self.min_reconnect_delay = min;
+        self.max_reconnect_delay = max.max(min);
+        self
+    }
+
+    /// Get the maximum number of consecutive failed reconnection attempts
+    /// before the stream ends (if any).
+    pub fn max_reconnect_attempts(&self) -> Option<u32> {
+        self.max_reconnect_attempts
+    }
+
+    /// Set the maximum number of consecutive failed reconnection attempts
+    /// before the stream ends.
+    ///
+    /// By default, the stream keeps trying to reconnect unless the server
+    /// rejects the request.
+    pub fn set_max_reconnect_attempts(mut self, attempts: u32) -> Self {
+        self.max_reconnect_attempts = Some(attempts);
+        self
+    }
+
+    /// Subscribe to the event stream.
+    ///
+    /// If the connection drops, the stream reconnects with exponential
+    /// backoff and resumes from the last seen event id. Every reconnection
+    /// is surfaced as [`EventStreamError::Reconnected`]. If the stream
+    /// cannot reconnect, the error is yielded and the stream ends (see
+    /// [`EventStreamError::RequestError`]).
+    pub async fn subscribe(&self) -> Result<EventStream, EventStreamError> {
+        let response = self.connect(None).await?;
+
+        let state = SubscriptionState {
+            client: self.clone(),
+            response: Some(response),
+            parser: SseParser::default(),
+            pending: VecDeque::new(),
+            last_event_id: None,
+            attempt: 0,
+            finished: false,
+        };
+        let messages = stream::unfold(state, |mut state| async move {
+            if state.finished {
+                return None;
+            }
+            let item = state.next().await;
+            Some((item, state))
+        });
+
+        Ok(EventStream {
+            messages: Box::pin(messages),
+            filter: EventFilter::new(),
+        })
+    }
+
+    async fn connect(&self, last_event_id: Option<&str>) -> Result<Response, ReqwestError> {
+        let mut request = self
+            .client
+            .get(self.url.clone())
+            .header(ACCEPT, "text/event-stream");
+        if let Some(id) = last_event_id {
+            request = request.header("Last-Event-ID", id);
+        }
+
+        request.send().await?.error_for_status()
+    }
+
+    /// Whether a failed connection attempt should not be retried.
+    fn is_permanent(&self, err: &ReqwestError, attempt: u32) -> bool {
+        let rejected = err.is_builder()
+            || err.status().is_some_and(|status| {
+                status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS
+            });
+        rejected
+            || self
+                .max_reconnect_attempts
+                .is_some_and(|max| attempt >= max)
+    }
+
+    fn reconnect_delay(&self, attempt: u32) -> Duration {
+        self.min_reconnect_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_reconnect_delay)
+    }
+}
+
+#[cfg(feature = "reqwest-transport")]
+struct SubscriptionState {
+    client: EventClient,
+    response: Option<Response>,
+    parser: SseParser,
+    pending: VecDeque<SseMessage>,
+    last_event_id: Option<String>,
+    attempt: u32,
+    finished: bool,
+}
+
+#[cfg(feature = "reqwest-transport")]
+impl SubscriptionState {
+    async fn next(&mut self) -> Result<SseMessage, EventStreamError> {
+        loop {
+            if let Some(message) = self.pending.pop_front() {
+                if message.id.is_some() {
+                    self.last_event_id.clone_from(&message.id);
+                }
+                return Ok(message);
+            }
+
+            match &mut self.response {
+                Some(response) => match response.chunk().await {
+                    Ok(Some(chunk)) => self.pending.extend(self.parser.push(&chunk)),
+                    // The connection dropped, so any partial message is discarded
+                    Ok(None) | Err(_) => {
+                        self.response = None;
+                        self.parser = SseParser::default();
+                    }
+                },
+                None => {
+                    tokio::time::sleep(self.client.reconnect_delay(self.attempt)).await;
+                    match self.client.connect(self.last_event_id.as_deref()).await {
+                        Ok(response) => {
+                            self.response = Some(response);
+                            self.attempt = 0;
+                            return Err(EventStreamError::Reconnected {
+                                last_event_id: self.last_event_id.clone(),
+                            });
+                        }
+                        Err(err) => {
+                            self.attempt = self.attempt.saturating_add(1);
+                            if self.client.is_permanent(&err, self.attempt) {
+                                self.finished = true;
+                                return Err(err.into());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "reqwest-transport")]
+/// A stream of MEV-Share events.
+///
+/// See [`EventClient::subscribe`].
+pub struct EventStream {
+    messages: Pin<Box<dyn Stream<Item = Result<SseMessage, EventStreamError>> + Send>>,
+    filter: EventFilter,
+}
+
+#[cfg(feature = "reqwest-transport")]
+impl EventStream {
+    /// Only yield events that match the given filter.
+    pub fn set_filter(mut self, filter: EventFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+#[cfg(feature = "reqwest-transport")]
+impl Stream for EventStream {
+    type Item = Result<Event, EventStreamError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let message = match futures_util::ready!(self.messages.poll_next_unpin(ctx)) {
+                Some(Ok(message)) => message,
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => return Poll::Ready(None),
+            };
+
+            let event: Event = match serde_json::from_str(&message.data) {
+                Ok(event) => event,
+                Err(err) => {
+                    return Poll::Ready(Some(Err(EventStreamError::EventSerdeJson {
+                        err,
+                        text: message.data,
+                    })))
+                }
+            };
+
+            if self.filter.matches(&event) {
+                return Poll::Ready(Some(Ok(event)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "reqwest-transport")]
+    use crate::fixtures::{json_response, serve};
+    use std::str::FromStr;
+
+    fn event() -> Event {
+        serde_json::from_str(
+            r#"{
+                "hash": "0x669b4704a7d993a946cdd6e2f95233f308ce0c4649d2e04944e8299efcaa098a",
+                "logs": [{
+                    "address": "0x0101010101010101010101010101010101010101",
+                    "topics": ["0xd78ad95fa46c994b6551d0da85fc275fe613ce37657fb8d5e3d130840159d822"],
+                    "data": "0x"
+                }],
+                "txs": [{
+                    "to": "0x0202020202020202020202020202020202020202",
+                    "functionSelector": "0x38ed1739",
+                    "callData": null
+                }],
+                "mevGasPrice": "0x2540be400",
+                "gasUsed": "0x30d40"
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn event_deserialize() {
+        let event = event();
+        assert_eq!(event.logs.len(), 1);
+        assert_eq!(event.txs[0].to, Some(Address::repeat_byte(0x2)));
+        assert_eq!(event.mev_gas_price, Some(10000000000u64.into()));
+
+        let event: Event = serde_json::from_str(
+            r#"{
+                "hash": "0x669b4704a7d993a946cdd6e2f95233f308ce0c4649d2e04944e8299efcaa098a",
+                "logs": null,
+                "txs": null
+            }"#,
+        )
+        .unwrap();
+        assert!(event.logs.is_empty());
+        assert!(event.txs.is_empty());
+    }
+
+    #[test]
+    fn event_filter() {
+        let event = event();
+        let topic =
+            H256::from_str("0xd78ad95fa46c994b6551d0da85fc275fe613ce37657fb8d5e3d130840159d822")
+                .unwrap();
+
+        assert!(EventFilter::new().matches(&event));
+        assert!(EventFilter::new()
+            .to(Address::repeat_byte(0x2))
+            .matches(&event));
+        assert!(!EventFilter::new()
+            .to(Address::repeat_byte(0x1))
+            .matches(&event));
+        assert!(EventFilter::new().topic(topic).matches(&event));
+        assert!(!EventFilter::new().topic(H256::zero()).matches(&event));
+        assert!(EventFilter::new()
+            .function_selector([0x38, 0xed, 0x17, 0x39])
+            .to(Address::repeat_byte(0x2))
+            .matches(&event));
+        assert!(!EventFilter::new()
+            .function_selector([0x38, 0xed, 0x17, 0x39])
+            .to(Address::repeat_byte(0x3))
+            .matches(&event));
+    }
+
+    #[test]
+    #[cfg(feature = "reqwest-transport")]
+    fn reconnect_delay() {
+        let client = EventClient::new(Url::parse(MEV_SHARE_EVENTS_URL).unwrap())
+            .set_reconnect_delay(Duration::from_secs(1), Duration::from_secs(10));
+
+        assert_eq!(client.reconnect_delay(0), Duration::from_secs(1));
+        assert_eq!(client.reconnect_delay(1), Duration::from_secs(2));
+        assert_eq!(client.reconnect_delay(3), Duration::from_secs(8));
+        assert_eq!(client.reconnect_delay(4), Duration::from_secs(10));
+        assert_eq!(client.reconnect_delay(u32::MAX), Duration::from_secs(10));
+    }
+
+    #[cfg(feature = "reqwest-transport")]
+    const EVENT_RESPONSE: &str = "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\nconnection: close\r\n\r\nid: 1\ndata: {\"hash\":\"0x669b4704a7d993a946cdd6e2f95233f308ce0c4649d2e04944e8299efcaa098a\"}\n\n";
+
+    #[tokio::test]
+    #[cfg(feature = "reqwest-transport")]
+    async fn stream_ends_on_rejected_reconnect() {
+        let (url, _) = serve(vec![
+            EVENT_RESPONSE.to_string(),
+            json_response("404 Not Found", ""),
+        ])
+        .await;
+        let client = EventClient::new(url)
+            .set_reconnect_delay(Duration::from_millis(1), Duration::from_millis(1));
+
+        let mut events = client.subscribe().await.unwrap();
+        assert!(events.next().await.unwrap().is_ok());
+        match events.next().await.unwrap() {
+            Err(EventStreamError::RequestError(err)) => {
+                assert_eq!(err.status(), Some(StatusCode::NOT_FOUND))
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+        assert!(events.next().await.is_none());
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "reqwest-transport")]
+    async fn stream_ends_after_max_reconnect_attempts() {
+        let unavailable = json_response("503 Service Unavailable", "");
+        let (url, _) = serve(vec![
+            EVENT_RESPONSE.to_string(),
+            unavailable.clone(),
+            unavailable,
+        ])
+        .await;
+        let client = EventClient::new(url)
+            .set_reconnect_delay(Duration::from_millis(1), Duration::from_millis(1))
+            .set_max_reconnect_attempts(2);
+
+        let mut events = client.subscribe().await.unwrap();
+        assert!(events.next().await.unwrap().is_ok());
+        match events.next().await.unwrap() {
+            Err(EventStreamError::RequestError(err)) => {
+                assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE))
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+        assert!(events.next().await.is_none());
+    }
+}
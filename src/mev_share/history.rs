@@ -0,0 +1,231 @@
+#[cfg(feature = "reqwest-transport")]
+use crate::mev_share::EventClient;
+use crate::{mev_share::Event, utils::deserialize_u64};
+use ethers::core::types::U64;
+#[cfg(feature = "reqwest-transport")]
+use reqwest::Error as ReqwestError;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "reqwest-transport")]
+use thiserror::Error;
+
+#[cfg(feature = "reqwest-transport")]
+/// Errors for MEV-Share event history requests.
+#[derive(Error, Debug)]
+pub enum EventHistoryError {
+    /// The request failed.
+    #[error(transparent)]
+    RequestError(#[from] ReqwestError),
+    /// The request URL could not be constructed.
+    #[error(transparent)]
+    UrlError(#[from] url::ParseError),
+    /// The response could not be deserialized.
+    #[error("Deserialization error: {err}. Response: {text}")]
+    ResponseSerdeJson {
+        err: serde_json::Error,
+        text: String,
+    },
+}
+
+/// An event from the MEV-Share event history.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct HistoricalEvent {
+    /// The block the event was received in.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub block: U64,
+    /// The time the event was received, in seconds since the UNIX epoch.
+    pub timestamp: u64,
+    /// The event, as it was sent on the event stream.
+    pub hint: Event,
+}
+
+/// Information about the MEV-Share event history.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventHistoryInfo {
+    /// The number of events in the history.
+    pub count: u64,
+    /// The first block in the history.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub min_block: U64,
+    /// The last block in the history.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub max_block: U64,
+    /// The time of the first event in the history.
+    pub min_timestamp: u64,
+    /// The time of the last event in the history.
+    pub max_timestamp: u64,
+    /// The maximum number of events returned per request.
+    pub max_limit: u64,
+}
+
+/// A query for the MEV-Share event history.
+///
+/// Results are paginated: the server returns at most
+/// [`EventHistoryInfo::max_limit`] events per request. Use
+/// [`EventHistoryQuery::next_page`] to fetch the following events.
+///
+/// ```
+/// # #[cfg(feature = "reqwest-transport")]
+/// # use ethers_flashbots::mev_share::{EventClient, EventHistoryQuery};
+/// # #[cfg(feature = "reqwest-transport")]
+/// # async fn history(client: EventClient) -> Result<(), Box<dyn std::error::Error>> {
+/// let mut query = EventHistoryQuery::new().block_start(17_000_000u64.into());
+/// loop {
+///     let events = client.event_history(&query).await?;
+///     if events.is_empty() {
+///         break;
+///     }
+///     // Backtest against the events...
+///     query = query.next_page(events.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventHistoryQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_start: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_end: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp_start: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp_end: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<u64>,
+}
+
+impl EventHistoryQuery {
+    /// Creates a query for the entire event history.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Only return events received in or after the given block.
+    pub fn block_start(mut self, block: U64) -> Self {
+        self.block_start = Some(block.as_u64());
+        self
+    }
+
+    /// Only return events received in or before the given block.
+    pub fn block_end(mut self, block: U64) -> Self {
+        self.block_end = Some(block.as_u64());
+        self
+    }
+
+    /// Only return events received at or after the given time, in seconds
+    /// since the UNIX epoch.
+    pub fn timestamp_start(mut self, timestamp: u64) -> Self {
+        self.timestamp_start = Some(timestamp);
+        self
+    }
+
+    /// Only return events received at or before the given time, in
+    /// seconds since the UNIX epoch.
+    pub fn timestamp_end(mut self, timestamp: u64) -> Self {
+        self.timestamp_end = Some(timestamp);
+        self
+    }
+
+    /// The maximum number of events to return.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// The number of events to skip.
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Advances the query past a page of `received` events.
+    pub fn next_page(mut self, received: usize) -> Self {
+        self.offset = Some(self.offset.unwrap_or_default() + received as u64);
+        self
+    }
+}
+
+#[cfg(feature = "reqwest-transport")]
+impl EventClient {
+    /// Get events from the MEV-Share event history.
+    pub async fn event_history(
+        &self,
+        query: &EventHistoryQuery,
+    ) -> Result<Vec<HistoricalEvent>, EventHistoryError> {
+        self.get_history("api/v1/history", query).await
+    }
+
+    /// Get information about the MEV-Share event history.
+    pub async fn event_history_info(&self) -> Result<EventHistoryInfo, EventHistoryError> {
+        self.get_history("api/v1/history/info", &EventHistoryQuery::new())
+            .await
+    }
+
+    async fn get_history<R: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &EventHistoryQuery,
+    ) -> Result<R, EventHistoryError> {
+        let text = self
+            .client
+            .get(self.url.join(path)?)
+            .query(query)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        serde_json::from_str(&text)
+            .map_err(|err| EventHistoryError::ResponseSerdeJson { err, text })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn historical_event_deserialize() {
+        let events: Vec<HistoricalEvent> = serde_json::from_str(
+            r#"[{
+                "block": 17000000,
+                "timestamp": 1680911891,
+                "hint": {
+                    "txs": null,
+                    "hash": "0x669b4704a7d993a946cdd6e2f95233f308ce0c4649d2e04944e8299efcaa098a",
+                    "logs": null,
+                    "gasUsed": "0x30d40",
+                    "mevGasPrice": "0x2540be400"
+                }
+            }]"#,
+        )
+        .unwrap();
+
+        assert_eq!(events[0].block, 17000000u64.into());
+        assert_eq!(events[0].hint.gas_used, Some(200000u64.into()));
+    }
+
+    #[test]
+    fn history_query_pagination() {
+        let query = EventHistoryQuery::new()
+            .block_start(17000000u64.into())
+            .limit(500);
+        assert_eq!(query.offset, None);
+
+        let query = query.next_page(500).next_page(250);
+        assert_eq!(query.offset, Some(750));
+        assert_eq!(
+            serde_json::to_value(&query).unwrap(),
+            serde_json::json!({
+                "blockStart": 17000000,
+                "limit": 500,
+                "offset": 750
+            })
+        );
+    }
+}
@@ -0,0 +1,38 @@
+//! Types for [MEV-Share][mev_share], the Flashbots order flow auction.
+//!
+//! MEV-Share bundles and private transactions can be sent using
+//! [`FlashbotsMiddleware::send_mev_share_bundle`](crate::FlashbotsMiddleware::send_mev_share_bundle)
+//! and [`FlashbotsMiddleware::send_private_transaction`](crate::FlashbotsMiddleware::send_private_transaction).
+//! Transactions and bundles shared by users can be received using an
+//! [`EventClient`], which also gives access to the history of past events.
+//! The event client streams responses with reqwest and requires the
+//! `reqwest-transport` feature.
+//!
+//! [mev_share]: https://docs.flashbots.net/flashbots-mev-share/overview
+mod bundle;
+pub use bundle::{
+    BundleItem, MevShareBundle, MevShareBundleError, Refund, RefundConfig, MAX_BUNDLE_DEPTH,
+};
+
+mod events;
+pub use events::{Event, EventFilter, EventLog, EventTransaction, MEV_SHARE_EVENTS_URL};
+#[cfg(feature = "reqwest-transport")]
+pub use events::{EventClient, EventStream, EventStreamError};
+
+mod history;
+#[cfg(feature = "reqwest-transport")]
+pub use history::EventHistoryError;
+pub use history::{EventHistoryInfo, EventHistoryQuery, HistoricalEvent};
+
+mod privacy;
+pub use privacy::Hint;
+pub(crate) use privacy::Privacy;
+
+mod private_tx;
+pub use private_tx::PrivateTransaction;
+
+mod simulation;
+pub use simulation::{MevShareSimulation, MevShareSimulationLogs};
+
+#[cfg(feature = "reqwest-transport")]
+mod sse;
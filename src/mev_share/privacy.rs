@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// A privacy hint for MEV-Share bundles and private transactions.
+///
+/// Hints control which parts of a bundle or transaction are shared with
+/// searchers through the MEV-Share event stream. Sharing more data makes
+/// it easier for searchers to backrun the bundle or transaction, which
+/// increases the chance of a refund.
+///
+/// See the [MEV-Share documentation][hints] for more information.
+///
+/// [hints]: https://docs.flashbots.net/flashbots-mev-share/searchers/understanding-bundles#hints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hint {
+    /// Share the calldata of the transactions.
+    Calldata,
+    /// Share the addresses of the contracts the transactions interact with.
+    ContractAddress,
+    /// Share the 4-byte function selectors of the transactions.
+    FunctionSelector,
+    /// Share the logs emitted by the transactions.
+    Logs,
+    /// Share the hashes of the transactions.
+    TxHash,
+    /// Share all of the data above.
+    Full,
+}
+
+/// Privacy preferences of a MEV-Share bundle or private transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Privacy {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) hints: Vec<Hint>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) builders: Vec<String>,
+}
+
+impl Privacy {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.hints.is_empty() && self.builders.is_empty()
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{bundle::BundleTransaction, mev_share::Hint, mev_share::Privacy};
+use ethers::core::types::{Bytes, U64};
+use serde::{Deserialize, Serialize};
+
+/// A private transaction that can be submitted to the MEV-Share matchmaker.
+///
+/// Private transactions are not sent to the public mempool. Parts of the
+/// transaction can be shared with searchers using [privacy hints](Hint) in
+/// exchange for a refund of the value they extract.
+///
+/// See [`eth_sendPrivateTransaction`][send_private_transaction] for more
+/// information.
+///
+/// [send_private_transaction]: https://docs.flashbots.net/flashbots-auction/advanced/rpc-endpoint#eth_sendprivatetransaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivateTransaction {
+    tx: Bytes,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_block_number: Option<U64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Preferences::is_empty")]
+    preferences: Preferences,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Preferences {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Privacy::is_empty")]
+    privacy: Privacy,
+}
+
+impl Preferences {
+    fn is_empty(&self) -> bool {
+        self.privacy.is_empty()
+    }
+}
+
+impl PrivateTransaction {
+    /// Creates a private transaction.
+    pub fn new<T: Into<BundleTransaction>>(tx: T) -> Self {
+        Self {
+            tx: tx.into().rlp(),
+            max_block_number: None,
+            preferences: Default::default(),
+        }
+    }
+
+    /// Get the RLP encoded signed transaction.
+    pub fn transaction(&self) -> &Bytes {
+        &self.tx
+    }
+
+    /// Get the last block the transaction can be included in (if any).
+    pub fn max_block_number(&self) -> Option<U64> {
+        self.max_block_number
+    }
+
+    /// Set the last block the transaction can be included in.
+    ///
+    /// If this is not set, the matchmaker tries to include the transaction
+    /// for a number of blocks chosen by the matchmaker.
+    pub fn set_max_block_number(mut self, block: U64) -> Self {
+        self.max_block_number = Some(block);
+        self
+    }
+
+    /// Get a reference to the privacy hints of the transaction.
+    pub fn hints(&self) -> &Vec<Hint> {
+        &self.preferences.privacy.hints
+    }
+
+    /// Set the privacy hints of the transaction.
+    ///
+    /// If no hints are set, the default hints of the matchmaker are used.
+    pub fn set_hints(mut self, hints: Vec<Hint>) -> Self {
+        self.preferences.privacy.hints = hints;
+        self
+    }
+
+    /// Get a reference to the builders the transaction is sent to.
+    pub fn builders(&self) -> &Vec<String> {
+        &self.preferences.privacy.builders
+    }
+
+    /// Set the builders the matchmaker sends the transaction to, by name
+    /// (e.g. `flashbots`).
+    ///
+    /// If no builders are set, the transaction is only sent to the
+    /// Flashbots builder.
+    pub fn set_builders(mut self, builders: Vec<String>) -> Self {
+        self.preferences.privacy.builders = builders;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn private_transaction_serialize() {
+        let tx = PrivateTransaction::new(Bytes::from(vec![0x1]))
+            .set_max_block_number(10.into())
+            .set_hints(vec![Hint::Logs, Hint::FunctionSelector]);
+
+        assert_eq!(
+            &serde_json::to_string(&tx).unwrap(),
+            r#"{"tx":"0x01","maxBlockNumber":"0xa","preferences":{"privacy":{"hints":["logs","function_selector"]}}}"#
+        );
+
+        let tx = PrivateTransaction::new(Bytes::from(vec![0x1]));
+        assert_eq!(&serde_json::to_string(&tx).unwrap(), r#"{"tx":"0x01"}"#);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{
+    simulation::SimulationResult,
+    utils::{deserialize_u256, deserialize_u64},
+};
+use ethers::core::types::{Log, U256, U64};
+use serde::Deserialize;
+
+/// The result of simulating a MEV-Share bundle.
+///
+/// Unlike [`SimulatedBundle`](crate::SimulatedBundle), MEV-Share
+/// simulations do not report results per item: if any non-revertible item
+/// fails, the simulation fails as a whole.
+///
+/// See [`mev_simBundle`][sim_bundle] for more information.
+///
+/// [sim_bundle]: https://docs.flashbots.net/flashbots-auction/advanced/rpc-endpoint#mev_simbundle
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MevShareSimulation {
+    /// Whether the simulation succeeded.
+    pub success: bool,
+    /// The error of the simulation (if any).
+    #[serde(default)]
+    pub error: Option<String>,
+    /// The block the bundle was simulated on top of.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub state_block: U64,
+    /// The effective gas price of the bundle, i.e. `profit / gas_used`.
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub mev_gas_price: U256,
+    /// The value the bundle pays to the block builder.
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub profit: U256,
+    /// The part of the profit that can be refunded to the users included
+    /// in the bundle.
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub refundable_value: U256,
+    /// The total amount of gas used by the bundle.
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub gas_used: U256,
+    /// The logs emitted by each item in the bundle.
+    #[serde(default)]
+    pub logs: Vec<MevShareSimulationLogs>,
+}
+
+/// The logs emitted by an item of a simulated MEV-Share bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MevShareSimulationLogs {
+    /// The logs emitted by the transaction, if the item is a transaction.
+    #[serde(default)]
+    pub tx_logs: Option<Vec<Log>>,
+    /// The logs emitted by the items of the nested bundle, if the item is
+    /// a bundle.
+    #[serde(default)]
+    pub bundle_logs: Option<Vec<MevShareSimulationLogs>>,
+}
+
+impl SimulationResult for MevShareSimulation {
+    fn is_success(&self) -> bool {
+        self.success
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error.as_deref().filter(|error| !error.is_empty())
+    }
+
+    fn simulation_block(&self) -> U64 {
+        self.state_block
+    }
+
+    fn gas_used(&self) -> U256 {
+        self.gas_used
+    }
+
+    fn coinbase_profit(&self) -> U256 {
+        self.profit
+    }
+
+    /// MEV-Share simulations do not report which item failed, so this is
+    /// always empty. Use [`SimulationResult::is_success`] instead.
+    fn reverted_items(&self) -> Vec<usize> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mev_share_simulation_deserialize() {
+        let simulation: MevShareSimulation = serde_json::from_str(
+            r#"{
+                "success": true,
+                "error": "",
+                "stateBlock": "0x8b8da8",
+                "mevGasPrice": "0x74c7906005",
+                "profit": "0x4bc800904fc000",
+                "refundableValue": "0x4bc800904fc000",
+                "gasUsed": "0xa620",
+                "logs": [{}, {"txLogs": []}]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(simulation.is_success());
+        assert_eq!(simulation.error(), None);
+        assert_eq!(simulation.simulation_block(), 9145768u64.into());
+        assert_eq!(simulation.gas_used(), 42528u64.into());
+        assert_eq!(
+            simulation.effective_gas_price(),
+            simulation.profit.checked_div(simulation.gas_used)
+        );
+        assert_eq!(simulation.logs[1].tx_logs, Some(vec![]));
+    }
+}
@@ -0,0 +1,87 @@
+/// A message received from a server-sent events stream.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SseMessage {
+    pub(crate) id: Option<String>,
+    pub(crate) data: String,
+}
+
+/// An incremental parser for server-sent events.
+///
+/// See the [HTML specification][sse] for the format.
+///
+/// [sse]: https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation
+#[derive(Debug, Default)]
+pub(crate) struct SseParser {
+    buffer: Vec<u8>,
+    message: SseMessage,
+    has_data: bool,
+}
+
+impl SseParser {
+    /// Feed a chunk of the stream to the parser, returning the messages
+    /// that were completed by the chunk.
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Vec<SseMessage> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut messages = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                // An empty line dispatches the message
+                if self.has_data {
+                    messages.push(std::mem::take(&mut self.message));
+                }
+                self.message.data.clear();
+                self.has_data = false;
+                continue;
+            }
+
+            let (field, value) = match line.split_once(':') {
+                Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                None => (line, ""),
+            };
+            match field {
+                "data" => {
+                    if self.has_data {
+                        self.message.data.push('\n');
+                    }
+                    self.message.data.push_str(value);
+                    self.has_data = true;
+                }
+                "id" => self.message.id = Some(value.to_string()),
+                // Comments, event types and retry hints are ignored
+                _ => {}
+            }
+        }
+
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sse_parser() {
+        let mut parser = SseParser::default();
+
+        assert!(parser.push(b": ping\n\nid: 1\ndata: {\"a\"").is_empty());
+        assert_eq!(
+            parser.push(b":1}\n\ndata: line 1\r\ndata: line 2\r\n\r\n"),
+            vec![
+                SseMessage {
+                    id: Some("1".into()),
+                    data: "{\"a\":1}".into()
+                },
+                SseMessage {
+                    id: None,
+                    data: "line 1\nline 2".into()
+                }
+            ]
+        );
+    }
+}
@@ -1,35 +1,71 @@
 use crate::{
-    bundle::{BundleHash, BundleRequest, BundleStats, SimulatedBundle},
-    pending_bundle::PendingBundle,
-    relay::{GetBundleStatsParams, GetUserStatsParams, Relay, RelayError, SendBundleResponse},
+    builders::BuilderEndpoint,
+    bundle::{
+        BundleHash, BundleRequest, BundleStats, BundleTransaction, SimulatedBundle, TxOptions,
+        TxOptionsError,
+    },
+    dedupe::DuplicatePolicy,
+    error::ErrorKind,
+    failover::{
+        Failover, SimulationFailover, SimulationFailoverEvent, SimulationFailoverHook,
+        SimulationFailoverState,
+    },
+    fill::fill_bundle,
+    inclusion::InclusionHook,
+    mev_share::{MevShareBundle, MevShareBundleError, MevShareSimulation, PrivateTransaction},
+    network::Network,
+    payment::{coinbase_payment, ProfitBid},
+    pending_bundle::{OwnedPendingBundle, PendingBundle, PendingBundleError},
+    relay::{
+        CancelBundleByHashParams, GetBundleStatsParams, GetUserStatsParams, Relay, RelayError,
+        SendBundleResponse, SignatureScheme, SignedRequest,
+    },
+    relay_stats::RelayStats,
+    spend::{SpendGuard, SpendLimit},
+    tip::{self, TipError, TipSuggestion},
+    transport::{default_transport, HttpTransport},
     UserStats,
 };
 use async_trait::async_trait;
 use ethers::{
+    contract::FunctionCall,
     core::{
-        types::{BlockNumber, Bytes, U64},
+        types::{
+            transaction::eip2718::TypedTransaction, BlockNumber, Bytes, TransactionReceipt, TxHash,
+            U256, U64,
+        },
         utils::keccak256,
     },
-    providers::{Middleware, MiddlewareError, PendingTransaction},
+    providers::{JsonRpcClient, Middleware, MiddlewareError, PendingTransaction},
     signers::Signer,
 };
 use futures_util::future;
+#[cfg(feature = "reqwest-transport")]
+use reqwest::Client;
+use serde::Serialize;
+use std::{borrow::Borrow, sync::Arc, time::Duration};
 use thiserror::Error;
 use url::Url;
 
 /// Errors for the Flashbots middleware.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum FlashbotsMiddlewareError<M: Middleware, S: Signer> {
     /// Some parameters were missing.
     ///
     /// For bundle simulation, check that the following are set:
-    /// - `simulation_block`
     /// - `simulation_timestamp`
     /// - `block`
+    /// - `simulation_block` (or `simulation_state`), if simulations are
+    ///   strict
     ///
     /// For bundle submission, check that the following are set:
     /// - `block`
     ///
+    /// For bundle cancellations, check that the following are set:
+    /// - `block`
+    /// - `uuid`
+    ///
     /// Additionally, `min_timestamp` and `max_timestamp` must
     /// both be set or unset.
     #[error("Some parameters were missing")]
@@ -37,7 +73,7 @@ pub enum FlashbotsMiddlewareError<M: Middleware, S: Signer> {
     /// The relay responded with an error.
     #[error(transparent)]
     RelayError(#[from] RelayError<S>),
-    /// An error occured in one of the middlewares.
+    /// An error occurred in one of the middlewares.
     #[error("{0}")]
     MiddlewareError(M::Error),
     /// Empty data for bundle simulation request.
@@ -49,6 +85,119 @@ pub enum FlashbotsMiddlewareError<M: Middleware, S: Signer> {
     /// Empty data for user stats request.
     #[error("User stats are not available")]
     UserStatsError,
+    /// A tip could not be suggested.
+    #[error(transparent)]
+    TipSuggestionError(TipError),
+    /// The MEV-Share bundle is invalid.
+    #[error(transparent)]
+    MevShareBundleError(#[from] MevShareBundleError),
+    /// The relay returned an empty response.
+    #[error("The relay returned an empty response")]
+    EmptyResponse,
+    /// The bundle is not supported by the builder.
+    ///
+    /// See [`BuilderCapabilities`](crate::BuilderCapabilities).
+    #[error("The bundle is not supported by the builder")]
+    UnsupportedBundle,
+    /// The request is not supported by the builder, e.g. private
+    /// transactions or MEV-Share bundles.
+    ///
+    /// See [`BuilderCapabilities`](crate::BuilderCapabilities).
+    #[error("The builder does not support {0}")]
+    UnsupportedRequest(&'static str),
+    /// A bundle transaction could not be signed.
+    #[error(transparent)]
+    TransactionSignerError(Box<dyn std::error::Error + Send + Sync>),
+    /// The chain of the relay does not match the chain of the inner
+    /// middleware.
+    #[error("Relay {relay} is on chain {expected}, but the provider is on chain {actual}")]
+    ChainIdMismatch {
+        relay: Url,
+        expected: u64,
+        actual: U256,
+    },
+    /// The transaction is not pending, i.e. it is unknown to the inner
+    /// middleware or has already been included in a block.
+    #[error("Transaction {0:?} is not pending")]
+    TransactionNotPending(TxHash),
+    /// The bundle was already submitted to the relay.
+    ///
+    /// See [`DuplicatePolicy`].
+    #[error("The bundle was already submitted to the relay")]
+    DuplicateSubmission,
+    /// An error occurred while waiting for the bundle to be included.
+    #[error(transparent)]
+    PendingBundleError(#[from] PendingBundleError),
+    /// The receipt of an included transaction is not available.
+    #[error("The receipt of transaction {0:?} is not available")]
+    MissingReceipt(TxHash),
+    /// The serialized bundle exceeds the size limit of the relay.
+    ///
+    /// See [`BundleLimits`](crate::BundleLimits).
+    #[error("The bundle is {size} bytes, but the relay accepts at most {limit} bytes")]
+    BundleTooLarge { size: usize, limit: usize },
+    /// The bundle has more transactions than the relay accepts.
+    ///
+    /// See [`BundleLimits`](crate::BundleLimits).
+    #[error("The bundle has {count} transactions, but the relay accepts at most {limit}")]
+    TooManyTransactions { count: usize, limit: usize },
+    /// The bundle is not profitable according to the bid.
+    ///
+    /// See [`ProfitBid`](crate::ProfitBid).
+    #[error("The bundle is not profitable")]
+    UnprofitableBundle,
+    /// The options of a bundle transaction are invalid.
+    #[error(transparent)]
+    TxOptionsError(#[from] TxOptionsError),
+    /// The bundle would exceed the spend limit of the middleware.
+    ///
+    /// See [`SpendLimit`](crate::SpendLimit).
+    #[error("The bundle would spend {spend} wei, but the limit is {limit} wei")]
+    SpendLimitExceeded { spend: U256, limit: U256 },
+    /// The inner middleware has no default sender to sign the payment of
+    /// a profit bid with.
+    ///
+    /// See [`FlashbotsMiddleware::apply_profit_bid`].
+    #[error("The inner middleware has no default sender")]
+    MissingSender,
+}
+
+impl<M: Middleware, S: Signer> FlashbotsMiddlewareError<M, S> {
+    /// Get the kind of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            FlashbotsMiddlewareError::RelayError(err) => err.kind(),
+            FlashbotsMiddlewareError::MiddlewareError(_) => ErrorKind::Provider,
+            FlashbotsMiddlewareError::MissingParameters
+            | FlashbotsMiddlewareError::MevShareBundleError(_)
+            | FlashbotsMiddlewareError::UnsupportedBundle
+            | FlashbotsMiddlewareError::UnsupportedRequest(_)
+            | FlashbotsMiddlewareError::TransactionNotPending(_)
+            | FlashbotsMiddlewareError::DuplicateSubmission
+            | FlashbotsMiddlewareError::BundleTooLarge { .. }
+            | FlashbotsMiddlewareError::TooManyTransactions { .. }
+            | FlashbotsMiddlewareError::UnprofitableBundle
+            | FlashbotsMiddlewareError::TxOptionsError(_)
+            | FlashbotsMiddlewareError::SpendLimitExceeded { .. } => ErrorKind::InvalidRequest,
+            FlashbotsMiddlewareError::BundleSimError
+            | FlashbotsMiddlewareError::BundleStatsError
+            | FlashbotsMiddlewareError::UserStatsError
+            | FlashbotsMiddlewareError::MissingReceipt(_) => ErrorKind::Unavailable,
+            FlashbotsMiddlewareError::PendingBundleError(err) => err.kind(),
+            FlashbotsMiddlewareError::TipSuggestionError(err) => err.kind(),
+            FlashbotsMiddlewareError::EmptyResponse => ErrorKind::InvalidResponse,
+            FlashbotsMiddlewareError::TransactionSignerError(_) => ErrorKind::Signer,
+            FlashbotsMiddlewareError::ChainIdMismatch { .. }
+            | FlashbotsMiddlewareError::MissingSender => ErrorKind::Configuration,
+        }
+    }
+
+    /// Whether retrying the request may succeed.
+    ///
+    /// See [`ErrorKind::is_retryable`].
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
 }
 
 impl<M: Middleware, S: Signer> MiddlewareError for FlashbotsMiddlewareError<M, S> {
@@ -126,6 +275,11 @@ pub struct FlashbotsMiddleware<M, S> {
     inner: M,
     relay: Relay<S>,
     simulation_relay: Option<Relay<S>>,
+    simulation_failover: Option<SimulationFailoverState<S>>,
+    simulation_failover_hook: Option<SimulationFailoverHook>,
+    strict_simulation: bool,
+    spend_guard: Option<SpendGuard>,
+    inclusion_hooks: Vec<InclusionHook>,
 }
 
 impl<M: Middleware, S: Signer> FlashbotsMiddleware<M, S> {
@@ -137,14 +291,72 @@ impl<M: Middleware, S: Signer> FlashbotsMiddleware<M, S> {
             inner,
             relay: Relay::new(relay_url, Some(relay_signer)),
             simulation_relay: None,
+            simulation_failover: None,
+            simulation_failover_hook: None,
+            strict_simulation: false,
+            spend_guard: None,
+            inclusion_hooks: Vec::new(),
+        }
+    }
+
+    /// Initialize a new Flashbots middleware for a well-known builder.
+    ///
+    /// The relay uses the dialect and capabilities of the builder. See
+    /// [`builders`](crate::builders).
+    pub fn from_endpoint(inner: M, endpoint: &BuilderEndpoint, relay_signer: S) -> Self {
+        Self {
+            inner,
+            relay: Relay::from_endpoint(endpoint, Some(relay_signer)),
+            simulation_relay: None,
+            simulation_failover: None,
+            simulation_failover_hook: None,
+            strict_simulation: false,
+            spend_guard: None,
+            inclusion_hooks: Vec::new(),
         }
     }
 
+    /// Check that the inner middleware is on the same chain as the relay.
+    ///
+    /// Bundles signed for one chain are silently dropped by relays on
+    /// another chain, so this should be called before sending bundles.
+    /// Relays without a known chain ID (see [`Relay::set_chain_id`]) are
+    /// not checked.
+    pub async fn verify(&self) -> Result<(), FlashbotsMiddlewareError<M, S>> {
+        verify_chain_id(&self.inner, [&self.relay]).await
+    }
+
     /// Get the relay client used by the middleware.
     pub fn relay(&self) -> &Relay<S> {
         &self.relay
     }
 
+    /// Get a mutable reference to the relay client used by the middleware,
+    /// e.g. to configure it.
+    pub fn relay_mut(&mut self) -> &mut Relay<S> {
+        &mut self.relay
+    }
+
+    /// Get latency and success statistics of the relay, followed by the
+    /// simulation relay (if set).
+    ///
+    /// See [`RelayStats`].
+    pub fn relay_stats(&self) -> Vec<RelayStats> {
+        std::iter::once(&self.relay)
+            .chain(&self.simulation_relay)
+            .map(Relay::stats)
+            .collect()
+    }
+
+    /// Set relays to fail over to, in order, if the relay cannot be
+    /// reached.
+    ///
+    /// Requests return to the relay once it recovers. See [`Failover`] to
+    /// configure the failover further.
+    pub fn set_fallback_relays(&mut self, relay_urls: Vec<Url>) {
+        self.relay.set_failover(Failover::new(relay_urls));
+    }
+
     /// Get the relay client used by the middleware to simulate
     /// bundles if set.
     pub fn simulation_relay(&self) -> Option<&Relay<S>> {
@@ -159,6 +371,80 @@ impl<M: Middleware, S: Signer> FlashbotsMiddleware<M, S> {
         self.simulation_relay = Some(Relay::new(relay_url, None));
     }
 
+    /// Whether bundles can only be simulated if their simulation block is
+    /// set.
+    pub fn strict_simulation(&self) -> bool {
+        self.strict_simulation
+    }
+
+    /// Require the simulation block of bundles to be set when simulating
+    /// them.
+    ///
+    /// By default, bundles without a simulation block are simulated on
+    /// top of the latest block.
+    pub fn set_strict_simulation(&mut self, strict: bool) {
+        self.strict_simulation = strict;
+    }
+
+    /// Get the spend limit of the middleware (if any).
+    pub fn spend_limit(&self) -> Option<&SpendLimit> {
+        self.spend_guard.as_ref().map(SpendGuard::limit)
+    }
+
+    /// Set a limit on the amount bundles may spend.
+    ///
+    /// See [`SpendLimit`].
+    pub fn set_spend_limit(&mut self, limit: SpendLimit) {
+        self.spend_guard = Some(SpendGuard::new(limit));
+    }
+
+    /// Add a hook that is called with the outcome of every pending bundle
+    /// returned by the middleware.
+    ///
+    /// See [`InclusionHook`].
+    pub fn on_inclusion(&mut self, hook: InclusionHook) {
+        self.inclusion_hooks.push(hook);
+    }
+
+    /// Get the failover of bundle simulations (if any).
+    pub fn simulation_failover(&self) -> Option<&SimulationFailover> {
+        self.simulation_failover
+            .as_ref()
+            .map(|state| state.failover())
+    }
+
+    /// Fail over bundle simulations if the simulation relay fails
+    /// repeatedly.
+    ///
+    /// This only applies to [`FlashbotsMiddleware::simulate_bundle`] if a
+    /// simulation relay is set. See [`SimulationFailover`].
+    ///
+    /// The fallback relays use the transport and settings of the
+    /// simulation relay, and sign requests with the signer of the
+    /// middleware, so this should be called after the simulation relay is
+    /// set.
+    pub fn set_simulation_failover(&mut self, failover: SimulationFailover)
+    where
+        S: Clone,
+    {
+        let simulation_relay = self.simulation_relay.as_ref().unwrap_or(&self.relay);
+        let fallbacks = failover
+            .fallbacks()
+            .iter()
+            .map(|url| simulation_relay.for_endpoint(url.clone(), self.relay.signer().cloned()))
+            .collect();
+        self.simulation_failover = Some(SimulationFailoverState::new(failover, fallbacks));
+    }
+
+    /// Set a callback that is called when bundle simulations fail over to
+    /// the fallback relays, or return to the simulation relay.
+    pub fn on_simulation_failover(
+        &mut self,
+        hook: impl Fn(SimulationFailoverEvent) + Send + Sync + 'static,
+    ) {
+        self.simulation_failover_hook = Some(SimulationFailoverHook(Arc::new(hook)));
+    }
+
     /// Simulate a bundle.
     ///
     /// See [`eth_callBundle`][fb_callBundle] for more information.
@@ -168,21 +454,216 @@ impl<M: Middleware, S: Signer> FlashbotsMiddleware<M, S> {
         &self,
         bundle: &BundleRequest,
     ) -> Result<SimulatedBundle, FlashbotsMiddlewareError<M, S>> {
-        bundle
-            .block()
-            .and(bundle.simulation_block())
-            .and(bundle.simulation_timestamp())
+        let bundle = &SimulationParams::new(bundle, self.strict_simulation)
             .ok_or(FlashbotsMiddlewareError::MissingParameters)?;
 
-        self.simulation_relay
-            .as_ref()
-            .unwrap_or(&self.relay)
-            .request("eth_callBundle", [bundle])
-            .await
+        let (simulation_relay, failover) = match (&self.simulation_relay, &self.simulation_failover)
+        {
+            (Some(simulation_relay), Some(failover)) => (simulation_relay, failover),
+            (simulation_relay, _) => {
+                return simulation_relay
+                    .as_ref()
+                    .unwrap_or(&self.relay)
+                    .request("eth_callBundle", [bundle])
+                    .await
+                    .map_err(FlashbotsMiddlewareError::RelayError)?
+                    .ok_or(FlashbotsMiddlewareError::BundleSimError);
+            }
+        };
+
+        if failover.use_simulation_relay() {
+            let result = simulation_relay.request("eth_callBundle", [bundle]).await;
+            let retryable = matches!(&result, Err(err) if err.is_retryable());
+            if let Some(event) = failover.report(!retryable) {
+                self.notify_simulation_failover(event);
+            }
+            if !retryable || failover.use_simulation_relay() {
+                return result
+                    .map_err(FlashbotsMiddlewareError::RelayError)?
+                    .ok_or(FlashbotsMiddlewareError::BundleSimError);
+            }
+        }
+
+        let fallbacks = match failover.fallbacks() {
+            [] => std::slice::from_ref(&self.relay),
+            fallbacks => fallbacks,
+        };
+        let mut result = Ok(None);
+        for relay in fallbacks {
+            result = relay.request("eth_callBundle", [bundle]).await;
+            if !matches!(&result, Err(err) if err.is_retryable()) {
+                break;
+            }
+        }
+
+        result
             .map_err(FlashbotsMiddlewareError::RelayError)?
             .ok_or(FlashbotsMiddlewareError::BundleSimError)
     }
 
+    fn notify_simulation_failover(&self, event: SimulationFailoverEvent) {
+        if let Some(hook) = &self.simulation_failover_hook {
+            (hook.0)(event);
+        }
+    }
+
+    /// Suggest a coinbase payment for a simulated bundle from the gas
+    /// prices of recently included bundles.
+    ///
+    /// The suggestion is the given percentile (0-100) of the effective gas
+    /// prices of the bundles included in recent blocks that used a
+    /// comparable amount of gas, scaled by the amount of gas the bundle
+    /// used in simulation. Included bundles are fetched from the Flashbots
+    /// blocks API (see [`FLASHBOTS_BLOCKS_API_URL`](crate::FLASHBOTS_BLOCKS_API_URL)),
+    /// which only covers mainnet, using the transport of the relay.
+    ///
+    /// See [`TipSuggestion::from_included_bundles`].
+    pub async fn suggest_tip(
+        &self,
+        bundle: &SimulatedBundle,
+        percentile: f64,
+    ) -> Result<TipSuggestion, FlashbotsMiddlewareError<M, S>> {
+        tip::suggest_tip(self.relay.transport(), bundle.gas_used, percentile)
+            .await
+            .map_err(FlashbotsMiddlewareError::TipSuggestionError)
+    }
+
+    /// Fill the missing fields of the transactions of a bundle, in the
+    /// order of the bundle.
+    ///
+    /// See [`fill_bundle`](crate::fill_bundle).
+    pub async fn fill_bundle(
+        &self,
+        txs: &mut [TypedTransaction],
+    ) -> Result<(), FlashbotsMiddlewareError<M, S>> {
+        fill_bundle(&self.inner, txs)
+            .await
+            .map_err(FlashbotsMiddlewareError::MiddlewareError)
+    }
+
+    /// Fill and sign a transaction so it can be added to a bundle.
+    ///
+    /// The sender and chain ID are set from the signer, and any missing
+    /// fields (such as gas and nonce) are filled in using the inner
+    /// middleware. Note that nonces are filled from the pending state of
+    /// the chain, so transactions from the same sender earlier in the
+    /// bundle are not accounted for.
+    pub async fn sign_transaction<T: Signer>(
+        &self,
+        tx: impl Into<TypedTransaction>,
+        signer: &T,
+    ) -> Result<BundleTransaction, FlashbotsMiddlewareError<M, S>>
+    where
+        T::Error: 'static,
+    {
+        sign_transaction(&self.inner, tx.into(), signer).await
+    }
+
+    /// Fill and sign a payment of the given amount (in wei) to
+    /// `block.coinbase`, so it can be added to a bundle.
+    ///
+    /// See [`coinbase_payment`](crate::coinbase_payment) and
+    /// [`FlashbotsMiddleware::sign_transaction`].
+    pub async fn sign_coinbase_payment<T: Signer>(
+        &self,
+        amount: U256,
+        signer: &T,
+    ) -> Result<BundleTransaction, FlashbotsMiddlewareError<M, S>>
+    where
+        T::Error: 'static,
+    {
+        sign_transaction(&self.inner, coinbase_payment(amount), signer).await
+    }
+
+    /// Simulate a bundle, and add a payment of a percentage of its
+    /// simulated profit to `block.coinbase` as its last transaction.
+    ///
+    /// The bundle must not already contain a payment and must have the
+    /// parameters required for simulation. The payment is signed by the
+    /// inner middleware (e.g. a
+    /// [`SignerMiddleware`](ethers::middleware::SignerMiddleware)) on
+    /// behalf of its default sender, after any transactions of the sender
+    /// in the bundle.
+    ///
+    /// Returns [`FlashbotsMiddlewareError::UnprofitableBundle`] if the
+    /// bundle is not profitable according to the bid, and
+    /// [`FlashbotsMiddlewareError::MissingSender`] if the inner middleware
+    /// has no default sender.
+    pub async fn apply_profit_bid(
+        &self,
+        bundle: &BundleRequest,
+        bid: &ProfitBid,
+    ) -> Result<BundleRequest, FlashbotsMiddlewareError<M, S>> {
+        let simulation = self.simulate_bundle(bundle).await?;
+        let payment = bid
+            .payment(&simulation)
+            .ok_or(FlashbotsMiddlewareError::UnprofitableBundle)?;
+
+        let sender = self
+            .inner
+            .default_sender()
+            .ok_or(FlashbotsMiddlewareError::MissingSender)?;
+        let nonce = self
+            .inner
+            .get_transaction_count(sender, Some(BlockNumber::Pending.into()))
+            .await
+            .map_err(FlashbotsMiddlewareError::MiddlewareError)?;
+        let earlier_txs = simulation
+            .transactions
+            .iter()
+            .filter(|tx| tx.from == sender)
+            .count();
+
+        let mut tx = coinbase_payment(payment);
+        tx.set_from(sender);
+        tx.set_nonce(nonce + earlier_txs);
+        self.inner
+            .fill_transaction(&mut tx, None)
+            .await
+            .map_err(FlashbotsMiddlewareError::MiddlewareError)?;
+        let signature = self
+            .inner
+            .sign_transaction(&tx, sender)
+            .await
+            .map_err(FlashbotsMiddlewareError::MiddlewareError)?;
+
+        Ok(bundle
+            .clone()
+            .push_transaction(BundleTransaction::Raw(tx.rlp_signed(&signature))))
+    }
+
+    /// Fill and sign a contract call so it can be added to a bundle.
+    ///
+    /// See [`FlashbotsMiddleware::sign_transaction`].
+    pub async fn sign_contract_call<B, C, D, T>(
+        &self,
+        call: &FunctionCall<B, C, D>,
+        signer: &T,
+    ) -> Result<BundleTransaction, FlashbotsMiddlewareError<M, S>>
+    where
+        B: Borrow<C>,
+        C: Middleware,
+        T: Signer,
+        T::Error: 'static,
+    {
+        sign_transaction(&self.inner, call.tx.clone(), signer).await
+    }
+
+    /// Fetch a pending transaction from the mempool of the inner
+    /// middleware and add it to the bundle with the given options.
+    ///
+    /// This is useful for including transactions observed in the mempool,
+    /// e.g. when backrunning them.
+    pub async fn push_mempool_transaction(
+        &self,
+        bundle: BundleRequest,
+        tx_hash: TxHash,
+        options: TxOptions,
+    ) -> Result<BundleRequest, FlashbotsMiddlewareError<M, S>> {
+        let tx = fetch_pending_transaction(&self.inner, tx_hash).await?;
+        Ok(bundle.push_transaction_with_options(tx, options)?)
+    }
+
     /// Send a bundle to the relayer.
     ///
     /// See [`eth_sendBundle`][fb_sendBundle] for more information.
@@ -202,27 +683,282 @@ impl<M: Middleware, S: Signer> FlashbotsMiddleware<M, S> {
         if bundle.min_timestamp().xor(bundle.max_timestamp()).is_some() {
             return Err(FlashbotsMiddlewareError::MissingParameters);
         }
+        let reservation = self
+            .spend_guard
+            .as_ref()
+            .map(|guard| guard.reserve(bundle))
+            .transpose()
+            .map_err(
+                |(spend, limit)| FlashbotsMiddlewareError::SpendLimitExceeded { spend, limit },
+            )?;
+
+        let request = self
+            .relay
+            .sign_request(
+                "eth_sendBundle",
+                [bundle.for_profile(self.relay.field_profile())],
+            )
+            .await
+            .map_err(FlashbotsMiddlewareError::RelayError)?;
+        let bundle_hash = send_bundle_request(&self.relay, &request, bundle).await?;
+        if let Some(reservation) = reservation {
+            reservation.commit();
+        }
+
+        let mut pending_bundle = PendingBundle::new(
+            bundle_hash,
+            bundle.block().unwrap(),
+            bundle.transaction_hashes(),
+            self.provider(),
+        );
+        pending_bundle.tracking_uuid = bundle.tracking_uuid();
+        for hook in &self.inclusion_hooks {
+            pending_bundle = pending_bundle.on_inclusion(hook.clone());
+        }
+
+        Ok(pending_bundle)
+    }
+
+    /// Send a bundle to the relayer, and return an [`OwnedPendingBundle`]
+    /// that polls a clone of the provider.
+    ///
+    /// Unlike [`FlashbotsMiddleware::send_bundle`], the pending bundle does
+    /// not borrow the middleware, so it can be spawned.
+    pub async fn send_bundle_owned(
+        &self,
+        bundle: &BundleRequest,
+    ) -> Result<OwnedPendingBundle<<Self as Middleware>::Provider>, FlashbotsMiddlewareError<M, S>>
+    where
+        <Self as Middleware>::Provider: Clone + 'static,
+    {
+        Ok(self.send_bundle(bundle).await?.into_owned())
+    }
+
+    /// Send a bundle to the relayer, splitting it into multiple bundles
+    /// targeting the same block if it has more transactions than the
+    /// relay accepts.
+    ///
+    /// The bundle is split at its split points (see
+    /// [`BundleRequest::split`]), and the bundles are sent in order. If the
+    /// transactions between two split points exceed the transaction limit
+    /// of the relay,
+    /// [`FlashbotsMiddlewareError::TooManyTransactions`] is returned.
+    pub async fn send_split_bundle(
+        &self,
+        bundle: &BundleRequest,
+    ) -> Result<
+        Vec<PendingBundle<'_, <Self as Middleware>::Provider>>,
+        FlashbotsMiddlewareError<M, S>,
+    > {
+        let limit = match self.relay.bundle_limits().max_transactions() {
+            Some(limit) => limit,
+            None => return Ok(vec![self.send_bundle(bundle).await?]),
+        };
+        let bundles = bundle
+            .split(limit)
+            .ok_or(FlashbotsMiddlewareError::TooManyTransactions {
+                count: bundle.transactions().len(),
+                limit,
+            })?;
+
+        let mut pending_bundles = Vec::with_capacity(bundles.len());
+        for bundle in &bundles {
+            pending_bundles.push(self.send_bundle(bundle).await?);
+        }
+        Ok(pending_bundles)
+    }
+
+    /// Send a bundle to the relayer, wait until its target block is
+    /// included, and fetch the receipts of the bundle's transactions.
+    ///
+    /// Returns the block the bundle was included in, and the receipts of
+    /// the transactions in the order of the bundle. If the bundle was not
+    /// included in the target block,
+    /// [`PendingBundleError::BundleNotIncluded`] is returned as a
+    /// [`FlashbotsMiddlewareError::PendingBundleError`].
+    pub async fn send_bundle_and_wait(
+        &self,
+        bundle: &BundleRequest,
+    ) -> Result<(U64, Vec<TransactionReceipt>), FlashbotsMiddlewareError<M, S>> {
+        let pending_bundle = self.send_bundle(bundle).await?;
+        let block = pending_bundle.block;
+        let transactions = pending_bundle.transactions.clone();
+        pending_bundle.await?;
+
+        let receipts = future::try_join_all(transactions.into_iter().map(|tx_hash| async move {
+            self.inner
+                .get_transaction_receipt(tx_hash)
+                .await
+                .map_err(FlashbotsMiddlewareError::MiddlewareError)?
+                .ok_or(FlashbotsMiddlewareError::MissingReceipt(tx_hash))
+        }))
+        .await?;
+
+        Ok((block, receipts))
+    }
+
+    /// Cancel a previously submitted bundle.
+    ///
+    /// The cancellation must be created using [`BundleRequest::cancellation`]
+    /// and must have a target block.
+    pub async fn send_cancellation(
+        &self,
+        cancellation: &BundleRequest,
+    ) -> Result<(), FlashbotsMiddlewareError<M, S>> {
+        if !cancellation.is_cancellation() || cancellation.block().is_none() {
+            return Err(FlashbotsMiddlewareError::MissingParameters);
+        }
+
+        self.relay
+            .request::<_, serde_json::Value>("eth_sendBundle", [cancellation])
+            .await
+            .map_err(FlashbotsMiddlewareError::RelayError)?;
+
+        Ok(())
+    }
+
+    /// Send a MEV-Share bundle to the relay, returning the bundle hash.
+    ///
+    /// See [`mev_sendBundle`][mev_send_bundle] for more information.
+    ///
+    /// [mev_send_bundle]: https://docs.flashbots.net/flashbots-auction/advanced/rpc-endpoint#mev_sendbundle
+    pub async fn send_mev_share_bundle(
+        &self,
+        bundle: &MevShareBundle,
+    ) -> Result<BundleHash, FlashbotsMiddlewareError<M, S>> {
+        // The first block must be set
+        bundle
+            .block()
+            .ok_or(FlashbotsMiddlewareError::MissingParameters)?;
+        bundle.validate()?;
+        if !self.relay.capabilities().mev_share {
+            return Err(FlashbotsMiddlewareError::UnsupportedRequest(
+                "MEV-Share bundles",
+            ));
+        }
 
         let response: Option<SendBundleResponse> = self
             .relay
-            .request("eth_sendBundle", [bundle])
+            .request("mev_sendBundle", [bundle])
             .await
             .map_err(FlashbotsMiddlewareError::RelayError)?;
 
-        match response {
-            Some(r) => Ok(PendingBundle::new(
-                r.bundle_hash,
-                bundle.block().unwrap(),
-                bundle.transaction_hashes(),
-                self.provider(),
-            )),
-            None => Ok(PendingBundle::new(
-                None,
-                bundle.block().unwrap(),
-                bundle.transaction_hashes(),
-                self.provider(),
-            )),
+        response
+            .and_then(|r| r.bundle_hash)
+            .ok_or(FlashbotsMiddlewareError::EmptyResponse)
+    }
+
+    /// Backrun a transaction in the given block, returning the bundle hash.
+    ///
+    /// This sends a MEV-Share bundle containing the target transaction
+    /// (referenced by its hash) followed by the given transaction. See
+    /// [`MevShareBundle::backrun`].
+    pub async fn backrun_transaction<T: Into<BundleTransaction>>(
+        &self,
+        target_tx_hash: TxHash,
+        tx: T,
+        target_block: U64,
+    ) -> Result<BundleHash, FlashbotsMiddlewareError<M, S>> {
+        self.send_mev_share_bundle(&MevShareBundle::backrun(target_tx_hash, tx, target_block))
+            .await
+    }
+
+    /// Simulate a MEV-Share bundle.
+    ///
+    /// Bundles that contain transaction hashes can only be simulated once
+    /// the referenced transactions are known to the relay.
+    ///
+    /// See [`mev_simBundle`][sim_bundle] for more information.
+    ///
+    /// [sim_bundle]: https://docs.flashbots.net/flashbots-auction/advanced/rpc-endpoint#mev_simbundle
+    pub async fn simulate_mev_share_bundle(
+        &self,
+        bundle: &MevShareBundle,
+    ) -> Result<MevShareSimulation, FlashbotsMiddlewareError<M, S>> {
+        bundle
+            .block()
+            .ok_or(FlashbotsMiddlewareError::MissingParameters)?;
+        bundle.validate()?;
+
+        let relay = self.simulation_relay.as_ref().unwrap_or(&self.relay);
+        if !relay.capabilities().mev_share {
+            return Err(FlashbotsMiddlewareError::UnsupportedRequest(
+                "MEV-Share bundles",
+            ));
         }
+        relay
+            .request("mev_simBundle", [bundle])
+            .await
+            .map_err(FlashbotsMiddlewareError::RelayError)?
+            .ok_or(FlashbotsMiddlewareError::EmptyResponse)
+    }
+
+    /// Cancel a previously submitted MEV-Share bundle by its bundle hash.
+    ///
+    /// Cancellation is best effort: the bundle may already have been
+    /// included, or sent to builders that do not support cancellation.
+    pub async fn cancel_mev_share_bundle(
+        &self,
+        bundle_hash: BundleHash,
+    ) -> Result<(), FlashbotsMiddlewareError<M, S>> {
+        if !self.relay.capabilities().mev_share {
+            return Err(FlashbotsMiddlewareError::UnsupportedRequest(
+                "MEV-Share bundles",
+            ));
+        }
+
+        self.relay
+            .request::<_, serde_json::Value>(
+                "mev_cancelBundleByHash",
+                [CancelBundleByHashParams { bundle_hash }],
+            )
+            .await
+            .map_err(FlashbotsMiddlewareError::RelayError)?;
+
+        Ok(())
+    }
+
+    /// Replace a previously submitted MEV-Share bundle, returning the
+    /// hash of the new bundle.
+    ///
+    /// The new bundle is validated before the old bundle is cancelled, so
+    /// an invalid replacement leaves the old bundle in place.
+    pub async fn replace_mev_share_bundle(
+        &self,
+        bundle_hash: BundleHash,
+        bundle: &MevShareBundle,
+    ) -> Result<BundleHash, FlashbotsMiddlewareError<M, S>> {
+        bundle
+            .block()
+            .ok_or(FlashbotsMiddlewareError::MissingParameters)?;
+        bundle.validate()?;
+
+        self.cancel_mev_share_bundle(bundle_hash).await?;
+        self.send_mev_share_bundle(bundle).await
+    }
+
+    /// Send a private transaction to the relay, returning the transaction
+    /// hash.
+    ///
+    /// See [`eth_sendPrivateTransaction`][send_private_transaction] for more
+    /// information.
+    ///
+    /// [send_private_transaction]: https://docs.flashbots.net/flashbots-auction/advanced/rpc-endpoint#eth_sendprivatetransaction
+    pub async fn send_private_transaction(
+        &self,
+        tx: &PrivateTransaction,
+    ) -> Result<TxHash, FlashbotsMiddlewareError<M, S>> {
+        if !self.relay.capabilities().private_transactions {
+            return Err(FlashbotsMiddlewareError::UnsupportedRequest(
+                "private transactions",
+            ));
+        }
+
+        self.relay
+            .request("eth_sendPrivateTransaction", [tx])
+            .await
+            .map_err(FlashbotsMiddlewareError::RelayError)?
+            .ok_or(FlashbotsMiddlewareError::EmptyResponse)
     }
 
     /// Get stats for a particular bundle.
@@ -244,6 +980,52 @@ impl<M: Middleware, S: Signer> FlashbotsMiddleware<M, S> {
             .ok_or(FlashbotsMiddlewareError::BundleStatsError)
     }
 
+    /// Get stats for a pending bundle.
+    ///
+    /// The tracking uuid of the pending bundle (if any) is carried over
+    /// to the returned stats.
+    pub async fn get_pending_bundle_stats<P>(
+        &self,
+        pending_bundle: &PendingBundle<'_, P>,
+    ) -> Result<BundleStats, FlashbotsMiddlewareError<M, S>> {
+        let bundle_hash = pending_bundle
+            .bundle_hash
+            .ok_or(FlashbotsMiddlewareError::BundleStatsError)?;
+
+        let mut stats = self
+            .get_bundle_stats(bundle_hash, pending_bundle.block)
+            .await?;
+        stats.tracking_uuid = pending_bundle.tracking_uuid;
+
+        Ok(stats)
+    }
+
+    /// Poll the stats of a pending bundle at the given interval, calling
+    /// `on_stats` with the stats after each poll.
+    ///
+    /// This returns once the cancel handle of the pending bundle is
+    /// cancelled (see [`PendingBundle::cancel_handle`]), or if fetching the
+    /// stats fails.
+    pub async fn poll_pending_bundle_stats<P>(
+        &self,
+        pending_bundle: &PendingBundle<'_, P>,
+        interval: Duration,
+        mut on_stats: impl FnMut(&BundleStats),
+    ) -> Result<(), FlashbotsMiddlewareError<M, S>>
+    where
+        P: JsonRpcClient,
+    {
+        let cancel = pending_bundle.cancel_handle();
+        let poll = async {
+            loop {
+                let stats = self.get_pending_bundle_stats(pending_bundle).await?;
+                on_stats(&stats);
+                tokio::time::sleep(interval).await;
+            }
+        };
+        cancel.run_until_cancelled(poll).await.unwrap_or(Ok(()))
+    }
+
     /// Get stats for your searcher identity.
     ///
     /// Your searcher identity is determined by the signer you
@@ -268,6 +1050,152 @@ impl<M: Middleware, S: Signer> FlashbotsMiddleware<M, S> {
     }
 }
 
+/// The parameters of an `eth_callBundle` request.
+///
+/// Bundles without a simulation block are simulated on top of the latest
+/// block.
+#[derive(Serialize)]
+struct SimulationParams<'a> {
+    #[serde(flatten)]
+    bundle: &'a BundleRequest,
+    #[serde(rename = "stateBlockNumber")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest: Option<&'static str>,
+}
+
+impl<'a> SimulationParams<'a> {
+    /// Returns `None` if parameters required to simulate the bundle are
+    /// missing.
+    fn new(bundle: &'a BundleRequest, strict: bool) -> Option<Self> {
+        bundle.block().and(bundle.simulation_timestamp())?;
+        if strict && bundle.simulation_state().is_none() {
+            return None;
+        }
+
+        Some(Self {
+            bundle,
+            latest: bundle.simulation_state().is_none().then_some("latest"),
+        })
+    }
+}
+
+/// Sends a signed `eth_sendBundle` request to the relay, handling
+/// duplicate submissions according to the duplicate policy of the relay.
+///
+/// The bundle must have a target block.
+async fn send_bundle_request<M: Middleware, S: Signer>(
+    relay: &Relay<S>,
+    request: &SignedRequest,
+    bundle: &BundleRequest,
+) -> Result<Option<BundleHash>, FlashbotsMiddlewareError<M, S>> {
+    let limits = relay.bundle_limits();
+    if let Some(limit) = limits.max_transactions() {
+        let count = bundle.transactions().len();
+        if count > limit {
+            return Err(FlashbotsMiddlewareError::TooManyTransactions { count, limit });
+        }
+    }
+    if let Some(limit) = limits.max_size() {
+        let size = request.body().len();
+        if size > limit {
+            return Err(FlashbotsMiddlewareError::BundleTooLarge { size, limit });
+        }
+    }
+
+    let block = bundle.block().expect("the target block is set");
+    let cache = relay.submission_cache();
+    if let Some(cache) = cache {
+        if let Some(bundle_hash) = cache.reserve(block, request.content_hash()) {
+            return match cache.policy() {
+                DuplicatePolicy::Skip => Ok(bundle_hash),
+                DuplicatePolicy::Reject => Err(FlashbotsMiddlewareError::DuplicateSubmission),
+            };
+        }
+    }
+
+    let result = relay
+        .send_signed::<SendBundleResponse>(request)
+        .await
+        .map(|response| response.and_then(|r| r.bundle_hash));
+    if let Some(cache) = cache {
+        match &result {
+            Ok(bundle_hash) => cache.complete(request.content_hash(), *bundle_hash),
+            Err(_) => cache.release(request.content_hash()),
+        }
+    }
+
+    result.map_err(FlashbotsMiddlewareError::RelayError)
+}
+
+async fn sign_transaction<M: Middleware, S: Signer, T: Signer>(
+    inner: &M,
+    mut tx: TypedTransaction,
+    signer: &T,
+) -> Result<BundleTransaction, FlashbotsMiddlewareError<M, S>>
+where
+    T::Error: 'static,
+{
+    tx.set_from(signer.address());
+    if tx.chain_id().is_none() {
+        tx.set_chain_id(signer.chain_id());
+    }
+
+    inner
+        .fill_transaction(&mut tx, None)
+        .await
+        .map_err(FlashbotsMiddlewareError::MiddlewareError)?;
+
+    let signature = signer
+        .sign_transaction(&tx)
+        .await
+        .map_err(|err| FlashbotsMiddlewareError::TransactionSignerError(Box::new(err)))?;
+
+    Ok(BundleTransaction::Raw(tx.rlp_signed(&signature)))
+}
+
+async fn verify_chain_id<'a, M: Middleware, S: Signer + 'a>(
+    inner: &M,
+    relays: impl IntoIterator<Item = &'a Relay<S>>,
+) -> Result<(), FlashbotsMiddlewareError<M, S>> {
+    let mut relays = relays
+        .into_iter()
+        .filter_map(|relay| relay.chain_id().map(|chain_id| (relay, chain_id)))
+        .peekable();
+    if relays.peek().is_none() {
+        return Ok(());
+    }
+
+    let actual = inner
+        .get_chainid()
+        .await
+        .map_err(FlashbotsMiddlewareError::MiddlewareError)?;
+    for (relay, expected) in relays {
+        if actual != U256::from(expected) {
+            return Err(FlashbotsMiddlewareError::ChainIdMismatch {
+                relay: relay.url().clone(),
+                expected,
+                actual,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+async fn fetch_pending_transaction<M: Middleware, S: Signer>(
+    inner: &M,
+    tx_hash: TxHash,
+) -> Result<BundleTransaction, FlashbotsMiddlewareError<M, S>> {
+    let tx = inner
+        .get_transaction(tx_hash)
+        .await
+        .map_err(FlashbotsMiddlewareError::MiddlewareError)?
+        .filter(|tx| tx.block_number.is_none())
+        .ok_or(FlashbotsMiddlewareError::TransactionNotPending(tx_hash))?;
+
+    Ok(BundleTransaction::Raw(tx.rlp()))
+}
+
 #[async_trait]
 impl<M, S> Middleware for FlashbotsMiddleware<M, S>
 where
@@ -373,18 +1301,73 @@ pub struct BroadcasterMiddleware<M, S> {
     inner: M,
     relays: Vec<Relay<S>>,
     simulation_relay: Relay<S>,
+    strict_simulation: bool,
+    spend_guard: Option<SpendGuard>,
+    inclusion_hooks: Vec<InclusionHook>,
 }
 
 impl<M: Middleware, S: Signer> BroadcasterMiddleware<M, S> {
     /// Initialize a new Flashbots middleware.
     ///
-    /// The signer is used to sign requests to the relay.
+    /// The signer is used to sign requests to the relay. All relays share a
+    /// single HTTP client.
+    ///
+    /// Each relay gets a clone of the signer. Signers that cannot be cloned
+    /// can be shared by the relays using [`SharedSigner`](crate::SharedSigner).
     pub fn new(
         inner: M,
         relay_urls: Vec<Url>,
         simulation_relay: impl Into<Url>,
         relay_signer: S,
     ) -> Self
+    where
+        S: Clone,
+    {
+        Self::with_transport(
+            inner,
+            relay_urls,
+            simulation_relay,
+            relay_signer,
+            default_transport(),
+        )
+    }
+
+    /// Initialize a new Flashbots middleware where all relays send
+    /// requests using the given HTTP client.
+    ///
+    /// The relays share the connection pool of the client, so connections
+    /// (and TLS sessions) are reused across submissions.
+    #[cfg(feature = "reqwest-transport")]
+    pub fn with_client(
+        inner: M,
+        relay_urls: Vec<Url>,
+        simulation_relay: impl Into<Url>,
+        relay_signer: S,
+        client: Client,
+    ) -> Self
+    where
+        S: Clone,
+    {
+        Self::with_transport(
+            inner,
+            relay_urls,
+            simulation_relay,
+            relay_signer,
+            Arc::new(client),
+        )
+    }
+
+    /// Initialize a new Flashbots middleware where all relays send
+    /// requests using the given HTTP transport.
+    ///
+    /// See [`HttpTransport`].
+    pub fn with_transport(
+        inner: M,
+        relay_urls: Vec<Url>,
+        simulation_relay: impl Into<Url>,
+        relay_signer: S,
+        transport: Arc<dyn HttpTransport>,
+    ) -> Self
     where
         S: Clone,
     {
@@ -392,23 +1375,147 @@ impl<M: Middleware, S: Signer> BroadcasterMiddleware<M, S> {
             inner,
             relays: relay_urls
                 .into_iter()
-                .map(|r| Relay::new(r, Some(relay_signer.clone())))
+                .map(|r| Relay::with_transport(r, Some(relay_signer.clone()), transport.clone()))
                 .collect(),
-            simulation_relay: Relay::new(simulation_relay, Some(relay_signer)),
+            simulation_relay: Relay::with_transport(
+                simulation_relay,
+                Some(relay_signer),
+                transport,
+            ),
+            strict_simulation: false,
+            spend_guard: None,
+            inclusion_hooks: Vec::new(),
         }
     }
 
+    /// Initialize a new Flashbots middleware that broadcasts bundles to the
+    /// given well-known builders.
+    ///
+    /// Each relay uses the dialect and capabilities of its builder. See
+    /// [`builders`](crate::builders).
+    pub fn from_endpoints(
+        inner: M,
+        endpoints: &[BuilderEndpoint],
+        simulation_relay: impl Into<Url>,
+        relay_signer: S,
+    ) -> Self
+    where
+        S: Clone,
+    {
+        let transport = default_transport();
+        Self {
+            inner,
+            relays: endpoints
+                .iter()
+                .map(|endpoint| {
+                    Relay::from_endpoint_with_transport(
+                        endpoint,
+                        Some(relay_signer.clone()),
+                        transport.clone(),
+                    )
+                })
+                .collect(),
+            simulation_relay: Relay::with_transport(
+                simulation_relay,
+                Some(relay_signer),
+                transport,
+            ),
+            strict_simulation: false,
+            spend_guard: None,
+            inclusion_hooks: Vec::new(),
+        }
+    }
+
+    /// Initialize a new Flashbots middleware that broadcasts bundles to all
+    /// known builders on the given network.
+    ///
+    /// See [`BroadcasterMiddleware::from_endpoints`].
+    pub fn for_network(
+        inner: M,
+        network: Network,
+        simulation_relay: impl Into<Url>,
+        relay_signer: S,
+    ) -> Self
+    where
+        S: Clone,
+    {
+        Self::from_endpoints(inner, network.builders(), simulation_relay, relay_signer)
+    }
+
+    /// Check that the inner middleware is on the same chain as the relays.
+    ///
+    /// See [`FlashbotsMiddleware::verify`].
+    pub async fn verify(&self) -> Result<(), FlashbotsMiddlewareError<M, S>> {
+        verify_chain_id(&self.inner, &self.relays).await
+    }
+
     /// Get the relay client used by the middleware.
     pub fn relay(&self) -> &Vec<Relay<S>> {
         &self.relays
     }
 
+    /// Get a mutable reference to the relay clients used by the middleware,
+    /// e.g. to configure them.
+    pub fn relays_mut(&mut self) -> &mut Vec<Relay<S>> {
+        &mut self.relays
+    }
+
+    /// Get latency and success statistics of each relay, in order,
+    /// followed by the simulation relay.
+    ///
+    /// See [`RelayStats`].
+    pub fn relay_stats(&self) -> Vec<RelayStats> {
+        self.relays
+            .iter()
+            .chain(std::iter::once(&self.simulation_relay))
+            .map(Relay::stats)
+            .collect()
+    }
+
     /// Get the relay client used by the middleware to simulate
     /// bundles.
     pub fn simulation_relay(&self) -> &Relay<S> {
         &self.simulation_relay
     }
 
+    /// Whether bundles can only be simulated if their simulation block is
+    /// set.
+    pub fn strict_simulation(&self) -> bool {
+        self.strict_simulation
+    }
+
+    /// Require the simulation block of bundles to be set when simulating
+    /// them.
+    ///
+    /// By default, bundles without a simulation block are simulated on
+    /// top of the latest block.
+    pub fn set_strict_simulation(&mut self, strict: bool) {
+        self.strict_simulation = strict;
+    }
+
+    /// Get the spend limit of the middleware (if any).
+    pub fn spend_limit(&self) -> Option<&SpendLimit> {
+        self.spend_guard.as_ref().map(SpendGuard::limit)
+    }
+
+    /// Set a limit on the amount bundles may spend.
+    ///
+    /// See [`SpendLimit`].
+    pub fn set_spend_limit(&mut self, limit: SpendLimit) {
+        self.spend_guard = Some(SpendGuard::new(limit));
+    }
+
+    /// Add a hook that is called with the outcome of every pending bundle
+    /// returned by the middleware.
+    ///
+    /// Bundles are sent to every relay, so the hook is called once for
+    /// each relay the bundle was sent to.
+    ///
+    /// See [`InclusionHook`].
+    pub fn on_inclusion(&mut self, hook: InclusionHook) {
+        self.inclusion_hooks.push(hook);
+    }
+
     /// Simulate a bundle.
     ///
     /// See [`eth_callBundle`][fb_callBundle] for more information.
@@ -418,10 +1525,7 @@ impl<M: Middleware, S: Signer> BroadcasterMiddleware<M, S> {
         &self,
         bundle: &BundleRequest,
     ) -> Result<SimulatedBundle, FlashbotsMiddlewareError<M, S>> {
-        bundle
-            .block()
-            .and(bundle.simulation_block())
-            .and(bundle.simulation_timestamp())
+        let bundle = &SimulationParams::new(bundle, self.strict_simulation)
             .ok_or(FlashbotsMiddlewareError::MissingParameters)?;
 
         self.simulation_relay
@@ -431,6 +1535,94 @@ impl<M: Middleware, S: Signer> BroadcasterMiddleware<M, S> {
             .ok_or(FlashbotsMiddlewareError::BundleSimError)
     }
 
+    /// Suggest a coinbase payment for a simulated bundle from the gas
+    /// prices of recently included bundles.
+    ///
+    /// The suggestion is the given percentile (0-100) of the effective gas
+    /// prices of the bundles included in recent blocks that used a
+    /// comparable amount of gas, scaled by the amount of gas the bundle
+    /// used in simulation. Included bundles are fetched from the Flashbots
+    /// blocks API (see [`FLASHBOTS_BLOCKS_API_URL`](crate::FLASHBOTS_BLOCKS_API_URL)),
+    /// which only covers mainnet, using the transport of the simulation relay.
+    ///
+    /// See [`TipSuggestion::from_included_bundles`].
+    pub async fn suggest_tip(
+        &self,
+        bundle: &SimulatedBundle,
+        percentile: f64,
+    ) -> Result<TipSuggestion, FlashbotsMiddlewareError<M, S>> {
+        tip::suggest_tip(
+            self.simulation_relay.transport(),
+            bundle.gas_used,
+            percentile,
+        )
+        .await
+        .map_err(FlashbotsMiddlewareError::TipSuggestionError)
+    }
+
+    /// Fill the missing fields of the transactions of a bundle, in the
+    /// order of the bundle.
+    ///
+    /// See [`fill_bundle`](crate::fill_bundle).
+    pub async fn fill_bundle(
+        &self,
+        txs: &mut [TypedTransaction],
+    ) -> Result<(), FlashbotsMiddlewareError<M, S>> {
+        fill_bundle(&self.inner, txs)
+            .await
+            .map_err(FlashbotsMiddlewareError::MiddlewareError)
+    }
+
+    /// Fill and sign a transaction so it can be added to a bundle.
+    ///
+    /// The sender and chain ID are set from the signer, and any missing
+    /// fields (such as gas and nonce) are filled in using the inner
+    /// middleware. Note that nonces are filled from the pending state of
+    /// the chain, so transactions from the same sender earlier in the
+    /// bundle are not accounted for.
+    pub async fn sign_transaction<T: Signer>(
+        &self,
+        tx: impl Into<TypedTransaction>,
+        signer: &T,
+    ) -> Result<BundleTransaction, FlashbotsMiddlewareError<M, S>>
+    where
+        T::Error: 'static,
+    {
+        sign_transaction(&self.inner, tx.into(), signer).await
+    }
+
+    /// Fill and sign a contract call so it can be added to a bundle.
+    ///
+    /// See [`BroadcasterMiddleware::sign_transaction`].
+    pub async fn sign_contract_call<B, C, D, T>(
+        &self,
+        call: &FunctionCall<B, C, D>,
+        signer: &T,
+    ) -> Result<BundleTransaction, FlashbotsMiddlewareError<M, S>>
+    where
+        B: Borrow<C>,
+        C: Middleware,
+        T: Signer,
+        T::Error: 'static,
+    {
+        sign_transaction(&self.inner, call.tx.clone(), signer).await
+    }
+
+    /// Fetch a pending transaction from the mempool of the inner
+    /// middleware and add it to the bundle with the given options.
+    ///
+    /// This is useful for including transactions observed in the mempool,
+    /// e.g. when backrunning them.
+    pub async fn push_mempool_transaction(
+        &self,
+        bundle: BundleRequest,
+        tx_hash: TxHash,
+        options: TxOptions,
+    ) -> Result<BundleRequest, FlashbotsMiddlewareError<M, S>> {
+        let tx = fetch_pending_transaction(&self.inner, tx_hash).await?;
+        Ok(bundle.push_transaction_with_options(tx, options)?)
+    }
+
     /// Broadcast a bundle to the builders.
     ///
     /// See [`eth_sendBundle`][fb_sendBundle] for more information.
@@ -452,35 +1644,140 @@ impl<M: Middleware, S: Signer> BroadcasterMiddleware<M, S> {
         bundle
             .block()
             .ok_or(FlashbotsMiddlewareError::MissingParameters)?;
+        let reservation = self
+            .spend_guard
+            .as_ref()
+            .map(|guard| guard.reserve(bundle))
+            .transpose()
+            .map_err(
+                |(spend, limit)| FlashbotsMiddlewareError::SpendLimitExceeded { spend, limit },
+            )?;
+
+        let (requests, relay_requests) = self.sign_for_relays(bundle).await?;
+        let requests = &requests;
 
         let futures = self
             .relays
             .iter()
-            .map(|relay| async move {
-                let response = relay.request("eth_sendBundle", [bundle]).await;
-                response
-                    .map(|response: Option<SendBundleResponse>| match response {
-                        Some(r) => PendingBundle::new(
-                            r.bundle_hash,
-                            bundle.block().unwrap(),
-                            bundle.transaction_hashes(),
-                            self.provider(),
-                        ),
-                        None => PendingBundle::new(
-                            None,
-                            bundle.block().unwrap(),
-                            bundle.transaction_hashes(),
-                            self.provider(),
-                        ),
-                    })
-                    .map_err(FlashbotsMiddlewareError::RelayError)
+            .zip(relay_requests)
+            .map(|(relay, index)| async move {
+                let request =
+                    &requests[index.ok_or(FlashbotsMiddlewareError::UnsupportedBundle)?];
+                let bundle_hash = send_bundle_request(relay, request, bundle).await?;
+
+                let mut pending_bundle = PendingBundle::new(
+                    bundle_hash,
+                    bundle.block().unwrap(),
+                    bundle.transaction_hashes(),
+                    self.provider(),
+                );
+                pending_bundle.tracking_uuid = bundle.tracking_uuid();
+                for hook in &self.inclusion_hooks {
+                    pending_bundle = pending_bundle.on_inclusion(hook.clone());
+                }
+                Ok(pending_bundle)
             })
             .collect::<Vec<_>>();
 
         let responses = future::join_all(futures).await;
+        if responses.iter().any(Result::is_ok) {
+            if let Some(reservation) = reservation {
+                reservation.commit();
+            }
+        }
 
         Ok(responses)
     }
+
+    /// Cancel a previously submitted bundle on all builders.
+    ///
+    /// The cancellation must be created using [`BundleRequest::cancellation`]
+    /// and must have a target block. The result of each builder's
+    /// cancellation is returned in the same order as the builders.
+    pub async fn send_cancellation(
+        &self,
+        cancellation: &BundleRequest,
+    ) -> Result<Vec<Result<(), FlashbotsMiddlewareError<M, S>>>, FlashbotsMiddlewareError<M, S>>
+    {
+        if !cancellation.is_cancellation() || cancellation.block().is_none() {
+            return Err(FlashbotsMiddlewareError::MissingParameters);
+        }
+
+        let (requests, relay_requests) = self.sign_for_relays(cancellation).await?;
+        let requests = &requests;
+
+        let futures = self
+            .relays
+            .iter()
+            .zip(relay_requests)
+            .map(|(relay, index)| async move {
+                let request =
+                    &requests[index.ok_or(FlashbotsMiddlewareError::UnsupportedBundle)?];
+                relay
+                    .send_signed::<serde_json::Value>(request)
+                    .await
+                    .map(|_| ())
+                    .map_err(FlashbotsMiddlewareError::RelayError)
+            });
+
+        Ok(future::join_all(futures).await)
+    }
+
+    /// Shape and sign a bundle for each of the relays.
+    ///
+    /// Relays that receive the same payload share a signed request, so
+    /// each distinct payload is only signed once. For each relay, the index
+    /// of its signed request is returned, or `None` if the builder does not
+    /// support the bundle.
+    async fn sign_for_relays(
+        &self,
+        bundle: &BundleRequest,
+    ) -> Result<(Vec<SignedRequest>, Vec<Option<usize>>), FlashbotsMiddlewareError<M, S>> {
+        let mut payloads: Vec<(String, serde_json::Value, SignatureScheme)> = Vec::new();
+        let mut requests = Vec::new();
+        let mut relay_requests = Vec::with_capacity(self.relays.len());
+
+        for relay in &self.relays {
+            let stripped = relay.capabilities().unsupported_fields(bundle);
+            let bundle = match relay.capabilities().shape(bundle) {
+                Some(bundle) => bundle,
+                None => {
+                    relay_requests.push(None);
+                    continue;
+                }
+            };
+            if !stripped.is_empty() {
+                tracing::warn!(
+                    target: "ethers_flashbots::middleware",
+                    relay = %relay.url(),
+                    fields = ?stripped,
+                    "Removed fields the builder does not support from the bundle"
+                );
+            }
+
+            let bundle = bundle.for_profile(relay.field_profile());
+            let params = serde_json::to_value([&*bundle])
+                .map_err(|err| FlashbotsMiddlewareError::RelayError(err.into()))?;
+            let (method, params) = relay.dialect().translate_request("eth_sendBundle", params);
+            let payload = (method, params, relay.signature_scheme());
+            let index = match payloads.iter().position(|p| *p == payload) {
+                Some(index) => index,
+                None => {
+                    requests.push(
+                        self.simulation_relay
+                            .sign_translated("eth_sendBundle", &payload.0, &payload.1, payload.2)
+                            .await
+                            .map_err(FlashbotsMiddlewareError::RelayError)?,
+                    );
+                    payloads.push(payload);
+                    requests.len() - 1
+                }
+            };
+            relay_requests.push(Some(index));
+        }
+
+        Ok((requests, relay_requests))
+    }
 }
 
 #[async_trait]
@@ -526,3 +1823,290 @@ where
             .interval(self.provider().get_interval()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{wallet, MockTransport, SIMULATED_BUNDLE};
+    use crate::{builders, BundleLimits};
+    use ethers::providers::Provider;
+
+    #[tokio::test]
+    async fn verify_chain_id() {
+        let (provider, mock) = Provider::mocked();
+        let signer = wallet();
+        let middleware =
+            FlashbotsMiddleware::from_endpoint(provider, &builders::sepolia::FLASHBOTS, signer);
+
+        mock.push(U256::from(builders::sepolia::CHAIN_ID)).unwrap();
+        assert!(middleware.verify().await.is_ok());
+
+        mock.push(U256::from(builders::mainnet::CHAIN_ID)).unwrap();
+        assert!(matches!(
+            middleware.verify().await,
+            Err(FlashbotsMiddlewareError::ChainIdMismatch { expected, .. })
+                if expected == builders::sepolia::CHAIN_ID
+        ));
+    }
+
+    #[cfg(feature = "reqwest-transport")]
+    #[tokio::test]
+    async fn simulation_failover_signs_requests() {
+        use crate::fixtures::{json_response, serve};
+
+        let body = format!(
+            r#"{{"id":1,"jsonrpc":"2.0","result":{}}}"#,
+            SIMULATED_BUNDLE
+        );
+        let (fallback, server) = serve(vec![json_response("200 OK", &body)]).await;
+
+        let (provider, _mock) = Provider::mocked();
+        let signer = wallet();
+        let mut middleware = FlashbotsMiddleware::new(
+            provider,
+            Url::parse("http://localhost:8545").unwrap(),
+            signer.clone(),
+        );
+        // Nothing listens on the simulation relay, so simulations fail over
+        // right away
+        middleware.set_simulation_relay(Url::parse("http://127.0.0.1:1").unwrap());
+        middleware.set_simulation_failover(
+            SimulationFailover::new()
+                .set_threshold(1)
+                .set_fallbacks(vec![fallback]),
+        );
+
+        let bundle = BundleRequest::new()
+            .set_block(10.into())
+            .set_simulation_block(9.into())
+            .set_simulation_timestamp(0);
+        let simulated = middleware.simulate_bundle(&bundle).await.unwrap();
+        assert_eq!(simulated.simulation_block, U64::from(5221585));
+
+        let request = server.await.unwrap().remove(0).to_lowercase();
+        let signature = format!("x-flashbots-signature: {:?}:", signer.address());
+        assert!(request.contains(&signature));
+    }
+
+    #[tokio::test]
+    async fn profit_bid_without_sender() {
+        let (provider, _mock) = Provider::mocked();
+        let mut middleware = FlashbotsMiddleware::new(
+            provider,
+            Url::parse("http://localhost:8545").unwrap(),
+            wallet(),
+        );
+        middleware.relay_mut().set_transport(MockTransport::new(
+            serde_json::from_str(SIMULATED_BUNDLE).unwrap(),
+        ));
+
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(10.into())
+            .set_simulation_block(9.into())
+            .set_simulation_timestamp(0);
+        let bid = ProfitBid::new(50, |_| Some(1000.into()));
+        assert!(matches!(
+            middleware.apply_profit_bid(&bundle, &bid).await,
+            Err(FlashbotsMiddlewareError::MissingSender)
+        ));
+    }
+
+    #[tokio::test]
+    async fn send_bundle_owned() {
+        let (provider, mock) = Provider::mocked();
+        let mut middleware = FlashbotsMiddleware::new(
+            provider,
+            Url::parse("http://localhost:8545").unwrap(),
+            wallet(),
+        );
+        middleware
+            .relay_mut()
+            .set_transport(MockTransport::bundles());
+
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(10.into());
+        mock.push(ethers::core::types::Block {
+            number: Some(10.into()),
+            transactions: bundle.transaction_hashes(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let pending = middleware
+            .send_bundle_owned(&bundle)
+            .await
+            .unwrap()
+            .interval(Duration::from_millis(1));
+        let res = tokio::spawn(pending).await.unwrap();
+        assert_eq!(res.unwrap(), Some(BundleHash::from_low_u64_be(1)));
+    }
+
+    #[tokio::test]
+    async fn poll_pending_bundle_stats() {
+        let (provider, _mock) = Provider::mocked();
+        let mut middleware = FlashbotsMiddleware::new(
+            provider,
+            Url::parse("http://localhost:8545").unwrap(),
+            wallet(),
+        );
+        middleware
+            .relay_mut()
+            .set_transport(MockTransport::new(serde_json::json!({
+                "isHighPriority": true,
+                "isSimulated": false,
+            })));
+
+        let pending = PendingBundle::new(
+            Some(BundleHash::from_low_u64_be(1)),
+            10.into(),
+            vec![],
+            middleware.provider(),
+        );
+        let cancel = pending.cancel_handle();
+        let mut polls = 0;
+        middleware
+            .poll_pending_bundle_stats(&pending, Duration::from_millis(1), |stats| {
+                assert!(stats.is_high_priority);
+                polls += 1;
+                if polls == 2 {
+                    cancel.cancel();
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(polls, 2);
+        assert!(matches!(pending.await, Err(PendingBundleError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn duplicate_submissions() {
+        let (provider, _mock) = Provider::mocked();
+        let signer = wallet();
+        let mut middleware = FlashbotsMiddleware::new(
+            provider,
+            Url::parse("http://localhost:8545").unwrap(),
+            signer,
+        );
+        let transport = MockTransport::bundles();
+        middleware.relay_mut().set_transport(transport.clone());
+        middleware
+            .relay_mut()
+            .set_duplicate_policy(DuplicatePolicy::Skip);
+
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(10.into());
+        let first = middleware.send_bundle(&bundle).await.unwrap().bundle_hash;
+        let second = middleware.send_bundle(&bundle).await.unwrap().bundle_hash;
+        assert_eq!(first, Some(BundleHash::from_low_u64_be(1)));
+        assert_eq!(first, second);
+        assert_eq!(transport.requests().len(), 1);
+
+        // Bundles for other blocks are not duplicates
+        middleware
+            .send_bundle(&bundle.clone_for_block(11.into()))
+            .await
+            .unwrap();
+        assert_eq!(transport.requests().len(), 2);
+
+        middleware
+            .relay_mut()
+            .set_duplicate_policy(DuplicatePolicy::Reject);
+        middleware.send_bundle(&bundle).await.unwrap();
+        assert!(matches!(
+            middleware.send_bundle(&bundle).await,
+            Err(FlashbotsMiddlewareError::DuplicateSubmission)
+        ));
+    }
+
+    #[tokio::test]
+    async fn spend_of_failed_sends() {
+        let (provider, _mock) = Provider::mocked();
+        let relay = Url::parse("http://localhost:8545").unwrap();
+        let mut middleware = FlashbotsMiddleware::new(provider, relay.clone(), wallet());
+        let transport = MockTransport::bundles();
+        transport.set_unreachable(vec![relay]);
+        middleware.relay_mut().set_transport(transport.clone());
+        middleware.set_spend_limit(
+            SpendLimit::new(vec![]).set_max_per_window(100.into(), Duration::from_secs(60)),
+        );
+
+        let bundle = |value: u64| {
+            BundleRequest::new()
+                .push_transaction(ethers::core::types::Transaction {
+                    value: value.into(),
+                    ..Default::default()
+                })
+                .set_block(10.into())
+        };
+        assert!(matches!(
+            middleware.send_bundle(&bundle(60)).await,
+            Err(FlashbotsMiddlewareError::RelayError(_))
+        ));
+
+        // The failed bundle does not count towards the limit
+        transport.set_unreachable(vec![]);
+        middleware.send_bundle(&bundle(61)).await.unwrap();
+        assert!(matches!(
+            middleware.send_bundle(&bundle(60)).await,
+            Err(FlashbotsMiddlewareError::SpendLimitExceeded { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn bundle_limits() {
+        let (provider, _mock) = Provider::mocked();
+        let signer = wallet();
+        let mut middleware = FlashbotsMiddleware::new(
+            provider,
+            Url::parse("http://localhost:8545").unwrap(),
+            signer,
+        );
+        let transport = MockTransport::bundles();
+        middleware.relay_mut().set_transport(transport.clone());
+
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .push_transaction(Bytes::from(vec![0x2]))
+            .set_block(10.into());
+
+        middleware
+            .relay_mut()
+            .set_bundle_limits(BundleLimits::new().set_max_transactions(1));
+        assert!(matches!(
+            middleware.send_bundle(&bundle).await,
+            Err(FlashbotsMiddlewareError::TooManyTransactions { count: 2, limit: 1 })
+        ));
+
+        middleware
+            .relay_mut()
+            .set_bundle_limits(BundleLimits::new().set_max_size(64));
+        match middleware.send_bundle(&bundle).await {
+            Err(FlashbotsMiddlewareError::BundleTooLarge { size, limit: 64 }) => {
+                assert!(size > 64)
+            }
+            res => panic!(
+                "unexpected result: {:?}",
+                res.map(|pending| pending.bundle_hash)
+            ),
+        }
+        assert_eq!(transport.requests().len(), 0);
+    }
+
+    #[test]
+    fn relaxed_simulation_params() {
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(10.into())
+            .set_simulation_timestamp(1000);
+        let params = serde_json::to_value(SimulationParams::new(&bundle, false)).unwrap();
+        assert_eq!(params["stateBlockNumber"], "latest");
+        assert!(SimulationParams::new(&bundle, true).is_none());
+
+        let bundle = bundle.set_simulation_block(9.into());
+        let params = serde_json::to_value(SimulationParams::new(&bundle, true)).unwrap();
+        assert_eq!(params["stateBlockNumber"], "0x9");
+    }
+}
@@ -0,0 +1,78 @@
+use crate::builders::{self, BuilderEndpoint};
+use std::fmt;
+
+/// A network with known builders.
+///
+/// See [`builders::for_network`].
+///
+/// ```
+/// use ethers_flashbots::Network;
+///
+/// assert_eq!(Network::from_chain_id(1), Some(Network::Mainnet));
+/// assert_eq!(Network::Sepolia.chain_id(), 11155111);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Network {
+    /// Ethereum mainnet.
+    Mainnet,
+    /// The Sepolia testnet.
+    Sepolia,
+    /// The Holesky testnet.
+    Holesky,
+    /// BNB Smart Chain.
+    Bsc,
+    /// Polygon.
+    Polygon,
+}
+
+impl Network {
+    /// All networks with known builders.
+    pub const ALL: &'static [Network] = &[
+        Network::Mainnet,
+        Network::Sepolia,
+        Network::Holesky,
+        Network::Bsc,
+        Network::Polygon,
+    ];
+
+    /// Get the network with the given chain ID (if known).
+    pub fn from_chain_id(chain_id: u64) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|network| network.chain_id() == chain_id)
+    }
+
+    /// Get the chain ID of the network.
+    pub const fn chain_id(&self) -> u64 {
+        match self {
+            Network::Mainnet => 1,
+            Network::Sepolia => 11155111,
+            Network::Holesky => 17000,
+            Network::Bsc => 56,
+            Network::Polygon => 137,
+        }
+    }
+
+    /// Get the name of the network.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "mainnet",
+            Network::Sepolia => "sepolia",
+            Network::Holesky => "holesky",
+            Network::Bsc => "bsc",
+            Network::Polygon => "polygon",
+        }
+    }
+
+    /// Get all known builders on the network.
+    pub fn builders(&self) -> &'static [BuilderEndpoint] {
+        builders::for_network(*self)
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
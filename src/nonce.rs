@@ -0,0 +1,125 @@
+use ethers::{
+    core::types::{transaction::eip2718::TypedTransaction, Address, BlockNumber, U256, U64},
+    providers::Middleware,
+};
+use std::{collections::HashMap, sync::Mutex};
+
+/// The number of blocks nonces are remembered for after their target
+/// block.
+const PRUNE_DEPTH: u64 = 32;
+
+/// A nonce manager for transactions in bundles.
+///
+/// Transactions in bundles are not in the mempool, so the pending nonce of
+/// their sender does not account for earlier transactions in the same
+/// bundle, or in other bundles targeting the same block. This manager
+/// assigns sequential nonces to the transactions of each sender per
+/// target block, starting at the pending nonce of the sender.
+///
+/// Nonces are assigned independently for each target block, so bundles
+/// resubmitted for the next block reuse the same nonces. If the chain's
+/// pending nonce moves past the assigned nonces (e.g. because a bundle was
+/// included), assignment continues from the pending nonce.
+///
+/// ```no_run
+/// # use ethers::{core::types::Address, providers::{Provider, Http}};
+/// # use ethers_flashbots::BundleNonceManager;
+/// # async fn assign(provider: Provider<Http>, sender: Address) -> Result<(), Box<dyn std::error::Error>> {
+/// let nonces = BundleNonceManager::new();
+/// let block = 17_000_000.into();
+/// let first = nonces.next_nonce(&provider, sender, block).await?;
+/// let second = nonces.next_nonce(&provider, sender, block).await?;
+/// assert_eq!(second, first + 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct BundleNonceManager {
+    /// The next nonce of each sender per target block.
+    nonces: Mutex<HashMap<(Address, U64), U256>>,
+}
+
+impl BundleNonceManager {
+    /// Creates a nonce manager without any assigned nonces.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Assign the next nonce of the sender for a bundle targeting the
+    /// given block.
+    pub async fn next_nonce<M: Middleware>(
+        &self,
+        middleware: &M,
+        sender: Address,
+        block: U64,
+    ) -> Result<U256, M::Error> {
+        let pending = middleware
+            .get_transaction_count(sender, Some(BlockNumber::Pending.into()))
+            .await?;
+        Ok(self.assign(sender, block, pending))
+    }
+
+    /// Set the nonce of a transaction to the next nonce of its sender for
+    /// a bundle targeting the given block, returning the nonce.
+    ///
+    /// If the transaction has no sender, the default sender of the
+    /// middleware is used. Returns `None` without changing the transaction
+    /// if neither is set.
+    pub async fn fill_nonce<M: Middleware>(
+        &self,
+        middleware: &M,
+        tx: &mut TypedTransaction,
+        block: U64,
+    ) -> Result<Option<U256>, M::Error> {
+        let sender = match tx.from().copied().or_else(|| middleware.default_sender()) {
+            Some(sender) => sender,
+            None => return Ok(None),
+        };
+        let nonce = self.next_nonce(middleware, sender, block).await?;
+        tx.set_from(sender);
+        tx.set_nonce(nonce);
+        Ok(Some(nonce))
+    }
+
+    /// Forget the assigned nonces of the sender, e.g. after sending a
+    /// transaction outside of a bundle.
+    pub fn reset(&self, sender: Address) {
+        self.nonces
+            .lock()
+            .unwrap()
+            .retain(|(address, _), _| *address != sender);
+    }
+
+    fn assign(&self, sender: Address, block: U64, pending: U256) -> U256 {
+        let mut nonces = self.nonces.lock().unwrap();
+        nonces.retain(|(_, target), _| *target + PRUNE_DEPTH >= block);
+
+        let next = nonces.entry((sender, block)).or_insert(pending);
+        let nonce = (*next).max(pending);
+        *next = nonce + 1;
+        nonce
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundle_nonces() {
+        let nonces = BundleNonceManager::new();
+        let (a, b) = (Address::repeat_byte(0xa), Address::repeat_byte(0xb));
+
+        assert_eq!(nonces.assign(a, 10.into(), 5.into()), 5.into());
+        assert_eq!(nonces.assign(a, 10.into(), 5.into()), 6.into());
+        assert_eq!(nonces.assign(b, 10.into(), 1.into()), 1.into());
+        // Resubmissions for the next block reuse the nonces
+        assert_eq!(nonces.assign(a, 11.into(), 5.into()), 5.into());
+        // The pending nonce moved past the assigned nonces
+        assert_eq!(nonces.assign(a, 10.into(), 8.into()), 8.into());
+
+        nonces.reset(a);
+        assert_eq!(nonces.assign(a, 10.into(), 5.into()), 5.into());
+        assert_eq!(nonces.assign(b, 10.into(), 1.into()), 2.into());
+    }
+}
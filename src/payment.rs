@@ -0,0 +1,162 @@
+use crate::bundle::SimulatedBundle;
+use ethers::core::types::{
+    transaction::eip2718::TypedTransaction, Bytes, Eip1559TransactionRequest, U256,
+};
+use std::sync::Arc;
+
+/// The gas limit of a coinbase payment.
+///
+/// The payment creates a contract, which costs 53000 gas, and transfers
+/// the value to the (warm) coinbase.
+pub const COINBASE_PAYMENT_GAS: u64 = 70_000;
+
+/// Init code that transfers the value of the transaction to
+/// `block.coinbase`, and reverts if the transfer fails.
+///
+/// ```text
+/// PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 0 CALLVALUE COINBASE GAS CALL
+/// PUSH1 0x13 JUMPI PUSH1 0 DUP1 REVERT JUMPDEST STOP
+/// ```
+const COINBASE_PAYMENT_CODE: [u8; 21] = [
+    0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x34, 0x41, 0x5a, 0xf1, 0x60, 0x13, 0x57, 0x60,
+    0x00, 0x80, 0xfd, 0x5b, 0x00,
+];
+
+/// Creates a transaction that pays the given amount (in wei) to
+/// `block.coinbase`.
+///
+/// Paying the coinbase directly is the recommended way to bid for
+/// inclusion of a bundle, so the payment is usually the last transaction
+/// of the bundle. The transaction creates a contract without code that
+/// forwards its value to the coinbase, so no contract needs to be
+/// deployed beforehand.
+///
+/// The sender, nonce, chain ID and fees must be set before the transaction
+/// is signed, e.g. using
+/// [`FlashbotsMiddleware::sign_coinbase_payment`](crate::FlashbotsMiddleware::sign_coinbase_payment).
+///
+/// ```
+/// use ethers::utils::parse_ether;
+/// use ethers_flashbots::{coinbase_payment, COINBASE_PAYMENT_GAS};
+///
+/// let tx = coinbase_payment(parse_ether("0.01").unwrap());
+/// assert_eq!(tx.gas(), Some(&COINBASE_PAYMENT_GAS.into()));
+/// ```
+pub fn coinbase_payment(amount: U256) -> TypedTransaction {
+    Eip1559TransactionRequest::new()
+        .value(amount)
+        .data(Bytes::from_static(&COINBASE_PAYMENT_CODE))
+        .gas(COINBASE_PAYMENT_GAS)
+        .into()
+}
+
+/// Computes the profit of a simulated bundle.
+type ProfitFn = dyn Fn(&SimulatedBundle) -> Option<U256> + Send + Sync;
+
+/// A bid of a percentage of the simulated profit of a bundle, paid to
+/// `block.coinbase`.
+///
+/// The profit of a bundle is not visible to the relay, so it is computed
+/// from the simulation by the given function, e.g. from the return value
+/// of a transaction (see [`ProfitBid::from_return_value`]).
+///
+/// See [`FlashbotsMiddleware::apply_profit_bid`](crate::FlashbotsMiddleware::apply_profit_bid).
+#[derive(Clone)]
+pub struct ProfitBid {
+    percent: u8,
+    profit: Arc<ProfitFn>,
+}
+
+impl ProfitBid {
+    /// Creates a bid of the given percentage (0-100) of the profit
+    /// computed by `profit`.
+    ///
+    /// If `profit` returns `None`, the bundle is not profitable.
+    pub fn new(
+        percent: u8,
+        profit: impl Fn(&SimulatedBundle) -> Option<U256> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            percent: percent.min(100),
+            profit: Arc::new(profit),
+        }
+    }
+
+    /// Creates a bid of the given percentage (0-100) of the profit
+    /// returned (as a `uint256`) by the transaction at the given index.
+    ///
+    /// The bundle is not profitable if the transaction reverted.
+    pub fn from_return_value(percent: u8, index: usize) -> Self {
+        Self::new(percent, move |simulation| {
+            let tx = simulation.transactions.get(index)?;
+            if tx.is_reverted() {
+                return None;
+            }
+            match tx.value.as_deref()? {
+                value if value.len() >= 32 => Some(U256::from_big_endian(&value[..32])),
+                _ => None,
+            }
+        })
+    }
+
+    /// Get the percentage of the profit that is paid.
+    pub fn percent(&self) -> u8 {
+        self.percent
+    }
+
+    /// Get the payment to the coinbase for the given simulation.
+    ///
+    /// Returns `None` if the bundle is not profitable.
+    pub fn payment(&self, simulation: &SimulatedBundle) -> Option<U256> {
+        let profit = (self.profit)(simulation).filter(|profit| !profit.is_zero())?;
+        // Split the profit to avoid overflows
+        let percent = U256::from(self.percent);
+        Some(profit / 100 * percent + profit % 100 * percent / 100)
+    }
+}
+
+impl std::fmt::Debug for ProfitBid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProfitBid")
+            .field("percent", &self.percent)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bundle::SimulatedTransaction,
+        fixtures::{simulated_bundle, simulated_transaction},
+    };
+    use ethers::core::types::H256;
+
+    #[test]
+    fn coinbase_payment_transaction() {
+        let tx = coinbase_payment(100.into());
+        assert_eq!(tx.to(), None);
+        assert_eq!(tx.value(), Some(&100.into()));
+        assert_eq!(tx.data().unwrap().as_ref(), &COINBASE_PAYMENT_CODE);
+    }
+
+    #[test]
+    fn profit_bid() {
+        let mut profit = [0u8; 32];
+        U256::from(1000).to_big_endian(&mut profit);
+        let mut simulation = simulated_bundle(vec![SimulatedTransaction {
+            value: Some(Bytes::from(profit.to_vec())),
+            ..simulated_transaction(H256::zero())
+        }]);
+
+        let bid = ProfitBid::from_return_value(90, 0);
+        assert_eq!(bid.payment(&simulation), Some(900.into()));
+        assert_eq!(
+            ProfitBid::from_return_value(90, 1).payment(&simulation),
+            None
+        );
+
+        simulation.transactions[0].revert = Some("unprofitable".into());
+        assert_eq!(bid.payment(&simulation), None);
+    }
+}
@@ -1,17 +1,116 @@
-use crate::bundle::BundleHash;
-use ethers::core::types::{Block, TxHash, U64};
+use crate::{
+    bundle::BundleHash,
+    error::ErrorKind,
+    inclusion::{InclusionEvent, InclusionHook, InclusionStatus},
+};
+use ethers::core::types::{TransactionReceipt, TxHash, U64};
 use ethers::providers::{
-    interval, JsonRpcClient, Middleware, Provider, ProviderError, DEFAULT_POLL_INTERVAL,
+    JsonRpcClient, Middleware, Provider, ProviderError, DEFAULT_POLL_INTERVAL,
 };
-use futures_core::stream::Stream;
-use futures_util::stream::StreamExt;
+use futures_util::future::Either;
 use pin_project::pin_project;
 use std::{
+    collections::HashMap,
+    fmt,
     future::Future,
     pin::Pin,
-    task::{Context, Poll},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    time::Duration,
 };
 use thiserror::Error;
+use uuid::Uuid;
+
+/// The builder methods shared by [`PendingBundle`] and
+/// [`OwnedPendingBundle`], which both have a `config` and a `cancel` field.
+macro_rules! watch_builders {
+    () => {
+        /// Sets the polling interval used to check whether the target block
+        /// has been included.
+        ///
+        /// Defaults to [`DEFAULT_POLL_INTERVAL`].
+        pub fn interval<T: Into<Duration>>(mut self, duration: T) -> Self {
+            self.config.poll_interval = duration.into();
+            self
+        }
+
+        /// Sets the number of confirmations the target block must have before
+        /// the inclusion of the bundle is checked, to protect against reorgs.
+        ///
+        /// A confirmation count of 1 checks the inclusion as soon as the target
+        /// block is mined. Defaults to 1.
+        pub fn confirmations(mut self, confirmations: usize) -> Self {
+            self.config.confirmations = confirmations.max(1);
+            self
+        }
+
+        /// Sets the number of blocks after the target block during which a
+        /// bundle that was not included is re-checked, in case the target block
+        /// is reorged.
+        ///
+        /// Short reorgs may replace the target block with a block that does
+        /// include the bundle. With a window of `n` blocks, the bundle is only
+        /// reported as not included if the target block does not include it
+        /// once `n` blocks have been mined on top of it. Defaults to 0.
+        pub fn reorg_blocks(mut self, blocks: u64) -> Self {
+            self.config.reorg_blocks = blocks;
+            self
+        }
+
+        /// Sets how the pending bundle checks whether it was included.
+        ///
+        /// Defaults to [`InclusionCheck::Block`].
+        pub fn inclusion_check(mut self, check: InclusionCheck) -> Self {
+            self.config.check = check;
+            self
+        }
+
+        /// Adds a hook that is called with the outcome of the pending bundle.
+        ///
+        /// See [`InclusionHook`].
+        pub fn on_inclusion(mut self, hook: InclusionHook) -> Self {
+            self.config.hooks.push(hook);
+            self
+        }
+
+        /// Sets the delays between retries after provider errors.
+        ///
+        /// The first retry waits `min`, and the delay doubles after every
+        /// consecutive error up to `max`. Defaults to the polling interval and
+        /// 1 minute.
+        pub fn error_backoff(mut self, min: Duration, max: Duration) -> Self {
+            self.config.min_error_delay = Some(min);
+            self.config.max_error_delay = max;
+            self
+        }
+
+        /// Sets the number of consecutive provider errors after which the
+        /// pending bundle resolves with the last error.
+        ///
+        /// By default, provider errors are retried indefinitely.
+        pub fn max_errors(mut self, max: u32) -> Self {
+            self.config.max_errors = Some(max);
+            self
+        }
+
+        /// Get a handle to cancel the pending bundle.
+        ///
+        /// See [`CancelHandle`].
+        pub fn cancel_handle(&self) -> CancelHandle {
+            self.cancel.handle.clone()
+        }
+
+        /// Sets the handle used to cancel the pending bundle, e.g. to cancel
+        /// several pending bundles at once.
+        pub fn set_cancel_handle(mut self, handle: CancelHandle) -> Self {
+            self.cancel = CancelWaiter::new(handle);
+            self
+        }
+    };
+}
 
 /// A pending bundle is one that has been submitted to a relay,
 /// but not yet included.
@@ -31,9 +130,14 @@ pub struct PendingBundle<'a, P> {
     pub bundle_hash: Option<BundleHash>,
     pub block: U64,
     pub transactions: Vec<TxHash>,
+    /// The client-side tracking uuid of the bundle (if any).
+    ///
+    /// See [`BundleRequest::set_tracking_uuid`](crate::BundleRequest::set_tracking_uuid).
+    pub tracking_uuid: Option<Uuid>,
     provider: &'a Provider<P>,
     state: PendingBundleState<'a>,
-    interval: Box<dyn Stream<Item = ()> + Send + Unpin>,
+    config: WatchConfig,
+    cancel: CancelWaiter,
 }
 
 impl<'a, P: JsonRpcClient> PendingBundle<'a, P> {
@@ -47,17 +151,63 @@ impl<'a, P: JsonRpcClient> PendingBundle<'a, P> {
             bundle_hash,
             block,
             transactions,
+            tracking_uuid: None,
             provider,
-            state: PendingBundleState::PausedGettingBlock,
-            interval: Box::new(interval(DEFAULT_POLL_INTERVAL)),
+            state: PendingBundleState::NotStarted,
+            config: WatchConfig::default(),
+            cancel: CancelWaiter::new(CancelHandle::new()),
         }
     }
 
+    watch_builders!();
+
+    /// Get the provider used to poll for the target block.
+    pub fn provider(&self) -> &'a Provider<P> {
+        self.provider
+    }
+
     /// Get the bundle hash for this pending bundle.
     #[deprecated(note = "use the bundle_hash field instead")]
     pub fn bundle_hash(&self) -> Option<BundleHash> {
         self.bundle_hash
     }
+
+    /// Check once whether the bundle was included, like awaiting the
+    /// pending bundle does on every poll, and call the hooks if so.
+    ///
+    /// Returns `Ok(None)` if the target block is not mined or confirmed yet.
+    pub(crate) async fn check(&self) -> Result<Option<Option<BundleHash>>, PendingBundleError> {
+        match resolve(
+            self.provider,
+            self.bundle_hash,
+            self.tracking_uuid,
+            self.block,
+            &self.transactions,
+            &self.config,
+        )
+        .await?
+        {
+            Some(res) => res.map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'a, P: JsonRpcClient + Clone + 'static> PendingBundle<'a, P> {
+    /// Convert the pending bundle into an [`OwnedPendingBundle`] that polls
+    /// a clone of [`PendingBundle::provider`].
+    ///
+    /// The polling configuration, tracking uuid and cancel handle are kept.
+    /// Any progress of the pending bundle is discarded.
+    pub fn into_owned(self) -> OwnedPendingBundle<P> {
+        let provider = Arc::new(self.provider.clone());
+        let mut owned =
+            OwnedPendingBundle::new(self.bundle_hash, self.block, self.transactions, provider)
+                .set_cancel_handle(self.cancel.handle.clone());
+        owned.tracking_uuid = self.tracking_uuid;
+        owned.config = self.config;
+        owned
+    }
 }
 
 impl<'a, P: JsonRpcClient> Future for PendingBundle<'a, P> {
@@ -66,81 +216,874 @@ impl<'a, P: JsonRpcClient> Future for PendingBundle<'a, P> {
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
         let this = self.project();
 
+        if !matches!(this.state, PendingBundleState::Completed) && this.cancel.poll_cancelled(ctx) {
+            *this.state = PendingBundleState::Completed;
+            return Poll::Ready(Err(PendingBundleError::Cancelled));
+        }
+
+        if let PendingBundleState::NotStarted = this.state {
+            let fut = Box::pin(wait_for_inclusion(
+                *this.provider,
+                *this.bundle_hash,
+                *this.tracking_uuid,
+                *this.block,
+                this.transactions.clone(),
+                this.config.clone(),
+            ));
+            *this.state = PendingBundleState::Watching(fut);
+        }
+
         match this.state {
-            PendingBundleState::PausedGettingBlock => {
-                futures_util::ready!(this.interval.poll_next_unpin(ctx));
-                let fut = Box::pin(this.provider.get_block(*this.block));
-                *this.state = PendingBundleState::GettingBlock(fut);
-                ctx.waker().wake_by_ref();
+            PendingBundleState::Watching(fut) => {
+                let res = futures_util::ready!(fut.as_mut().poll(ctx));
+                *this.state = PendingBundleState::Completed;
+                this.cancel.deregister();
+                Poll::Ready(res)
             }
-            PendingBundleState::GettingBlock(fut) => {
-                let block_res = futures_util::ready!(fut.as_mut().poll(ctx));
-
-                // If the provider errors, we try again after some interval.
-                if block_res.is_err() {
-                    *this.state = PendingBundleState::PausedGettingBlock;
-                    ctx.waker().wake_by_ref();
-                    return Poll::Pending;
-                }
+            _ => panic!("polled pending bundle future after completion"),
+        }
+    }
+}
 
-                let block_opt = block_res.unwrap();
-                // If the block doesn't exist yet, we try again after some interval.
-                if block_opt.is_none() {
-                    *this.state = PendingBundleState::PausedGettingBlock;
-                    ctx.waker().wake_by_ref();
-                    return Poll::Pending;
-                }
+/// A [`PendingBundle`] that owns a handle to its provider.
+///
+/// Unlike [`PendingBundle`], this does not borrow the provider, so it can be
+/// stored in structs, moved across tasks or spawned. Owned pending bundles
+/// are returned by
+/// [`FlashbotsMiddleware::send_bundle_owned`](crate::FlashbotsMiddleware::send_bundle_owned),
+/// or can be converted from pending bundles with
+/// [`PendingBundle::into_owned`]:
+///
+/// ```no_run
+/// # use ethers::providers::{Provider, Http};
+/// # use ethers_flashbots::OwnedPendingBundle;
+/// # use std::sync::Arc;
+/// # async fn spawn(provider: Arc<Provider<Http>>, pending: OwnedPendingBundle<Http>) {
+/// let handle = tokio::spawn(pending);
+/// match handle.await.unwrap() {
+///     Ok(_) => println!("Bundle was included"),
+///     Err(err) => println!("Bundle was not included: {}", err),
+/// }
+/// # }
+/// ```
+pub struct OwnedPendingBundle<P> {
+    pub bundle_hash: Option<BundleHash>,
+    pub block: U64,
+    pub transactions: Vec<TxHash>,
+    /// The client-side tracking uuid of the bundle (if any).
+    ///
+    /// See [`BundleRequest::set_tracking_uuid`](crate::BundleRequest::set_tracking_uuid).
+    pub tracking_uuid: Option<Uuid>,
+    provider: Arc<Provider<P>>,
+    config: WatchConfig,
+    cancel: CancelWaiter,
+    /// The inclusion check, started when the bundle is first polled.
+    fut: Option<PinBoxFut<'static, Option<BundleHash>, PendingBundleError>>,
+}
 
-                let block = block_opt.unwrap();
-                // If the block is pending, we try again after some interval.
-                if block.number.is_none() {
-                    *this.state = PendingBundleState::PausedGettingBlock;
-                    ctx.waker().wake_by_ref();
-                    return Poll::Pending;
-                }
+impl<P: JsonRpcClient + 'static> OwnedPendingBundle<P> {
+    pub fn new(
+        bundle_hash: Option<BundleHash>,
+        block: U64,
+        transactions: Vec<TxHash>,
+        provider: Arc<Provider<P>>,
+    ) -> Self {
+        Self {
+            bundle_hash,
+            block,
+            transactions,
+            tracking_uuid: None,
+            provider,
+            config: WatchConfig::default(),
+            cancel: CancelWaiter::new(CancelHandle::new()),
+            fut: None,
+        }
+    }
 
-                // Check if all transactions of the bundle are present in the block
-                let included: bool = this
-                    .transactions
-                    .iter()
-                    .all(|tx_hash| block.transactions.contains(tx_hash));
+    watch_builders!();
 
-                *this.state = PendingBundleState::Completed;
-                if included {
-                    return Poll::Ready(Ok(*this.bundle_hash));
-                } else {
-                    return Poll::Ready(Err(PendingBundleError::BundleNotIncluded));
+    /// Get the provider used to poll for the target block.
+    pub fn provider(&self) -> &Arc<Provider<P>> {
+        &self.provider
+    }
+}
+
+impl<P> fmt::Debug for OwnedPendingBundle<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedPendingBundle")
+            .field("bundle_hash", &self.bundle_hash)
+            .field("block", &self.block)
+            .field("transactions", &self.transactions)
+            .field("tracking_uuid", &self.tracking_uuid)
+            .field("config", &self.config)
+            .field("cancel", &self.cancel.handle)
+            .finish()
+    }
+}
+
+impl<P: JsonRpcClient + 'static> Future for OwnedPendingBundle<P> {
+    type Output = Result<Option<BundleHash>, PendingBundleError>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = &mut *self;
+        if this.cancel.poll_cancelled(ctx) {
+            // Drop the inclusion check so no more requests are made
+            this.fut = None;
+            return Poll::Ready(Err(PendingBundleError::Cancelled));
+        }
+        if this.fut.is_none() {
+            let provider = this.provider.clone();
+            let (bundle_hash, tracking_uuid, block) =
+                (this.bundle_hash, this.tracking_uuid, this.block);
+            let (transactions, config) = (this.transactions.clone(), this.config.clone());
+            this.fut = Some(Box::pin(async move {
+                wait_for_inclusion(
+                    &provider,
+                    bundle_hash,
+                    tracking_uuid,
+                    block,
+                    transactions,
+                    config,
+                )
+                .await
+            }));
+        }
+        let res = futures_util::ready!(this.fut.as_mut().unwrap().as_mut().poll(ctx));
+        this.cancel.deregister();
+        Poll::Ready(res)
+    }
+}
+
+/// The default maximum delay between retries after provider errors.
+const DEFAULT_MAX_ERROR_DELAY: Duration = Duration::from_secs(60);
+
+/// How a pending bundle checks whether it was included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InclusionCheck {
+    /// Fetch the target block and check that it contains all transactions
+    /// of the bundle.
+    #[default]
+    Block,
+    /// Fetch the receipts of the first and last transaction of the bundle
+    /// once the target block is mined, and check that they are in the
+    /// target block with the rest of the bundle in between.
+    ///
+    /// This is cheaper than fetching blocks with many transactions, but
+    /// assumes that the transactions of the bundle are included in order,
+    /// as relays do. Missing receipts are fetched again until a block has
+    /// been mined on top of the target block.
+    Receipts,
+}
+
+/// How a pending bundle polls for its target block.
+#[derive(Debug, Clone)]
+struct WatchConfig {
+    check: InclusionCheck,
+    confirmations: usize,
+    reorg_blocks: u64,
+    poll_interval: Duration,
+    /// Defaults to the polling interval.
+    min_error_delay: Option<Duration>,
+    max_error_delay: Duration,
+    max_errors: Option<u32>,
+    hooks: Vec<InclusionHook>,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            check: InclusionCheck::default(),
+            confirmations: 1,
+            reorg_blocks: 0,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            min_error_delay: None,
+            max_error_delay: DEFAULT_MAX_ERROR_DELAY,
+            max_errors: None,
+            hooks: Vec::new(),
+        }
+    }
+}
+
+impl WatchConfig {
+    /// The delay before retrying after the given number of consecutive
+    /// provider errors.
+    fn error_delay(&self, errors: u32) -> Duration {
+        let min = self.min_error_delay.unwrap_or(self.poll_interval);
+        min.saturating_mul(2u32.saturating_pow(errors.saturating_sub(1)))
+            .min(self.max_error_delay.max(min))
+    }
+}
+
+/// Wait for the target block and check whether all transactions of the
+/// bundle are in it.
+async fn wait_for_inclusion<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    bundle_hash: Option<BundleHash>,
+    tracking_uuid: Option<Uuid>,
+    block: U64,
+    transactions: Vec<TxHash>,
+    config: WatchConfig,
+) -> Result<Option<BundleHash>, PendingBundleError> {
+    let mut delay = config.poll_interval;
+    let mut errors = 0;
+    loop {
+        tokio::time::sleep(delay).await;
+        delay = config.poll_interval;
+
+        match resolve(
+            provider,
+            bundle_hash,
+            tracking_uuid,
+            block,
+            &transactions,
+            &config,
+        )
+        .await
+        {
+            Ok(Some(res)) => return res,
+            // If the target block isn't mined (or confirmed) yet, we try
+            // again after some interval.
+            Ok(None) => errors = 0,
+            // If the provider errors, we back off until the error budget is spent.
+            Err(err) => {
+                errors += 1;
+                if config.max_errors.is_some_and(|max| errors >= max) {
+                    return Err(err.into());
                 }
+                delay = config.error_delay(errors);
+            }
+        }
+    }
+}
+
+/// Check whether the bundle was included and call the hooks, or `None` if
+/// the target block is not mined or confirmed yet.
+async fn resolve<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    bundle_hash: Option<BundleHash>,
+    tracking_uuid: Option<Uuid>,
+    block: U64,
+    transactions: &[TxHash],
+    config: &WatchConfig,
+) -> Result<Option<Result<Option<BundleHash>, PendingBundleError>>, ProviderError> {
+    let included = match check_inclusion(provider, block, transactions, config).await? {
+        Some(included) => included,
+        None => return Ok(None),
+    };
+
+    if !config.hooks.is_empty() {
+        let receipts = included_receipts(provider, block, transactions).await;
+        let status = if included {
+            InclusionStatus::Included
+        } else if receipts.is_empty() {
+            InclusionStatus::NotIncluded
+        } else {
+            InclusionStatus::PartiallyIncluded
+        };
+        let event = InclusionEvent {
+            status,
+            bundle_hash,
+            tracking_uuid,
+            block,
+            transactions: transactions.to_vec(),
+            receipts,
+        };
+        for hook in &config.hooks {
+            hook.call(event.clone()).await;
+        }
+    }
+
+    Ok(Some(if included {
+        Ok(bundle_hash)
+    } else {
+        Err(PendingBundleError::BundleNotIncluded)
+    }))
+}
+
+/// Get the receipts of the transactions that are in the target block.
+///
+/// Receipts that cannot be fetched are skipped.
+async fn included_receipts<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    block: U64,
+    transactions: &[TxHash],
+) -> Vec<TransactionReceipt> {
+    let mut receipts = Vec::new();
+    for tx_hash in transactions {
+        if let Ok(Some(receipt)) = provider.get_transaction_receipt(*tx_hash).await {
+            if receipt.block_number == Some(block) {
+                receipts.push(receipt);
             }
-            PendingBundleState::Completed => {
-                panic!("polled pending bundle future after completion")
+        }
+    }
+    receipts
+}
+
+/// Check whether the bundle was included, or `None` if the target block is
+/// not mined or confirmed yet.
+async fn check_inclusion<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    block: U64,
+    transactions: &[TxHash],
+    config: &WatchConfig,
+) -> Result<Option<bool>, ProviderError> {
+    // The latest block is only needed for confirmations and reorgs
+    let latest = if config.confirmations > 1 || config.reorg_blocks > 0 {
+        Some(provider.get_block_number().await?)
+    } else {
+        None
+    };
+
+    // Checking at the confirmed height means reorged bundles are not
+    // reported as included
+    let confirmed = block + (config.confirmations - 1) as u64;
+    if latest.is_some_and(|latest| latest < confirmed) {
+        return Ok(None);
+    }
+
+    let included = match config.check {
+        InclusionCheck::Block => check_block(provider, block, transactions).await?,
+        InclusionCheck::Receipts => check_receipts(provider, block, transactions).await?,
+    };
+
+    // The target block may still be replaced by a block that includes the
+    // bundle, so the bundle is re-checked until the reorg window has passed
+    if included == Some(false) && latest.is_some_and(|latest| latest < block + config.reorg_blocks)
+    {
+        return Ok(None);
+    }
+
+    Ok(included)
+}
+
+/// Check whether the target block contains all transactions, or `None` if
+/// the block is not mined yet.
+async fn check_block<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    block: U64,
+    transactions: &[TxHash],
+) -> Result<Option<bool>, ProviderError> {
+    // A block without a number is pending
+    let block = match provider.get_block(block).await? {
+        Some(block) if block.number.is_some() => block,
+        _ => return Ok(None),
+    };
+
+    Ok(Some(
+        transactions
+            .iter()
+            .all(|tx_hash| block.transactions.contains(tx_hash)),
+    ))
+}
+
+/// Check whether the first and last transaction are in the target block,
+/// with the rest of the bundle in between, or `None` if the block is not
+/// mined yet.
+///
+/// Receipts may be missing because the node has not indexed the target
+/// block yet, so a missing receipt only means that the bundle was not
+/// included once a block has been mined on top of the target block.
+async fn check_receipts<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    block: U64,
+    transactions: &[TxHash],
+) -> Result<Option<bool>, ProviderError> {
+    let latest = provider.get_block_number().await?;
+    if latest < block {
+        return Ok(None);
+    }
+    let (first, last) = match (transactions.first(), transactions.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return Ok(Some(true)),
+    };
+
+    let transaction_index = |receipt: Option<TransactionReceipt>| match receipt {
+        Some(receipt) if receipt.block_number == Some(block) => Ok(receipt.transaction_index),
+        // The transaction is in another block
+        Some(receipt) if receipt.block_number.is_some() => Err(Some(false)),
+        _ if latest > block => Err(Some(false)),
+        _ => Err(None),
+    };
+    let first_index = match transaction_index(provider.get_transaction_receipt(first).await?) {
+        Ok(index) => index,
+        Err(included) => return Ok(included),
+    };
+    let last_index = if transactions.len() == 1 {
+        first_index
+    } else {
+        match transaction_index(provider.get_transaction_receipt(last).await?) {
+            Ok(index) => index,
+            Err(included) => return Ok(included),
+        }
+    };
+
+    let span = last_index.as_usize().checked_sub(first_index.as_usize());
+    Ok(Some(span == Some(transactions.len() - 1)))
+}
+
+/// A handle to cancel pending bundles.
+///
+/// Cancelling the handle resolves the pending bundles it belongs to with
+/// [`PendingBundleError::Cancelled`] and stops them from polling the
+/// provider, even if they are awaited elsewhere (e.g. in a spawned task).
+/// Polling the stats of the pending bundles with
+/// [`FlashbotsMiddleware::poll_pending_bundle_stats`](crate::FlashbotsMiddleware::poll_pending_bundle_stats)
+/// stops as well, and other work tied to a pending bundle can stop with
+/// [`CancelHandle::run_until_cancelled`].
+///
+/// The handle can be cloned, and all clones cancel the same pending
+/// bundles.
+///
+/// ```no_run
+/// # use ethers::providers::Http;
+/// # use ethers_flashbots::OwnedPendingBundle;
+/// # async fn cancel(pending: OwnedPendingBundle<Http>) {
+/// let handle = pending.cancel_handle();
+/// let task = tokio::spawn(pending);
+/// // ...
+/// handle.cancel();
+/// assert!(task.await.unwrap().is_err());
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    inner: Arc<CancelState>,
+}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    /// The wakers of the tasks waiting for cancellation, by the key of
+    /// their [`CancelWaiter`].
+    wakers: Mutex<HashMap<u64, Waker>>,
+    next_key: AtomicU64,
+}
+
+impl CancelHandle {
+    /// Creates a handle that is not cancelled.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Cancel the pending bundles of this handle.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        let wakers = std::mem::take(&mut *self.inner.wakers.lock().unwrap());
+        for waker in wakers.into_values() {
+            waker.wake();
+        }
+    }
+
+    /// Whether the handle has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Wait until the handle is cancelled.
+    pub async fn cancelled(&self) {
+        let mut waiter = CancelWaiter::new(self.clone());
+        futures_util::future::poll_fn(|ctx| {
+            if waiter.poll_cancelled(ctx) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Run the future until it completes, or until the handle is cancelled,
+    /// in which case the future is dropped and `None` is returned.
+    pub async fn run_until_cancelled<F: Future>(&self, future: F) -> Option<F::Output> {
+        let cancelled = self.cancelled();
+        futures_util::pin_mut!(future, cancelled);
+        match futures_util::future::select(cancelled, future).await {
+            Either::Left(_) => None,
+            Either::Right((output, _)) => Some(output),
+        }
+    }
+}
+
+/// Waits for a [`CancelHandle`] to be cancelled.
+///
+/// At most one waker is registered per waiter, and it is deregistered
+/// when the waiter completes or is dropped, so handles that are shared by
+/// many short-lived pending bundles do not accumulate wakers.
+#[derive(Debug)]
+struct CancelWaiter {
+    handle: CancelHandle,
+    key: Option<u64>,
+}
+
+impl CancelWaiter {
+    fn new(handle: CancelHandle) -> Self {
+        Self { handle, key: None }
+    }
+
+    /// Whether the handle has been cancelled, registering the waker of the
+    /// task to be woken on cancellation if not.
+    fn poll_cancelled(&mut self, ctx: &mut Context) -> bool {
+        if self.handle.is_cancelled() {
+            self.deregister();
+            return true;
+        }
+        let state = &self.handle.inner;
+        {
+            let mut wakers = state.wakers.lock().unwrap();
+            let key = *self
+                .key
+                .get_or_insert_with(|| state.next_key.fetch_add(1, Ordering::Relaxed));
+            match wakers.get_mut(&key) {
+                Some(waker) if waker.will_wake(ctx.waker()) => {}
+                Some(waker) => *waker = ctx.waker().clone(),
+                None => {
+                    wakers.insert(key, ctx.waker().clone());
+                }
             }
         }
+        // The handle may have been cancelled before the waker was registered
+        self.handle.is_cancelled()
+    }
 
-        Poll::Pending
+    /// Remove the waker of this waiter (if any).
+    fn deregister(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.handle.inner.wakers.lock().unwrap().remove(&key);
+        }
+    }
+}
+
+impl Drop for CancelWaiter {
+    fn drop(&mut self) {
+        self.deregister();
     }
 }
 
 /// Errors for pending bundles.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum PendingBundleError {
     /// The bundle was not included in the target block.
     #[error("Bundle was not included in target block")]
     BundleNotIncluded,
-    /// An error occured while interacting with the RPC endpoint.
+    /// An error occurred while interacting with the RPC endpoint.
     #[error(transparent)]
     ProviderError(#[from] ProviderError),
+    /// The pending bundle was cancelled with its [`CancelHandle`].
+    #[error("Pending bundle was cancelled")]
+    Cancelled,
 }
 
-type PinBoxFut<'a, T> = Pin<Box<dyn Future<Output = Result<T, ProviderError>> + Send + 'a>>;
+impl PendingBundleError {
+    /// Get the kind of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            PendingBundleError::BundleNotIncluded => ErrorKind::NotIncluded,
+            PendingBundleError::ProviderError(_) => ErrorKind::Provider,
+            PendingBundleError::Cancelled => ErrorKind::Cancelled,
+        }
+    }
+
+    /// Whether retrying may succeed.
+    ///
+    /// See [`ErrorKind::is_retryable`].
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+}
+
+type PinBoxFut<'a, T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'a>>;
 
 enum PendingBundleState<'a> {
-    /// Waiting for an interval before calling API again
-    PausedGettingBlock,
+    /// The future has not been polled yet
+    NotStarted,
 
-    /// Polling the blockchain to get block information
-    GettingBlock(PinBoxFut<'a, Option<Block<TxHash>>>),
+    /// Polling the blockchain for the target block
+    Watching(PinBoxFut<'a, Option<BundleHash>, PendingBundleError>),
 
     /// Future has completed
     Completed,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::types::Block;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn owned_pending_bundle() {
+        let (provider, mock) = Provider::mocked();
+        let tx_hash = TxHash::repeat_byte(0x1);
+        let block = Block::<TxHash> {
+            number: Some(10.into()),
+            transactions: vec![tx_hash],
+            ..Default::default()
+        };
+        mock.push(block).unwrap();
+
+        let pending = PendingBundle::new(None, 10.into(), vec![tx_hash], &provider)
+            .interval(Duration::from_millis(1))
+            .into_owned();
+        let res = tokio::spawn(pending).await.unwrap();
+        assert_eq!(res.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn cancel_pending_bundle() {
+        let (provider, _mock) = Provider::mocked();
+        let provider = Arc::new(provider);
+
+        // The target block is never found, so the bundles only resolve
+        // when they are cancelled
+        let handle = CancelHandle::new();
+        let owned = OwnedPendingBundle::new(None, 10.into(), vec![], provider.clone())
+            .interval(Duration::from_millis(1))
+            .set_cancel_handle(handle.clone());
+        let task = tokio::spawn(owned);
+        let pending = PendingBundle::new(None, 10.into(), vec![], &provider)
+            .interval(Duration::from_millis(1))
+            .set_cancel_handle(handle.clone());
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!handle.is_cancelled());
+        handle.cancel();
+        handle.cancelled().await;
+
+        assert!(matches!(
+            task.await.unwrap(),
+            Err(PendingBundleError::Cancelled)
+        ));
+        assert!(matches!(pending.await, Err(PendingBundleError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn cancel_handle_wakers() {
+        let (provider, mock) = Provider::mocked();
+        let handle = CancelHandle::new();
+        let wakers = || handle.inner.wakers.lock().unwrap().len();
+
+        // Waiters deregister when they are dropped
+        let pending = PendingBundle::new(None, 10.into(), vec![], &provider)
+            .set_cancel_handle(handle.clone());
+        assert!(tokio::time::timeout(Duration::from_millis(1), pending)
+            .await
+            .is_err());
+        assert!(
+            tokio::time::timeout(Duration::from_millis(1), handle.cancelled())
+                .await
+                .is_err()
+        );
+        assert_eq!(wakers(), 0);
+
+        // Waiters deregister when they complete
+        mock.push(Block::<TxHash> {
+            number: Some(10.into()),
+            ..Default::default()
+        })
+        .unwrap();
+        let pending = PendingBundle::new(None, 10.into(), vec![], &provider)
+            .interval(Duration::from_millis(1))
+            .set_cancel_handle(handle.clone());
+        let res = handle.run_until_cancelled(pending).await;
+        assert_eq!(res.unwrap().unwrap(), None);
+        assert_eq!(wakers(), 0);
+    }
+
+    #[tokio::test]
+    async fn pending_bundle_error_budget() {
+        // The mocked provider errors when it has no responses
+        let (provider, _mock) = Provider::mocked();
+        let res = PendingBundle::new(None, 10.into(), vec![], &provider)
+            .interval(Duration::from_millis(1))
+            .error_backoff(Duration::from_millis(1), Duration::from_millis(4))
+            .max_errors(3)
+            .await;
+        assert!(matches!(res, Err(PendingBundleError::ProviderError(_))));
+    }
+
+    #[tokio::test]
+    async fn receipt_inclusion_check() {
+        let (provider, mock) = Provider::mocked();
+        let (first, last) = (TxHash::repeat_byte(0x1), TxHash::repeat_byte(0x2));
+        let receipt = |index: u64| TransactionReceipt {
+            block_number: Some(10.into()),
+            transaction_index: index.into(),
+            ..Default::default()
+        };
+        // Responses are returned in reverse order
+        mock.push(receipt(7)).unwrap();
+        mock.push(receipt(5)).unwrap();
+        mock.push(U64::from(10)).unwrap();
+        mock.push(U64::from(9)).unwrap();
+
+        let pending = PendingBundle::new(None, 10.into(), vec![first, first, last], &provider)
+            .interval(Duration::from_millis(1))
+            .inclusion_check(InclusionCheck::Receipts);
+        assert_eq!(pending.await.unwrap(), None);
+
+        // A transaction in between is missing
+        mock.push(receipt(8)).unwrap();
+        mock.push(receipt(5)).unwrap();
+        mock.push(U64::from(10)).unwrap();
+        let pending = PendingBundle::new(None, 10.into(), vec![first, first, last], &provider)
+            .interval(Duration::from_millis(1))
+            .inclusion_check(InclusionCheck::Receipts);
+        assert!(matches!(
+            pending.await,
+            Err(PendingBundleError::BundleNotIncluded)
+        ));
+    }
+
+    #[tokio::test]
+    async fn receipt_inclusion_check_missing_receipt() {
+        let (provider, mock) = Provider::mocked();
+        let tx_hash = TxHash::repeat_byte(0x1);
+        let receipt = |block: u64| TransactionReceipt {
+            block_number: Some(block.into()),
+            ..Default::default()
+        };
+        let pending = || {
+            PendingBundle::new(None, 10.into(), vec![tx_hash], &provider)
+                .interval(Duration::from_millis(1))
+                .inclusion_check(InclusionCheck::Receipts)
+        };
+
+        // The receipt is fetched again while the target block is the latest
+        // block. Responses are returned in reverse order.
+        mock.push(receipt(10)).unwrap();
+        mock.push(U64::from(10)).unwrap();
+        mock.push(Option::<TransactionReceipt>::None).unwrap();
+        mock.push(U64::from(10)).unwrap();
+        assert_eq!(pending().await.unwrap(), None);
+
+        // The receipt is still missing after the next block
+        mock.push(Option::<TransactionReceipt>::None).unwrap();
+        mock.push(U64::from(11)).unwrap();
+        assert!(matches!(
+            pending().await,
+            Err(PendingBundleError::BundleNotIncluded)
+        ));
+
+        // The transaction is in another block
+        mock.push(receipt(9)).unwrap();
+        mock.push(U64::from(10)).unwrap();
+        assert!(matches!(
+            pending().await,
+            Err(PendingBundleError::BundleNotIncluded)
+        ));
+    }
+
+    #[tokio::test]
+    async fn pending_bundle_confirmations() {
+        let (provider, mock) = Provider::mocked();
+        let tx_hash = TxHash::repeat_byte(0x1);
+        let block = Block::<TxHash> {
+            number: Some(10.into()),
+            transactions: vec![tx_hash],
+            ..Default::default()
+        };
+        // Responses are returned in reverse order
+        mock.push(block).unwrap();
+        mock.push(U64::from(12)).unwrap();
+        mock.push(U64::from(11)).unwrap();
+
+        let pending = PendingBundle::new(None, 10.into(), vec![tx_hash], &provider)
+            .interval(Duration::from_millis(1))
+            .confirmations(3);
+        assert_eq!(pending.await.unwrap(), None);
+        // The block is only fetched once it has 3 confirmations
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn pending_bundle_reorg() {
+        let (provider, mock) = Provider::mocked();
+        let tx_hash = TxHash::repeat_byte(0x1);
+        let block = |transactions| Block::<TxHash> {
+            number: Some(10.into()),
+            transactions,
+            ..Default::default()
+        };
+        // Responses are returned in reverse order. The target block is
+        // replaced by a block including the bundle after 1 block.
+        mock.push(block(vec![tx_hash])).unwrap();
+        mock.push(U64::from(11)).unwrap();
+        mock.push(block(vec![])).unwrap();
+        mock.push(U64::from(10)).unwrap();
+
+        let pending = PendingBundle::new(None, 10.into(), vec![tx_hash], &provider)
+            .interval(Duration::from_millis(1))
+            .reorg_blocks(2);
+        assert_eq!(pending.await.unwrap(), None);
+
+        // The bundle is not included once the reorg window has passed
+        mock.push(block(vec![])).unwrap();
+        mock.push(U64::from(12)).unwrap();
+        let pending = PendingBundle::new(None, 10.into(), vec![tx_hash], &provider)
+            .interval(Duration::from_millis(1))
+            .reorg_blocks(2);
+        assert!(matches!(
+            pending.await,
+            Err(PendingBundleError::BundleNotIncluded)
+        ));
+    }
+
+    #[tokio::test]
+    async fn inclusion_hooks() {
+        let (provider, mock) = Provider::mocked();
+        let (included, missing) = (TxHash::repeat_byte(0x1), TxHash::repeat_byte(0x2));
+        let block = Block::<TxHash> {
+            number: Some(10.into()),
+            transactions: vec![included],
+            ..Default::default()
+        };
+        let receipt = TransactionReceipt {
+            transaction_hash: included,
+            block_number: Some(10.into()),
+            ..Default::default()
+        };
+        // Responses are returned in reverse order
+        mock.push(Option::<TransactionReceipt>::None).unwrap();
+        mock.push(receipt).unwrap();
+        mock.push(block).unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let hook = {
+            let events = events.clone();
+            InclusionHook::new(move |event| {
+                events.lock().unwrap().push(event);
+                async {}
+            })
+        };
+        let res = PendingBundle::new(None, 10.into(), vec![included, missing], &provider)
+            .interval(Duration::from_millis(1))
+            .on_inclusion(hook)
+            .await;
+        assert!(matches!(res, Err(PendingBundleError::BundleNotIncluded)));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].status, InclusionStatus::PartiallyIncluded);
+        assert_eq!(events[0].receipts.len(), 1);
+        assert_eq!(events[0].receipts[0].transaction_hash, included);
+    }
+
+    #[test]
+    fn error_delay() {
+        let mut config = WatchConfig {
+            poll_interval: Duration::from_secs(2),
+            ..Default::default()
+        };
+        assert_eq!(config.error_delay(1), Duration::from_secs(2));
+        assert_eq!(config.error_delay(3), Duration::from_secs(8));
+        assert_eq!(config.error_delay(10), Duration::from_secs(60));
+
+        config.min_error_delay = Some(Duration::from_secs(1));
+        config.max_error_delay = Duration::from_secs(5);
+        assert_eq!(config.error_delay(1), Duration::from_secs(1));
+        assert_eq!(config.error_delay(4), Duration::from_secs(5));
+        assert_eq!(config.error_delay(100), Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,317 @@
+use crate::{
+    bundle::BundleRequest,
+    middleware::{BroadcasterMiddleware, FlashbotsMiddleware, FlashbotsMiddlewareError},
+    pending_bundle::PendingBundle,
+};
+use ethers::{
+    core::{
+        types::{H256, U64},
+        utils::keccak256,
+    },
+    providers::Middleware,
+    signers::Signer,
+};
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashSet},
+    future::Future,
+    time::Duration,
+};
+
+/// A queue of bundles waiting to be sent to a relay.
+///
+/// Bundles are tagged with a priority when they are pushed, and are
+/// popped highest priority first. Bundles with the same priority are
+/// popped in the order they were pushed.
+///
+/// Identical bundles targeting the same block are only queued once, even
+/// after they have been popped. Bundles are identical if they are sent to
+/// relays identically, so e.g. cancellations of different bundles, or
+/// bundles with the same transactions but a different replacement uuid,
+/// are queued separately. Use
+/// [`BundleQueue::prune`] to forget about bundles for past blocks, and
+/// about popped bundles without a target block.
+#[derive(Debug, Default)]
+pub struct BundleQueue {
+    bundles: BinaryHeap<QueuedBundle>,
+    seen: HashSet<(Option<U64>, H256)>,
+    pacing: Duration,
+    sequence: u64,
+}
+
+#[derive(Debug)]
+struct QueuedBundle {
+    priority: u64,
+    sequence: u64,
+    key: (Option<U64>, H256),
+    bundle: BundleRequest,
+}
+
+impl PartialEq for QueuedBundle {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedBundle {}
+
+impl PartialOrd for QueuedBundle {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedBundle {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Higher priorities first, then older bundles first.
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl BundleQueue {
+    /// Creates an empty bundle queue.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get the delay between bundle submissions when draining the queue.
+    pub fn pacing(&self) -> Duration {
+        self.pacing
+    }
+
+    /// Set the delay between bundle submissions when draining the queue.
+    pub fn set_pacing(mut self, pacing: Duration) -> Self {
+        self.pacing = pacing;
+        self
+    }
+
+    /// The number of bundles in the queue.
+    pub fn len(&self) -> usize {
+        self.bundles.len()
+    }
+
+    /// Whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bundles.is_empty()
+    }
+
+    /// Adds a bundle to the queue with the given priority.
+    ///
+    /// Returns `false` if an identical bundle targeting the same block
+    /// has already been queued, in which case the bundle is not added.
+    pub fn push(&mut self, bundle: BundleRequest, priority: u64) -> bool {
+        let key = Self::key(&bundle);
+        if !self.seen.insert(key) {
+            return false;
+        }
+
+        self.sequence += 1;
+        self.bundles.push(QueuedBundle {
+            priority,
+            sequence: self.sequence,
+            key,
+            bundle,
+        });
+
+        true
+    }
+
+    /// Removes the bundle with the highest priority from the queue.
+    pub fn pop(&mut self) -> Option<BundleRequest> {
+        self.bundles.pop().map(|queued| queued.bundle)
+    }
+
+    /// Removes all bundles targeting blocks before the given block, and
+    /// forgets about previously queued bundles for those blocks.
+    ///
+    /// Bundles without a target block are never removed, but once they
+    /// have been popped they are forgotten as well.
+    pub fn prune(&mut self, block: U64) {
+        let is_current = |target: Option<U64>| !matches!(target, Some(target) if target < block);
+
+        self.bundles
+            .retain(|queued| is_current(queued.bundle.block()));
+
+        let untargeted: HashSet<_> = self
+            .bundles
+            .iter()
+            .filter(|queued| queued.key.0.is_none())
+            .map(|queued| queued.key)
+            .collect();
+        self.seen.retain(|key| match key.0 {
+            Some(target) => target >= block,
+            None => untargeted.contains(key),
+        });
+    }
+
+    /// Sends all queued bundles through the middleware, highest priority
+    /// first, waiting for the configured pacing between submissions.
+    pub async fn drain<'a, M: Middleware, S: Signer>(
+        &mut self,
+        middleware: &'a FlashbotsMiddleware<M, S>,
+    ) -> Vec<Result<PendingBundle<'a, M::Provider>, FlashbotsMiddlewareError<M, S>>> {
+        self.drain_with(|bundle| async move { middleware.send_bundle(&bundle).await })
+            .await
+    }
+
+    /// Broadcasts all queued bundles to the builders of the broadcaster,
+    /// highest priority first, waiting for the configured pacing between
+    /// submissions.
+    ///
+    /// See [`BroadcasterMiddleware::send_bundle`].
+    pub async fn drain_to_broadcaster<'a, M: Middleware, S: Signer>(
+        &mut self,
+        broadcaster: &'a BroadcasterMiddleware<M, S>,
+    ) -> Vec<
+        Result<
+            Vec<Result<PendingBundle<'a, M::Provider>, FlashbotsMiddlewareError<M, S>>>,
+            FlashbotsMiddlewareError<M, S>,
+        >,
+    > {
+        self.drain_with(|bundle| async move { broadcaster.send_bundle(&bundle).await })
+            .await
+    }
+
+    /// Sends all queued bundles using the given function, highest priority
+    /// first, waiting for the configured pacing between submissions.
+    ///
+    /// This can be used to send cancellations with
+    /// [`FlashbotsMiddleware::send_cancellation`], or to send bundles
+    /// through several middlewares.
+    pub async fn drain_with<F, Fut, T>(&mut self, mut send: F) -> Vec<T>
+    where
+        F: FnMut(BundleRequest) -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let mut results = Vec::with_capacity(self.len());
+
+        while let Some(bundle) = self.pop() {
+            if !results.is_empty() && !self.pacing.is_zero() {
+                tokio::time::sleep(self.pacing).await;
+            }
+
+            results.push(send(bundle).await);
+        }
+
+        results
+    }
+
+    fn key(bundle: &BundleRequest) -> (Option<U64>, H256) {
+        let serialized = serde_json::to_vec(bundle).expect("bundles can be serialized");
+
+        (bundle.block(), keccak256(serialized).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::types::Bytes;
+    use uuid::Uuid;
+
+    #[test]
+    fn queue_priority() {
+        let mut queue = BundleQueue::new();
+        let low = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(1.into());
+        let high = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x2]))
+            .set_block(1.into());
+        let high_later = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x3]))
+            .set_block(1.into());
+
+        assert!(queue.push(low, 1));
+        assert!(queue.push(high, 5));
+        assert!(queue.push(high_later, 5));
+
+        let popped: Vec<_> = std::iter::from_fn(|| queue.pop())
+            .map(|bundle| bundle.transaction_hashes()[0])
+            .collect();
+        assert_eq!(
+            popped,
+            vec![
+                H256::from(keccak256([0x2])),
+                keccak256([0x3]).into(),
+                keccak256([0x1]).into()
+            ]
+        );
+    }
+
+    #[test]
+    fn queue_deduplicates() {
+        let mut queue = BundleQueue::new();
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(1.into());
+
+        assert!(queue.push(bundle.clone(), 1));
+        assert!(!queue.push(bundle.clone(), 2));
+        assert!(queue.push(bundle.clone().set_block(2.into()), 1));
+        assert_eq!(queue.len(), 2);
+
+        queue.prune(2.into());
+        assert_eq!(queue.len(), 1);
+        assert!(!queue.push(bundle.set_block(2.into()), 1));
+    }
+
+    #[test]
+    fn queue_prunes_untargeted() {
+        let mut queue = BundleQueue::new();
+        let queued = BundleRequest::new().push_transaction(Bytes::from(vec![0x1]));
+        let popped = BundleRequest::new().push_transaction(Bytes::from(vec![0x2]));
+
+        assert!(queue.push(popped.clone(), 2));
+        assert!(queue.push(queued.clone(), 1));
+        queue.pop();
+
+        // Popped bundles are only deduplicated until the next prune
+        assert!(!queue.push(popped.clone(), 2));
+        queue.prune(10.into());
+        assert_eq!(queue.len(), 1);
+        assert!(!queue.push(queued, 1));
+        assert!(queue.push(popped, 2));
+    }
+
+    #[test]
+    fn queue_distinguishes_cancellations() {
+        let mut queue = BundleQueue::new();
+        let first = BundleRequest::cancellation(Uuid::from_u128(1)).set_block(1.into());
+        let second = BundleRequest::cancellation(Uuid::from_u128(2)).set_block(1.into());
+
+        assert!(queue.push(first.clone(), 1));
+        assert!(queue.push(second, 1));
+        assert!(!queue.push(first, 1));
+        assert_eq!(queue.len(), 2);
+
+        // Bundles with the same transactions but different submission
+        // fields are not identical either
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(1.into());
+        assert!(queue.push(bundle.clone(), 1));
+        assert!(queue.push(bundle.set_min_timestamp(10), 1));
+        assert_eq!(queue.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn drain_with_send_function() {
+        let mut queue = BundleQueue::new();
+        let low = BundleRequest::cancellation(Uuid::from_u128(1)).set_block(1.into());
+        let high = BundleRequest::cancellation(Uuid::from_u128(2)).set_block(1.into());
+        assert!(queue.push(low, 1));
+        assert!(queue.push(high, 2));
+
+        let sent = queue
+            .drain_with(|bundle| async move { *bundle.uuid() })
+            .await;
+        assert_eq!(
+            sent,
+            vec![Some(Uuid::from_u128(2)), Some(Uuid::from_u128(1))]
+        );
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+use std::{
+    num::NonZeroU32,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// A client-side rate limit for requests to a relay.
+///
+/// Limits are enforced using token buckets, so short bursts up to the
+/// limit are allowed. If a request would exceed the limit, it is delayed
+/// until it fits within the limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    per_second: Option<NonZeroU32>,
+    per_minute: Option<NonZeroU32>,
+}
+
+impl RateLimit {
+    /// Creates a rate limit without any limits.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get the maximum number of requests per second (if any).
+    pub fn per_second(&self) -> Option<NonZeroU32> {
+        self.per_second
+    }
+
+    /// Set the maximum number of requests per second.
+    pub fn set_per_second(mut self, requests: NonZeroU32) -> Self {
+        self.per_second = Some(requests);
+        self
+    }
+
+    /// Get the maximum number of requests per minute (if any).
+    pub fn per_minute(&self) -> Option<NonZeroU32> {
+        self.per_minute
+    }
+
+    /// Set the maximum number of requests per minute.
+    pub fn set_per_minute(mut self, requests: NonZeroU32) -> Self {
+        self.per_minute = Some(requests);
+        self
+    }
+}
+
+/// Enforces a [`RateLimit`].
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    buckets: Mutex<Vec<TokenBucket>>,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_second: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: NonZeroU32, period: Duration, now: Instant) -> Self {
+        let capacity = capacity.get();
+        Self {
+            capacity: capacity as f64,
+            tokens: capacity as f64,
+            refill_per_second: capacity as f64 / period.as_secs_f64(),
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// The time until a token is available.
+    fn wait_time(&self) -> Duration {
+        if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.refill_per_second)
+        }
+    }
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        let now = Instant::now();
+        let buckets = [
+            (limit.per_second, Duration::from_secs(1)),
+            (limit.per_minute, Duration::from_secs(60)),
+        ]
+        .iter()
+        .filter_map(|(requests, period)| requests.map(|r| TokenBucket::new(r, *period, now)))
+        .collect();
+
+        Self {
+            limit,
+            buckets: Mutex::new(buckets),
+        }
+    }
+
+    pub(crate) fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    /// Try to take a token from every bucket, returning how long to wait
+    /// before trying again if any of the buckets are empty.
+    pub(crate) fn try_acquire(&self, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        buckets.iter_mut().for_each(|bucket| bucket.refill(now));
+
+        let wait = buckets
+            .iter()
+            .map(TokenBucket::wait_time)
+            .max()
+            .unwrap_or(Duration::ZERO);
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+
+        buckets.iter_mut().for_each(|bucket| bucket.tokens -= 1.0);
+        Ok(())
+    }
+
+    /// Wait until a request fits within the rate limit.
+    pub(crate) async fn acquire(&self) {
+        while let Err(wait) = self.try_acquire(Instant::now()) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limiter_limits() {
+        let limiter = RateLimiter::new(
+            RateLimit::new()
+                .set_per_second(NonZeroU32::new(2).unwrap())
+                .set_per_minute(NonZeroU32::new(3).unwrap()),
+        );
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire(now).is_ok());
+        assert!(limiter.try_acquire(now).is_ok());
+        assert!(limiter.try_acquire(now).is_err());
+
+        // The per-second bucket has refilled, the per-minute bucket has not
+        let later = now + Duration::from_secs(1);
+        assert!(limiter.try_acquire(later).is_ok());
+        let wait = limiter.try_acquire(later).unwrap_err();
+        assert!(wait > Duration::from_secs(1));
+    }
+
+    #[test]
+    fn rate_limiter_unlimited() {
+        let limiter = RateLimiter::new(RateLimit::new());
+        let now = Instant::now();
+
+        for _ in 0..100 {
+            assert!(limiter.try_acquire(now).is_ok());
+        }
+    }
+}
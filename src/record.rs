@@ -0,0 +1,188 @@
+use crate::bundle::{deserialize_uuid_from_string, serialize_uuid_as_string, BundleRequest};
+use chrono::{DateTime, Utc};
+use ethers::core::types::U64;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, fs, io, path::Path};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// A durable record of a bundle.
+///
+/// A record contains the bundle as it is sent to relays, along with the
+/// parts of the bundle that are not sent as-is (its builder-specific
+/// fields, split points and tracking uuid) and some metadata, and can be
+/// written to and read from JSON files.
+/// This allows bundles to be archived for audits, or replayed after a
+/// restart.
+///
+/// Note that signed transactions are stored as RLP, so after reading a
+/// record all of the transactions in the bundle are
+/// [`BundleTransaction::Raw`](crate::BundleTransaction::Raw).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleRecord {
+    /// When the record was created.
+    pub created_at: DateTime<Utc>,
+    /// The client-side tracking uuid of the bundle (if any).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_uuid_as_string")]
+    #[serde(deserialize_with = "deserialize_uuid_from_string")]
+    pub tracking_uuid: Option<Uuid>,
+    /// The target block of the bundle (if any).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_block: Option<U64>,
+    /// The builder-specific fields of the bundle, by field profile.
+    ///
+    /// See [`BundleRequest::set_builder_field`].
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub builder_fields: BTreeMap<String, BTreeMap<String, Value>>,
+    /// The split points of the bundle.
+    ///
+    /// See [`BundleRequest::split_points`].
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub split_points: Vec<usize>,
+    /// The bundle.
+    pub bundle: BundleRequest,
+}
+
+/// Errors for reading and writing bundle records.
+#[derive(Error, Debug)]
+pub enum BundleRecordError {
+    /// The record file could not be read or written.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// The record could not be serialized or deserialized.
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+impl BundleRecord {
+    /// Creates a new record of the bundle, created now.
+    pub fn new(bundle: BundleRequest) -> Self {
+        Self {
+            created_at: Utc::now(),
+            tracking_uuid: bundle.tracking_uuid(),
+            target_block: bundle.block(),
+            builder_fields: bundle.all_builder_fields().clone(),
+            split_points: bundle.split_points().to_vec(),
+            bundle,
+        }
+    }
+
+    /// Consumes the record, returning the bundle.
+    ///
+    /// The tracking uuid, builder-specific fields and split points of the
+    /// record are restored on the bundle.
+    pub fn into_bundle(self) -> BundleRequest {
+        let bundle = self
+            .bundle
+            .set_all_builder_fields(self.builder_fields)
+            .set_split_points(self.split_points);
+        match self.tracking_uuid {
+            Some(uuid) => bundle.set_tracking_uuid(uuid),
+            None => bundle,
+        }
+    }
+
+    /// Writes the record to a JSON file.
+    pub fn to_file(&self, path: impl AsRef<Path>) -> Result<(), BundleRecordError> {
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Reads a record from a JSON file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, BundleRecordError> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+}
+
+impl BundleRequest {
+    /// Writes the bundle to a JSON file, along with metadata.
+    ///
+    /// See [`BundleRecord`].
+    pub fn to_file(&self, path: impl AsRef<Path>) -> Result<(), BundleRecordError> {
+        BundleRecord::new(self.clone()).to_file(path)
+    }
+
+    /// Reads a bundle from a JSON file written by [`BundleRequest::to_file`].
+    ///
+    /// See [`BundleRecord`].
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, BundleRecordError> {
+        Ok(BundleRecord::from_file(path)?.into_bundle())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::types::Bytes;
+    use std::{
+        path::PathBuf,
+        process,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    use uuid::uuid;
+
+    /// A path in the temporary directory that is unique to this test run.
+    fn temp_path() -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        std::env::temp_dir().join(format!(
+            "ethers-flashbots-bundle-record-{}-{}.json",
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ))
+    }
+
+    #[test]
+    fn bundle_record_roundtrip() {
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .push_revertible_transaction(Bytes::from(vec![0x2]))
+            .set_block(2.into())
+            .set_uuid(uuid!("67e55044-10b1-426f-9247-bb680e5fe0c8"))
+            .set_tracking_uuid(uuid!("2ab14fc2-8e5a-4b2e-9d4a-6a0f1e0c9d11"));
+
+        let path = temp_path();
+        bundle.to_file(&path).unwrap();
+
+        let record = BundleRecord::from_file(&path).unwrap();
+        assert_eq!(record.target_block, Some(2.into()));
+        assert_eq!(record.tracking_uuid, bundle.tracking_uuid());
+
+        let restored = BundleRequest::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(restored.tracking_uuid(), bundle.tracking_uuid());
+        assert_eq!(
+            serde_json::to_string(&restored).unwrap(),
+            serde_json::to_string(&bundle).unwrap()
+        );
+    }
+
+    #[test]
+    fn bundle_record_keeps_builder_fields_and_split_points() {
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .push_split_point()
+            .push_transaction(Bytes::from(vec![0x2]))
+            .set_block(2.into())
+            .set_builder_field("titan", "refundIndex", 0);
+
+        let path = temp_path();
+        bundle.to_file(&path).unwrap();
+        let restored = BundleRequest::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(restored.split_points(), &[1]);
+        assert_eq!(
+            restored.builder_fields("titan"),
+            bundle.builder_fields("titan")
+        );
+        assert_eq!(restored.split(1).unwrap().len(), 2);
+    }
+}
@@ -1,18 +1,52 @@
+#[cfg(feature = "reqwest-transport")]
+use crate::transport::into_reqwest_error;
 use crate::{
+    builders::BuilderEndpoint,
     bundle::BundleHash,
+    capabilities::BuilderCapabilities,
+    circuit_breaker::{CircuitBreaker, CircuitBreakerState},
+    dedupe::{DuplicatePolicy, SubmissionCache},
+    dialect::RelayDialect,
+    error::ErrorKind,
+    failover::{is_unreachable, Failover, FailoverState},
     jsonrpc::{JsonRpcError, Request, Response},
+    limits::BundleLimits,
+    logging::RequestLogging,
+    network::Network,
+    rate_limit::{RateLimit, RateLimiter},
+    relay_stats::{RelayStats, RelayStatsRecorder},
+    transport::{default_transport, HttpResponse, HttpTransport, ResponseTooLarge, TransportError},
 };
 use ethers::core::{
     types::{H256, U64},
     utils::keccak256,
 };
 use ethers::signers::Signer;
+#[cfg(feature = "reqwest-transport")]
 use reqwest::{Client, Error as ReqwestError};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::sync::atomic::{AtomicU64, Ordering};
+use serde_json::Value;
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    future::Future,
+    hash::{BuildHasher, Hasher},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
 use thiserror::Error;
+#[cfg(feature = "rt-multi-thread")]
+use tokio::runtime::{Handle, RuntimeFlavor};
 use url::Url;
 
+/// The default name of the signature header.
+const DEFAULT_SIGNATURE_HEADER: &str = "X-Flashbots-Signature";
+
+/// The default maximum size of relay responses.
+const DEFAULT_MAX_RESPONSE_SIZE: usize = 10 * 1024 * 1024;
+
 /// A Flashbots relay client.
 ///
 /// The client automatically signs every request and sets the Flashbots
@@ -23,24 +57,102 @@ use url::Url;
 /// [`FlashbotsMiddleware`](crate::FlashbotsMiddleware) instead.
 #[derive(Debug)]
 pub struct Relay<S> {
-    id: AtomicU64,
-    client: Client,
+    id: Arc<AtomicU64>,
+    id_policy: RequestIdPolicy,
+    transport: Arc<dyn HttpTransport>,
     url: Url,
     signer: Option<S>,
+    signature_scheme: SignatureScheme,
+    signature_header: String,
+    blocking_signing: bool,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    method_rate_limiters: HashMap<String, Arc<RateLimiter>>,
+    failover: Option<Arc<FailoverState>>,
+    stats: Arc<RelayStatsRecorder>,
+    circuit_breaker: Option<Arc<CircuitBreakerState>>,
+    submission_cache: Option<Arc<SubmissionCache>>,
+    bundle_limits: BundleLimits,
+    max_response_size: usize,
+    capabilities: BuilderCapabilities,
+    dialect: RelayDialect,
+    auth_header: Option<String>,
+    user_agent: Option<String>,
+    headers: Vec<(String, String)>,
+    logging: Option<RequestLogging>,
+    field_profile: Option<String>,
+    chain_id: Option<u64>,
+}
+
+/// How a [`Relay`] assigns JSON-RPC request ids when it is cloned.
+///
+/// See [`Relay::set_request_id_policy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RequestIdPolicy {
+    /// Clones share the id counter of the relay, so ids are unique across
+    /// all clones.
+    #[default]
+    Shared,
+    /// Clones get their own id counter, starting from a random offset.
+    RandomOffset,
+}
+
+/// How a [`Relay`] signs requests for the signature header.
+///
+/// See [`Relay::set_signature_scheme`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SignatureScheme {
+    /// Sign the hex-encoded keccak hash of the request body, as specified
+    /// by Flashbots.
+    #[default]
+    Flashbots,
+    /// Sign the request body itself.
+    RawBody,
 }
 
 /// Errors for relay requests.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum RelayError<S: Signer> {
     /// The request failed.
-    #[error(transparent)]
-    RequestError(#[from] ReqwestError),
-    /// The request could not be parsed.
-    #[error(transparent)]
-    JsonRpcError(#[from] JsonRpcError),
+    ///
+    /// This is only returned by the `reqwest` transport, and requires the
+    /// `reqwest-transport` feature.
+    #[cfg(feature = "reqwest-transport")]
+    #[error("{err}")]
+    RequestError {
+        /// The JSON-RPC id of the request.
+        id: u64,
+        err: ReqwestError,
+    },
+    /// The request failed in a custom transport.
+    #[error("{err}")]
+    TransportError {
+        /// The JSON-RPC id of the request.
+        id: u64,
+        err: TransportError,
+    },
+    /// The relay returned a server error.
+    #[error("Server error ({status}): {text}")]
+    ServerError {
+        /// The JSON-RPC id of the request.
+        id: u64,
+        status: u16,
+        text: String,
+    },
+    /// The relay responded with a JSON-RPC error.
+    #[error("{err}")]
+    JsonRpcError {
+        /// The JSON-RPC id of the request.
+        id: u64,
+        err: JsonRpcError,
+    },
     /// The request parameters were invalid.
     #[error("Client error: {text}")]
-    ClientError { text: String },
+    ClientError {
+        /// The JSON-RPC id of the request.
+        id: u64,
+        text: String,
+    },
     /// The request could not be serialized.
     #[error(transparent)]
     RequestSerdeJson(#[from] serde_json::Error),
@@ -50,22 +162,506 @@ pub enum RelayError<S: Signer> {
     /// The response could not be deserialized.
     #[error("Deserialization error: {err}. Response: {text}")]
     ResponseSerdeJson {
+        /// The JSON-RPC id of the request.
+        id: u64,
         err: serde_json::Error,
         text: String,
     },
+    /// The circuit breaker of the relay is open, so the request was not
+    /// sent.
+    ///
+    /// See [`CircuitBreaker`].
+    #[error("The circuit breaker of the relay is open")]
+    CircuitOpen,
+    /// The response of the relay exceeded the maximum response size.
+    ///
+    /// See [`Relay::set_max_response_size`].
+    #[error("The response exceeds the maximum size of {limit} bytes")]
+    ResponseTooLarge {
+        /// The JSON-RPC id of the request.
+        id: u64,
+        /// The maximum response size, in bytes.
+        limit: usize,
+    },
+}
+
+impl<S: Signer> RelayError<S> {
+    /// Get the kind of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            #[cfg(feature = "reqwest-transport")]
+            RelayError::RequestError { err, .. } => match err.status() {
+                Some(status) if status.is_server_error() => ErrorKind::Server,
+                Some(status) if status.is_client_error() => ErrorKind::InvalidRequest,
+                _ if err.is_decode() => ErrorKind::InvalidResponse,
+                _ => ErrorKind::Network,
+            },
+            RelayError::TransportError { .. } => ErrorKind::Network,
+            RelayError::ServerError { .. } => ErrorKind::Server,
+            RelayError::JsonRpcError { err, .. } => err.kind(),
+            RelayError::ClientError { .. } | RelayError::RequestSerdeJson(_) => {
+                ErrorKind::InvalidRequest
+            }
+            RelayError::SignerError(_) => ErrorKind::Signer,
+            RelayError::ResponseSerdeJson { .. } | RelayError::ResponseTooLarge { .. } => {
+                ErrorKind::InvalidResponse
+            }
+            RelayError::CircuitOpen => ErrorKind::Unavailable,
+        }
+    }
+
+    /// Whether retrying the request may succeed.
+    ///
+    /// See [`ErrorKind::is_retryable`].
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+
+    /// Get the JSON-RPC error returned by the relay (if any).
+    pub fn json_rpc_error(&self) -> Option<&JsonRpcError> {
+        match self {
+            RelayError::JsonRpcError { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Get the JSON-RPC id of the request that failed with this error (if
+    /// any).
+    ///
+    /// This is the same as [`SignedRequest::id`], and can be used to
+    /// correlate errors with outgoing requests.
+    pub fn request_id(&self) -> Option<u64> {
+        match self {
+            #[cfg(feature = "reqwest-transport")]
+            RelayError::RequestError { id, .. } => Some(*id),
+            RelayError::TransportError { id, .. }
+            | RelayError::ServerError { id, .. }
+            | RelayError::JsonRpcError { id, .. }
+            | RelayError::ClientError { id, .. }
+            | RelayError::ResponseSerdeJson { id, .. }
+            | RelayError::ResponseTooLarge { id, .. } => Some(*id),
+            _ => None,
+        }
+    }
 }
 
 impl<S: Signer> Relay<S> {
     /// Initializes a new relay client.
+    ///
+    /// Requests are sent using a new [`reqwest::Client`], unless the
+    /// `reqwest-transport` feature is disabled, in which case a transport
+    /// has to be set using [`Relay::set_transport`].
     pub fn new(url: impl Into<Url>, signer: Option<S>) -> Self {
+        Self::with_transport(url, signer, default_transport())
+    }
+
+    /// Initializes a new relay client that sends requests using the given
+    /// HTTP client.
+    ///
+    /// Clones of a [`Client`] share its connection pool, so a single client
+    /// can be shared by several relays.
+    #[cfg(feature = "reqwest-transport")]
+    pub fn with_client(url: impl Into<Url>, signer: Option<S>, client: Client) -> Self {
+        Self::with_transport(url, signer, Arc::new(client))
+    }
+
+    /// Initializes a new relay client that sends requests using the given
+    /// HTTP transport.
+    ///
+    /// The transport can be shared by several relays. See [`HttpTransport`].
+    pub fn with_transport(
+        url: impl Into<Url>,
+        signer: Option<S>,
+        transport: Arc<dyn HttpTransport>,
+    ) -> Self {
         Self {
-            id: AtomicU64::new(0),
-            client: Client::new(),
+            id: Arc::new(AtomicU64::new(0)),
+            id_policy: RequestIdPolicy::default(),
+            transport,
             url: url.into(),
             signer,
+            signature_scheme: SignatureScheme::default(),
+            signature_header: DEFAULT_SIGNATURE_HEADER.to_string(),
+            blocking_signing: false,
+            rate_limiter: None,
+            method_rate_limiters: HashMap::new(),
+            failover: None,
+            stats: Default::default(),
+            circuit_breaker: None,
+            submission_cache: None,
+            bundle_limits: BundleLimits::default(),
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            capabilities: BuilderCapabilities::default(),
+            dialect: RelayDialect::default(),
+            auth_header: None,
+            user_agent: None,
+            headers: Vec::new(),
+            logging: None,
+            field_profile: None,
+            chain_id: None,
         }
     }
 
+    /// Initializes a new relay client for a well-known builder endpoint,
+    /// with the dialect and capabilities of the builder.
+    ///
+    /// The field profile of the relay is set to the name of the builder,
+    /// and the chain ID of the relay to the chain of the builder.
+    ///
+    /// See [`builders`](crate::builders).
+    pub fn from_endpoint(endpoint: &BuilderEndpoint, signer: Option<S>) -> Self {
+        Self::from_endpoint_with_transport(endpoint, signer, default_transport())
+    }
+
+    pub(crate) fn from_endpoint_with_transport(
+        endpoint: &BuilderEndpoint,
+        signer: Option<S>,
+        transport: Arc<dyn HttpTransport>,
+    ) -> Self {
+        let mut relay = Self::with_transport(endpoint.url(), signer, transport);
+        relay.set_dialect(endpoint.dialect());
+        relay.set_capabilities(endpoint.capabilities());
+        relay.set_field_profile(endpoint.name());
+        relay.set_chain_id(endpoint.chain_id());
+        relay
+    }
+
+    /// Get the API dialect of the relay.
+    pub fn dialect(&self) -> RelayDialect {
+        self.dialect
+    }
+
+    /// Set the API dialect of the relay.
+    ///
+    /// See [`RelayDialect`].
+    pub fn set_dialect(&mut self, dialect: RelayDialect) {
+        self.dialect = dialect;
+    }
+
+    /// Get the value of the `Authorization` header sent with requests to
+    /// the relay (if any).
+    pub fn auth_header(&self) -> Option<&str> {
+        self.auth_header.as_deref()
+    }
+
+    /// Set the value of the `Authorization` header sent with requests to
+    /// the relay.
+    ///
+    /// This is required by some builders, e.g. bloXroute. Requests are
+    /// still signed using the signer of the relay (if any).
+    pub fn set_auth_header(&mut self, auth_header: impl Into<String>) {
+        self.auth_header = Some(auth_header.into());
+    }
+
+    /// Get the value of the `User-Agent` header sent with requests to the
+    /// relay (if any).
+    pub fn user_agent(&self) -> Option<&str> {
+        self.user_agent.as_deref()
+    }
+
+    /// Set the value of the `User-Agent` header sent with requests to the
+    /// relay, e.g. to identify your infrastructure in relay logs.
+    pub fn set_user_agent(&mut self, user_agent: impl Into<String>) {
+        self.user_agent = Some(user_agent.into());
+    }
+
+    /// Get the additional headers sent with requests to the relay.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Add a header that is sent with every request to the relay.
+    ///
+    /// Headers set by the relay client itself, such as the signature
+    /// header, are always sent as well.
+    pub fn add_header(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.headers.push((name.into(), value.into()));
+    }
+
+    /// Get the logging configuration of the relay (if logging is
+    /// enabled).
+    pub fn logging(&self) -> Option<RequestLogging> {
+        self.logging
+    }
+
+    /// Enable logging of outgoing requests to the relay.
+    ///
+    /// See [`RequestLogging`].
+    pub fn set_logging(&mut self, logging: RequestLogging) {
+        self.logging = Some(logging);
+    }
+
+    /// Get how request ids are assigned when the relay is cloned.
+    pub fn request_id_policy(&self) -> RequestIdPolicy {
+        self.id_policy
+    }
+
+    /// Set how request ids are assigned when the relay is cloned.
+    ///
+    /// See [`RequestIdPolicy`].
+    pub fn set_request_id_policy(&mut self, policy: RequestIdPolicy) {
+        self.id_policy = policy;
+    }
+
+    /// Get how requests to the relay are signed.
+    pub fn signature_scheme(&self) -> SignatureScheme {
+        self.signature_scheme
+    }
+
+    /// Set how requests to the relay are signed.
+    ///
+    /// See [`SignatureScheme`].
+    pub fn set_signature_scheme(&mut self, scheme: SignatureScheme) {
+        self.signature_scheme = scheme;
+    }
+
+    /// Get the name of the header the signature is sent in.
+    pub fn signature_header(&self) -> &str {
+        &self.signature_header
+    }
+
+    /// Set the name of the header the signature is sent in, for builders
+    /// that use the Flashbots signature under a different name (e.g.
+    /// `X-Auction-Signature`).
+    ///
+    /// Defaults to `X-Flashbots-Signature`.
+    pub fn set_signature_header(&mut self, name: impl Into<String>) {
+        self.signature_header = name.into();
+    }
+
+    /// Whether requests are signed on a blocking thread.
+    pub fn blocking_signing(&self) -> bool {
+        self.blocking_signing
+    }
+
+    /// Set whether requests are signed on a blocking thread.
+    ///
+    /// This keeps other tasks responsive if the signer is CPU-heavy or
+    /// blocks, e.g. because it calls out to an HSM synchronously. The
+    /// other tasks of the worker are moved to another thread while the
+    /// request is signed (see [`tokio::task::block_in_place`]), which
+    /// requires the `rt-multi-thread` feature (enabled by default) and the
+    /// multi-threaded Tokio runtime. Otherwise requests are signed on the
+    /// async worker as usual, and a warning is logged.
+    ///
+    /// Signing is not moved to [`tokio::task::spawn_blocking`], which also
+    /// works on the current-thread runtime, since that would require the
+    /// signer of every relay to be `'static`.
+    pub fn set_blocking_signing(&mut self, blocking: bool) {
+        self.blocking_signing = blocking;
+    }
+
+    /// Set the HTTP transport used to send requests to the relay.
+    ///
+    /// See [`HttpTransport`].
+    pub fn set_transport(&mut self, transport: impl HttpTransport + 'static) {
+        self.transport = Arc::new(transport);
+    }
+
+    /// Get the HTTP transport used to send requests to the relay.
+    pub(crate) fn transport(&self) -> &dyn HttpTransport {
+        self.transport.as_ref()
+    }
+
+    /// Get the signer used to sign requests (if any).
+    pub(crate) fn signer(&self) -> Option<&S> {
+        self.signer.as_ref()
+    }
+
+    /// Creates a relay client for another endpoint, with the transport and
+    /// settings of this relay.
+    ///
+    /// State that belongs to the endpoint is not shared, i.e. the new relay
+    /// has its own request ids, stats, rate limits, circuit breaker,
+    /// failover and submission cache.
+    pub(crate) fn for_endpoint(&self, url: Url, signer: Option<S>) -> Self {
+        Self {
+            id: Arc::new(AtomicU64::new(0)),
+            id_policy: self.id_policy,
+            transport: self.transport.clone(),
+            url,
+            signer,
+            signature_scheme: self.signature_scheme,
+            signature_header: self.signature_header.clone(),
+            blocking_signing: self.blocking_signing,
+            rate_limiter: None,
+            method_rate_limiters: HashMap::new(),
+            failover: None,
+            stats: Default::default(),
+            circuit_breaker: None,
+            submission_cache: None,
+            bundle_limits: self.bundle_limits,
+            max_response_size: self.max_response_size,
+            capabilities: self.capabilities,
+            dialect: self.dialect,
+            auth_header: self.auth_header.clone(),
+            user_agent: self.user_agent.clone(),
+            headers: self.headers.clone(),
+            logging: self.logging,
+            field_profile: self.field_profile.clone(),
+            chain_id: self.chain_id,
+        }
+    }
+
+    /// Get the URL of the relay.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Get the ID of the chain the relay builds blocks for (if known).
+    pub fn chain_id(&self) -> Option<u64> {
+        self.chain_id
+    }
+
+    /// Get the network the relay builds blocks for (if known).
+    pub fn network(&self) -> Option<Network> {
+        self.chain_id.and_then(Network::from_chain_id)
+    }
+
+    /// Set the ID of the chain the relay builds blocks for.
+    ///
+    /// This is used to check that the relay and the provider are on the
+    /// same chain, see [`FlashbotsMiddleware::verify`](crate::FlashbotsMiddleware::verify).
+    pub fn set_chain_id(&mut self, chain_id: u64) {
+        self.chain_id = Some(chain_id);
+    }
+
+    /// Get the field profile of the relay (if any).
+    pub fn field_profile(&self) -> Option<&str> {
+        self.field_profile.as_deref()
+    }
+
+    /// Set the field profile of the relay.
+    ///
+    /// Bundles sent to the relay include the builder-specific fields set
+    /// for this profile using [`BundleRequest::set_builder_field`](crate::BundleRequest::set_builder_field).
+    pub fn set_field_profile(&mut self, profile: impl Into<String>) {
+        self.field_profile = Some(profile.into());
+    }
+
+    /// Get the capabilities of the builder behind the relay.
+    pub fn capabilities(&self) -> BuilderCapabilities {
+        self.capabilities
+    }
+
+    /// Set the capabilities of the builder behind the relay.
+    ///
+    /// See [`BuilderCapabilities`].
+    pub fn set_capabilities(&mut self, capabilities: BuilderCapabilities) {
+        self.capabilities = capabilities;
+    }
+
+    /// Get the client-side rate limit of the relay (if any).
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limiter.as_ref().map(|limiter| limiter.limit())
+    }
+
+    /// Set a client-side rate limit for requests to the relay.
+    ///
+    /// Requests that would exceed the limit are delayed until they fit
+    /// within it. Clones of the relay share the same limit.
+    pub fn set_rate_limit(&mut self, limit: RateLimit) {
+        self.rate_limiter = Some(Arc::new(RateLimiter::new(limit)));
+    }
+
+    /// Get the client-side rate limit for requests with the given method
+    /// (if any).
+    pub fn method_rate_limit(&self, method: &str) -> Option<RateLimit> {
+        self.method_rate_limiters
+            .get(method)
+            .map(|limiter| limiter.limit())
+    }
+
+    /// Set a client-side rate limit for requests with the given method,
+    /// e.g. `eth_callBundle`.
+    ///
+    /// Requests are subject to both the limit of their method and the
+    /// limit of the relay (see [`Relay::set_rate_limit`]). Clones of the
+    /// relay share the same limits.
+    pub fn set_method_rate_limit(&mut self, method: impl Into<String>, limit: RateLimit) {
+        self.method_rate_limiters
+            .insert(method.into(), Arc::new(RateLimiter::new(limit)));
+    }
+
+    /// Get latency and success statistics of the recent requests to the
+    /// relay.
+    ///
+    /// Clones of the relay share the same statistics. See [`RelayStats`].
+    pub fn stats(&self) -> RelayStats {
+        self.stats.stats(&self.url)
+    }
+
+    /// Get the circuit breaker of the relay (if any).
+    pub fn circuit_breaker(&self) -> Option<CircuitBreaker> {
+        self.circuit_breaker.as_ref().map(|state| state.breaker())
+    }
+
+    /// Set a circuit breaker for requests to the relay.
+    ///
+    /// See [`CircuitBreaker`]. Clones of the relay share the same circuit.
+    pub fn set_circuit_breaker(&mut self, breaker: CircuitBreaker) {
+        self.circuit_breaker = Some(Arc::new(CircuitBreakerState::new(breaker)));
+    }
+
+    /// Get the fallback endpoints of the relay (if any).
+    pub fn failover(&self) -> Option<&Failover> {
+        self.failover.as_ref().map(|state| state.failover())
+    }
+
+    /// Set fallback endpoints for requests to the relay.
+    ///
+    /// See [`Failover`]. Clones of the relay share the health of the
+    /// endpoints.
+    pub fn set_failover(&mut self, failover: Failover) {
+        self.failover = Some(Arc::new(FailoverState::new(failover)));
+    }
+
+    /// Get the maximum size of responses from the relay, in bytes.
+    pub fn max_response_size(&self) -> usize {
+        self.max_response_size
+    }
+
+    /// Set the maximum size of responses from the relay, in bytes.
+    ///
+    /// Larger responses fail with [`RelayError::ResponseTooLarge`]. The
+    /// default is 10 MiB.
+    pub fn set_max_response_size(&mut self, bytes: usize) {
+        self.max_response_size = bytes;
+    }
+
+    /// Get the limits on bundles submitted to the relay.
+    pub fn bundle_limits(&self) -> BundleLimits {
+        self.bundle_limits
+    }
+
+    /// Set limits on bundles submitted to the relay.
+    ///
+    /// See [`BundleLimits`].
+    pub fn set_bundle_limits(&mut self, limits: BundleLimits) {
+        self.bundle_limits = limits;
+    }
+
+    /// Get how bundles that were already submitted to the relay are
+    /// handled (if duplicates are detected).
+    pub fn duplicate_policy(&self) -> Option<DuplicatePolicy> {
+        self.submission_cache.as_ref().map(|cache| cache.policy())
+    }
+
+    /// Detect bundles that were already submitted to the relay, and handle
+    /// them according to the given policy.
+    ///
+    /// This applies to bundles sent using
+    /// [`FlashbotsMiddleware::send_bundle`](crate::FlashbotsMiddleware::send_bundle)
+    /// and [`BroadcasterMiddleware::send_bundle`](crate::BroadcasterMiddleware::send_bundle).
+    /// Clones of the relay share the same submissions.
+    pub fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) {
+        self.submission_cache = Some(Arc::new(SubmissionCache::new(policy)));
+    }
+
+    pub(crate) fn submission_cache(&self) -> Option<&SubmissionCache> {
+        self.submission_cache.as_deref()
+    }
+
     /// Sends a request with the provided method to the relay, with the
     /// parameters serialized as JSON.
     pub async fn request<T: Serialize + Send + Sync, R: DeserializeOwned>(
@@ -73,75 +669,358 @@ impl<S: Signer> Relay<S> {
         method: &str,
         params: T,
     ) -> Result<Option<R>, RelayError<S>> {
-        let next_id = self.id.load(Ordering::SeqCst) + 1;
-        self.id.store(next_id, Ordering::SeqCst);
-
-        let payload = Request::new(next_id, method, params);
-
-        let mut req = self.client.post(self.url.as_ref());
-
-        if let Some(signer) = &self.signer {
-            let signature = signer
-                .sign_message(format!(
-                    "0x{:x}",
-                    H256::from(keccak256(
-                        serde_json::to_string(&payload)
-                            .map_err(RelayError::RequestSerdeJson)?
-                            .as_bytes()
-                    ))
-                ))
-                .await
+        let request = self.sign_request(method, params).await?;
+        self.send_signed(&request).await
+    }
+
+    /// Serializes and signs a request with the provided method, without
+    /// sending it.
+    ///
+    /// The signed request can be sent to any number of relays using
+    /// [`Relay::send_signed`], which avoids signing the same payload
+    /// once per relay.
+    pub async fn sign_request<T: Serialize + Send + Sync>(
+        &self,
+        method: &str,
+        params: T,
+    ) -> Result<SignedRequest, RelayError<S>> {
+        let params = serde_json::to_value(params).map_err(RelayError::RequestSerdeJson)?;
+        let (translated_method, params) = self.dialect.translate_request(method, params);
+        self.sign_translated(method, &translated_method, &params, self.signature_scheme)
+            .await
+    }
+
+    /// Signs a request that has already been translated into the dialect
+    /// of the relay, using the given signature scheme.
+    pub(crate) async fn sign_translated(
+        &self,
+        method: &str,
+        translated_method: &str,
+        params: &Value,
+        scheme: SignatureScheme,
+    ) -> Result<SignedRequest, RelayError<S>> {
+        // Ids must be unique to correlate responses with concurrent requests
+        let next_id = self.id.fetch_add(1, Ordering::SeqCst) + 1;
+
+        // Requests without parameters omit the `params` field
+        let body = match params {
+            Value::Null => serde_json::to_string(&Request::new(next_id, translated_method, ())),
+            params => serde_json::to_string(&Request::new(next_id, translated_method, params)),
+        }
+        .map_err(RelayError::RequestSerdeJson)?;
+
+        let signature = match &self.signer {
+            Some(signer) => {
+                let message = match scheme {
+                    SignatureScheme::Flashbots => {
+                        format!("0x{:x}", H256::from(keccak256(body.as_bytes()))).into_bytes()
+                    }
+                    SignatureScheme::RawBody => body.as_bytes().to_vec(),
+                };
+                let signature = if self.blocking_signing {
+                    block_in_place(signer.sign_message(message)).await
+                } else {
+                    signer.sign_message(message).await
+                }
                 .map_err(RelayError::SignerError)?;
 
-            req = req.header(
-                "X-Flashbots-Signature",
-                format!("{:?}:0x{}", signer.address(), signature),
-            );
+                Some(format!("{:?}:0x{}", signer.address(), signature))
+            }
+            None => None,
+        };
+
+        Ok(SignedRequest {
+            id: next_id,
+            method: method.to_string(),
+            content_hash: keccak256(format!("{}:{}", translated_method, params)).into(),
+            body,
+            signature,
+        })
+    }
+
+    /// Sends a request that was previously signed using
+    /// [`Relay::sign_request`] to the relay.
+    pub async fn send_signed<R: DeserializeOwned>(
+        &self,
+        request: &SignedRequest,
+    ) -> Result<Option<R>, RelayError<S>> {
+        self.send_signed_response(request)
+            .await
+            .map(|response| response.result)
+    }
+
+    /// Sends a request that was previously signed using
+    /// [`Relay::sign_request`] to the relay, and returns the result
+    /// together with the JSON-RPC id of the response.
+    pub async fn send_signed_response<R: DeserializeOwned>(
+        &self,
+        request: &SignedRequest,
+    ) -> Result<RelayResponse<R>, RelayError<S>> {
+        if let Some(breaker) = &self.circuit_breaker {
+            if !breaker.allow() {
+                return Err(RelayError::CircuitOpen);
+            }
+        }
+        if let Some(limiter) = self.method_rate_limiters.get(&request.method) {
+            limiter.acquire().await;
+        }
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
         }
 
-        let res = req.json(&payload).send().await?;
-        let status = res.error_for_status_ref();
+        let start = Instant::now();
+        let result = self.send(request).await;
+        self.stats
+            .record(start.elapsed(), result.as_ref().err().map(RelayError::kind));
+        if let Some(breaker) = &self.circuit_breaker {
+            breaker.report(matches!(&result, Err(err) if err.is_retryable()));
+        }
+        result
+    }
 
-        match status {
-            Err(err) => {
-                let text = res.text().await?;
-                let status_code = err.status().unwrap();
-                if status_code.is_client_error() {
-                    // Client error (400-499)
-                    Err(RelayError::ClientError { text })
-                } else {
-                    // Internal server error (500-599)
-                    Err(RelayError::RequestError(err))
+    async fn send<R: DeserializeOwned>(
+        &self,
+        request: &SignedRequest,
+    ) -> Result<RelayResponse<R>, RelayError<S>> {
+        let mut headers = vec![("content-type", "application/json")];
+        if let Some(user_agent) = &self.user_agent {
+            headers.push(("user-agent", user_agent));
+        }
+        headers.extend(
+            self.headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+        if let Some(signature) = &request.signature {
+            headers.push((self.signature_header.as_str(), signature));
+        }
+        if let Some(auth_header) = &self.auth_header {
+            headers.push(("authorization", auth_header));
+        }
+
+        if let Some(logging) = &self.logging {
+            tracing::info!(
+                target: "ethers_flashbots::relay",
+                relay = %self.url,
+                id = request.id,
+                method = %request.method,
+                request = %logging.redact(&request.method, &request.body),
+                "Sending request to relay"
+            );
+        }
+
+        let id = request.id;
+        let res = self
+            .post(&headers, &request.body)
+            .await
+            .map_err(|err| match err.downcast::<ResponseTooLarge>() {
+                Ok(err) => RelayError::ResponseTooLarge {
+                    id,
+                    limit: err.limit,
+                },
+                Err(err) => {
+                    #[cfg(feature = "reqwest-transport")]
+                    let err = match into_reqwest_error(err) {
+                        Ok(err) => return RelayError::RequestError { id, err },
+                        Err(err) => err,
+                    };
+                    RelayError::TransportError { id, err }
                 }
+            })?;
+
+        match res.status {
+            // Client error (400-499)
+            400..=499 => Err(RelayError::ClientError { id, text: res.body }),
+            // Internal server error (500-599)
+            500..=599 => Err(RelayError::ServerError {
+                id,
+                status: res.status,
+                text: res.body,
+            }),
+            _ => {
+                let text = res.body;
+                let res: Response<Value> = match serde_json::from_str(&text) {
+                    Ok(res) => res,
+                    Err(err) => return Err(RelayError::ResponseSerdeJson { id, err, text }),
+                };
+
+                let result = match res
+                    .data
+                    .into_result()
+                    .map_err(|err| RelayError::JsonRpcError { id, err })?
+                {
+                    Some(result) => serde_json::from_value(
+                        self.dialect.translate_response(&request.method, result),
+                    )
+                    .map(Some)
+                    .map_err(|err| RelayError::ResponseSerdeJson { id, err, text })?,
+                    None => None,
+                };
+
+                Ok(RelayResponse { id: res.id, result })
             }
-            Ok(_) => {
-                let text = res.text().await?;
-                let res: Response<R> = serde_json::from_str(&text)
-                    .map_err(|err| RelayError::ResponseSerdeJson { err, text })?;
+        }
+    }
+
+    /// Posts a request body to the relay, failing over to the fallback
+    /// endpoints if the relay cannot be reached.
+    async fn post(
+        &self,
+        headers: &[(&str, &str)],
+        body: &str,
+    ) -> Result<HttpResponse, TransportError> {
+        let post = |url| {
+            self.transport
+                .post_with_limit(url, headers, body.to_string(), self.max_response_size)
+        };
+        let failover = match &self.failover {
+            Some(failover) => failover,
+            None => return post(&self.url).await,
+        };
 
-                Ok(res.data.into_result()?)
+        let mut last_err = None;
+        for (index, url) in failover.endpoints(&self.url) {
+            match post(url).await {
+                Err(err) if is_unreachable(&err) => {
+                    failover.report(index, false);
+                    last_err = Some(err);
+                }
+                res => {
+                    failover.report(index, true);
+                    return res;
+                }
             }
         }
+        Err(last_err.expect("there is at least one endpoint"))
+    }
+}
+
+/// A successful response of a relay.
+///
+/// See [`Relay::send_signed_response`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayResponse<R> {
+    /// The JSON-RPC id of the response.
+    ///
+    /// This should be the same as the id of the request.
+    pub id: u64,
+    /// The result of the request (if any).
+    pub result: Option<R>,
+}
+
+/// A serialized and signed JSON-RPC request.
+///
+/// See [`Relay::sign_request`].
+#[derive(Debug, Clone)]
+pub struct SignedRequest {
+    id: u64,
+    method: String,
+    content_hash: H256,
+    body: String,
+    signature: Option<String>,
+}
+
+impl SignedRequest {
+    /// Get the JSON-RPC id of the request.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Get the serialized body of the request.
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Get the value of the signature header (if the request was signed).
+    pub fn signature(&self) -> Option<&str> {
+        self.signature.as_deref()
+    }
+
+    /// The hash of the method and parameters of the request, which is
+    /// the same for identical requests.
+    pub(crate) fn content_hash(&self) -> H256 {
+        self.content_hash
     }
 }
 
 impl<S: Signer + Clone> Clone for Relay<S> {
     fn clone(&self) -> Self {
+        let id = match self.id_policy {
+            RequestIdPolicy::Shared => self.id.clone(),
+            RequestIdPolicy::RandomOffset => Arc::new(AtomicU64::new(random_id_offset())),
+        };
+
         Self {
-            id: AtomicU64::new(0),
-            client: self.client.clone(),
+            id,
+            id_policy: self.id_policy,
+            transport: self.transport.clone(),
             url: self.url.clone(),
             signer: self.signer.clone(),
+            signature_scheme: self.signature_scheme,
+            signature_header: self.signature_header.clone(),
+            blocking_signing: self.blocking_signing,
+            rate_limiter: self.rate_limiter.clone(),
+            method_rate_limiters: self.method_rate_limiters.clone(),
+            failover: self.failover.clone(),
+            stats: self.stats.clone(),
+            circuit_breaker: self.circuit_breaker.clone(),
+            submission_cache: self.submission_cache.clone(),
+            bundle_limits: self.bundle_limits,
+            max_response_size: self.max_response_size,
+            capabilities: self.capabilities,
+            dialect: self.dialect,
+            auth_header: self.auth_header.clone(),
+            user_agent: self.user_agent.clone(),
+            headers: self.headers.clone(),
+            logging: self.logging,
+            field_profile: self.field_profile.clone(),
+            chain_id: self.chain_id,
         }
     }
 }
 
+/// Runs a future to completion on the current thread, after moving the
+/// other tasks of the worker to another thread.
+///
+/// Outside of the multi-threaded Tokio runtime, the future is awaited
+/// as usual.
+async fn block_in_place<F: Future>(future: F) -> F::Output {
+    #[cfg(feature = "rt-multi-thread")]
+    if let Ok(handle) = Handle::try_current() {
+        if handle.runtime_flavor() == RuntimeFlavor::MultiThread {
+            return tokio::task::block_in_place(|| handle.block_on(future));
+        }
+    }
+
+    tracing::warn!(
+        target: "ethers_flashbots::relay",
+        "Blocking signing requires the multi-threaded Tokio runtime, signing on the async worker"
+    );
+    future.await
+}
+
+/// Returns a random offset for request ids.
+///
+/// Offsets are kept below 2^32, so ids stay within the range of integers
+/// that can be represented exactly by JSON parsers using doubles.
+fn random_id_offset() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    hasher.finish() >> 32
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct SendBundleResponse {
     pub(crate) bundle_hash: Option<BundleHash>,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CancelBundleByHashParams {
+    pub(crate) bundle_hash: BundleHash,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct GetBundleStatsParams {
@@ -154,3 +1033,323 @@ pub(crate) struct GetBundleStatsParams {
 pub(crate) struct GetUserStatsParams {
     pub(crate) block_number: U64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{wallet, MockTransport, StaticTransport};
+    use ethers::{core::types::Signature, signers::LocalWallet};
+    use std::str::FromStr;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sign_request() {
+        let signer = wallet();
+        let relay = Relay::new(
+            Url::parse("http://localhost:8545").unwrap(),
+            Some(signer.clone()),
+        );
+
+        let request = relay.sign_request("eth_sendBundle", [1]).await.unwrap();
+        assert_eq!(request.id(), 1);
+        assert_eq!(
+            request.body(),
+            r#"{"id":1,"jsonrpc":"2.0","method":"eth_sendBundle","params":[1]}"#
+        );
+
+        let (address, signature) = request.signature().unwrap().split_once(':').unwrap();
+        assert_eq!(address, format!("{:?}", signer.address()));
+
+        let signature = Signature::from_str(signature.trim_start_matches("0x")).unwrap();
+        let message = format!("0x{:x}", H256::from(keccak256(request.body().as_bytes())));
+        assert_eq!(signature.recover(message).unwrap(), signer.address());
+
+        let mut relay = relay;
+        relay.set_signature_scheme(SignatureScheme::RawBody);
+        relay.set_blocking_signing(true);
+        let request = relay.sign_request("eth_sendBundle", [1]).await.unwrap();
+        let (_, signature) = request.signature().unwrap().split_once(':').unwrap();
+        let signature = Signature::from_str(signature.trim_start_matches("0x")).unwrap();
+        assert_eq!(signature.recover(request.body()).unwrap(), signer.address());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn blocking_signing() {
+        let signer = wallet();
+        let mut relay = Relay::new(
+            Url::parse("http://localhost:8545").unwrap(),
+            Some(signer.clone()),
+        );
+        relay.set_blocking_signing(true);
+
+        let request = relay.sign_request("eth_sendBundle", [1]).await.unwrap();
+        let (_, signature) = request.signature().unwrap().split_once(':').unwrap();
+        let signature = Signature::from_str(signature.trim_start_matches("0x")).unwrap();
+        let message = format!("0x{:x}", H256::from(keccak256(request.body().as_bytes())));
+        assert_eq!(signature.recover(message).unwrap(), signer.address());
+    }
+
+    #[tokio::test]
+    async fn custom_transport() {
+        let mut relay: Relay<LocalWallet> =
+            Relay::new(Url::parse("http://localhost:8545").unwrap(), None);
+
+        relay.set_transport(StaticTransport(
+            200,
+            r#"{"id":1,"jsonrpc":"2.0","result":"0x1"}"#,
+        ));
+        let request = relay.sign_request("eth_blockNumber", ()).await.unwrap();
+        let response: RelayResponse<U64> = relay.send_signed_response(&request).await.unwrap();
+        assert_eq!(response.id, request.id());
+        assert_eq!(response.result, Some(1.into()));
+
+        relay.set_transport(StaticTransport(
+            200,
+            r#"{"id":2,"jsonrpc":"2.0","error":{"code":-32000,"message":"bundle too large"}}"#,
+        ));
+        let err = relay
+            .request::<_, U64>("eth_blockNumber", ())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RelayError::JsonRpcError { id: 2, .. }));
+        assert_eq!(
+            err.json_rpc_error().map(|err| err.code),
+            Some(JsonRpcError::SERVER_ERROR)
+        );
+        assert_eq!(err.kind(), ErrorKind::Relay);
+
+        relay.set_transport(StaticTransport(503, "unavailable"));
+        let err = relay
+            .request::<_, U64>("eth_blockNumber", ())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RelayError::ServerError { status: 503, .. }));
+        assert_eq!(err.request_id(), Some(3));
+        assert_eq!(err.kind(), ErrorKind::Server);
+        assert!(err.is_retryable());
+
+        relay.set_transport(StaticTransport(400, "invalid bundle"));
+        let err = relay
+            .request::<_, U64>("eth_blockNumber", ())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidRequest);
+        assert!(!err.is_retryable());
+
+        let transport = MockTransport::new(Value::Null);
+        transport.set_unreachable(vec![relay.url().clone()]);
+        relay.set_transport(transport);
+        let err = relay
+            .request::<_, U64>("eth_blockNumber", ())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RelayError::TransportError { id: 5, .. }));
+        assert_eq!(err.request_id(), Some(5));
+        assert_eq!(err.kind(), ErrorKind::Network);
+    }
+
+    #[tokio::test]
+    async fn custom_headers() {
+        let signer = wallet();
+        let mut relay = Relay::new(Url::parse("http://localhost:8545").unwrap(), Some(signer));
+        let transport = MockTransport::new("0x1".into());
+        relay.set_transport(transport.clone());
+        relay.set_user_agent("searcher/1.0");
+        relay.add_header("X-Searcher-Region", "eu");
+        relay.set_signature_header("X-Auction-Signature");
+
+        let relay = relay.clone();
+        assert_eq!(relay.user_agent(), Some("searcher/1.0"));
+        let result: Option<U64> = relay.request("eth_blockNumber", ()).await.unwrap();
+        assert_eq!(result, Some(1.into()));
+
+        let request = &transport.requests()[0];
+        assert_eq!(request.header("content-type"), Some("application/json"));
+        assert_eq!(request.header("user-agent"), Some("searcher/1.0"));
+        assert_eq!(request.header("X-Searcher-Region"), Some("eu"));
+        assert!(request.header("X-Auction-Signature").is_some());
+    }
+
+    #[tokio::test]
+    async fn cloned_request_ids() {
+        let relay: Relay<LocalWallet> =
+            Relay::new(Url::parse("http://localhost:8545").unwrap(), None);
+        let clone = relay.clone();
+        assert_eq!(
+            relay
+                .sign_request("eth_blockNumber", ())
+                .await
+                .unwrap()
+                .id(),
+            1
+        );
+        assert_eq!(
+            clone
+                .sign_request("eth_blockNumber", ())
+                .await
+                .unwrap()
+                .id(),
+            2
+        );
+
+        let mut relay = relay;
+        relay.set_request_id_policy(RequestIdPolicy::RandomOffset);
+        let clone = relay.clone();
+        assert_eq!(clone.request_id_policy(), RequestIdPolicy::RandomOffset);
+        let id = clone
+            .sign_request("eth_blockNumber", ())
+            .await
+            .unwrap()
+            .id();
+        assert!(id <= u32::MAX as u64 + 1);
+        assert_eq!(
+            relay
+                .sign_request("eth_blockNumber", ())
+                .await
+                .unwrap()
+                .id(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn failover() {
+        let primary = Url::parse("http://primary/").unwrap();
+        let fallback = Url::parse("http://fallback-a/").unwrap();
+        let mut relay: Relay<LocalWallet> = Relay::new(primary.clone(), None);
+        let transport = MockTransport::new("0x1".into());
+        transport.set_unreachable(vec![primary.clone()]);
+        relay.set_transport(transport.clone());
+        relay.set_failover(Failover::new(vec![
+            fallback.clone(),
+            Url::parse("http://fallback-b/").unwrap(),
+        ]));
+
+        let res: Option<U64> = relay.request("eth_blockNumber", ()).await.unwrap();
+        assert_eq!(res, Some(1.into()));
+        assert_eq!(transport.take_requests().last().unwrap().url, fallback);
+
+        // Only the fallback is unreachable, so the primary is used
+        transport.set_unreachable(vec![fallback.clone()]);
+        relay.set_failover(Failover::new(vec![fallback]));
+        let res: Option<U64> = relay.request("eth_blockNumber", ()).await.unwrap();
+        assert_eq!(res, Some(1.into()));
+        assert_eq!(transport.take_requests().last().unwrap().url, primary);
+    }
+
+    #[tokio::test]
+    async fn failover_unreachable() {
+        let endpoints: Vec<Url> = [
+            "http://primary/",
+            "http://fallback-a/",
+            "http://fallback-b/",
+        ]
+        .iter()
+        .map(|url| Url::parse(url).unwrap())
+        .collect();
+        let mut relay: Relay<LocalWallet> = Relay::new(endpoints[0].clone(), None);
+        let transport = MockTransport::new("0x1".into());
+        transport.set_unreachable(endpoints.clone());
+        relay.set_transport(transport.clone());
+        relay.set_failover(Failover::new(endpoints[1..].to_vec()));
+        let requests = || {
+            transport
+                .take_requests()
+                .into_iter()
+                .map(|request| request.url.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // All endpoints are unreachable, so the error of the last endpoint
+        // is returned
+        let err = relay
+            .request::<_, U64>("eth_blockNumber", ())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RelayError::TransportError { .. }));
+        assert_eq!(err.to_string(), "http://fallback-b/ is unreachable");
+        assert_eq!(
+            requests(),
+            vec![
+                "http://primary/",
+                "http://fallback-a/",
+                "http://fallback-b/"
+            ]
+        );
+
+        // Endpoints that failed within the cooldown are tried in order
+        transport.set_unreachable(endpoints[..2].to_vec());
+        let res: Option<U64> = relay.request("eth_blockNumber", ()).await.unwrap();
+        assert_eq!(res, Some(1.into()));
+        assert_eq!(
+            requests(),
+            vec![
+                "http://primary/",
+                "http://fallback-a/",
+                "http://fallback-b/"
+            ]
+        );
+
+        // The healthy endpoint is now tried first
+        let res: Option<U64> = relay.request("eth_blockNumber", ()).await.unwrap();
+        assert_eq!(res, Some(1.into()));
+        assert_eq!(requests(), vec!["http://fallback-b/"]);
+
+        transport.set_unreachable(endpoints);
+        relay
+            .request::<_, U64>("eth_blockNumber", ())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            requests(),
+            vec![
+                "http://fallback-b/",
+                "http://primary/",
+                "http://fallback-a/"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn circuit_breaker() {
+        let mut relay: Relay<LocalWallet> =
+            Relay::new(Url::parse("http://localhost:8545").unwrap(), None);
+        relay.set_transport(StaticTransport(503, "unavailable"));
+        relay.set_circuit_breaker(CircuitBreaker::new().set_window(2));
+
+        for _ in 0..2 {
+            let err = relay
+                .request::<_, Value>("eth_blockNumber", ())
+                .await
+                .unwrap_err();
+            assert!(matches!(err, RelayError::ServerError { .. }));
+        }
+        let err = relay
+            .request::<_, Value>("eth_blockNumber", ())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RelayError::CircuitOpen));
+        assert_eq!(err.kind(), ErrorKind::Unavailable);
+        assert_eq!(relay.stats().requests, 2);
+    }
+
+    #[tokio::test]
+    async fn max_response_size() {
+        let mut relay: Relay<LocalWallet> =
+            Relay::new(Url::parse("http://localhost:8545").unwrap(), None);
+        relay.set_transport(StaticTransport(
+            200,
+            r#"{"id":1,"jsonrpc":"2.0","result":"0x1"}"#,
+        ));
+        relay.set_max_response_size(16);
+
+        let err = relay
+            .request::<_, Value>("eth_blockNumber", ())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RelayError::ResponseTooLarge { id: 1, limit: 16 }
+        ));
+        assert_eq!(err.kind(), ErrorKind::InvalidResponse);
+    }
+}
@@ -0,0 +1,363 @@
+use crate::{
+    transport::{default_transport, HttpTransport, TransportError},
+    utils::{deserialize_u256, deserialize_u64},
+};
+use ethers::core::types::{Address, Bytes, H256, U256, U64};
+use serde::Deserialize;
+use std::sync::Arc;
+use thiserror::Error;
+use url::Url;
+
+/// A client for the data API of a MEV-Boost relay.
+///
+/// The data API is public and unauthenticated, and exposes information
+/// about the validators registered with the relay and the payloads the
+/// relay has delivered.
+///
+/// See the [relay API specification][relay_spec] for more information.
+///
+/// [relay_spec]: https://flashbots.github.io/relay-specs/
+#[derive(Debug, Clone)]
+pub struct RelayDataClient {
+    transport: Arc<dyn HttpTransport>,
+    url: Url,
+}
+
+/// Errors for relay data API requests.
+#[derive(Error, Debug)]
+pub enum RelayDataError {
+    /// The request failed in the transport.
+    #[error(transparent)]
+    TransportError(TransportError),
+    /// The relay responded with an error status.
+    #[error("Relay responded with status {status}: {text}")]
+    StatusError {
+        /// The status code of the response.
+        status: u16,
+        text: String,
+    },
+    /// The request URL could not be constructed.
+    #[error(transparent)]
+    UrlError(#[from] url::ParseError),
+    /// The response could not be deserialized.
+    #[error("Deserialization error: {err}. Response: {text}")]
+    ResponseSerdeJson {
+        err: serde_json::Error,
+        text: String,
+    },
+}
+
+/// A validator registered with a MEV-Boost relay for an upcoming slot.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ValidatorRegistration {
+    /// The slot the validator is proposing in.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub slot: U64,
+    /// The index of the validator.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub validator_index: U64,
+    /// The signed registration of the validator.
+    pub entry: SignedValidatorRegistration,
+}
+
+/// A signed validator registration.
+#[derive(Deserialize, Debug, Clone)]
+pub struct SignedValidatorRegistration {
+    /// The registration message.
+    pub message: ValidatorRegistrationMessage,
+    /// The validator's signature over the message.
+    pub signature: Bytes,
+}
+
+/// The preferences a validator registered with a MEV-Boost relay.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ValidatorRegistrationMessage {
+    /// The address block rewards are paid to.
+    pub fee_recipient: Address,
+    /// The preferred gas limit of the validator.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub gas_limit: U64,
+    /// The time of the registration.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub timestamp: U64,
+    /// The public key of the validator.
+    pub pubkey: Bytes,
+}
+
+/// A payload delivered to a proposer by a MEV-Boost relay.
+#[derive(Deserialize, Debug, Clone)]
+pub struct DeliveredPayload {
+    /// The slot of the payload.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub slot: U64,
+    /// The hash of the parent block.
+    pub parent_hash: H256,
+    /// The hash of the block.
+    pub block_hash: H256,
+    /// The public key of the builder that built the block.
+    pub builder_pubkey: Bytes,
+    /// The public key of the proposer.
+    pub proposer_pubkey: Bytes,
+    /// The address the proposer was paid to.
+    pub proposer_fee_recipient: Address,
+    /// The gas limit of the block.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub gas_limit: U64,
+    /// The gas used by the block.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub gas_used: U64,
+    /// The value paid to the proposer, in wei.
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub value: U256,
+    /// The number of the block.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub block_number: U64,
+    /// The number of transactions in the block.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub num_tx: U64,
+}
+
+impl RelayDataClient {
+    /// Initializes a new relay data API client.
+    ///
+    /// The URL should be the base URL of the relay, e.g.
+    /// `https://boost-relay.flashbots.net`.
+    ///
+    /// Requests are sent using a new [`reqwest::Client`], unless the
+    /// `reqwest-transport` feature is disabled, in which case a transport
+    /// has to be set using [`RelayDataClient::set_transport`].
+    pub fn new(url: impl Into<Url>) -> Self {
+        Self::with_transport(url, default_transport())
+    }
+
+    /// Initializes a new relay data API client that sends requests using
+    /// the given HTTP transport.
+    ///
+    /// The transport can be shared with relays. See [`HttpTransport`].
+    pub fn with_transport(url: impl Into<Url>, transport: Arc<dyn HttpTransport>) -> Self {
+        Self {
+            transport,
+            url: url.into(),
+        }
+    }
+
+    /// Set the HTTP transport used to send requests to the relay.
+    ///
+    /// See [`HttpTransport`].
+    pub fn set_transport(&mut self, transport: impl HttpTransport + 'static) {
+        self.transport = Arc::new(transport);
+    }
+
+    /// Get the base URL of the relay.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Get the validators registered with the relay that are proposing
+    /// in the current and the next epoch.
+    pub async fn registered_validators(
+        &self,
+    ) -> Result<Vec<ValidatorRegistration>, RelayDataError> {
+        self.get("relay/v1/builder/validators").await
+    }
+
+    /// Check whether the proposer of the given slot is registered with
+    /// the relay.
+    ///
+    /// Note that the relay only knows about proposers in the current and
+    /// the next epoch, so slots further in the future are reported as
+    /// unregistered.
+    pub async fn is_registered(&self, slot: U64) -> Result<bool, RelayDataError> {
+        Ok(self
+            .registered_validators()
+            .await?
+            .iter()
+            .any(|registration| registration.slot == slot))
+    }
+
+    /// Get the payload the relay delivered for the given block height (if
+    /// any).
+    ///
+    /// Returns `None` if no block at the height was built through the
+    /// relay. If the relay delivered payloads for several blocks at the
+    /// height, e.g. because of a reorg, the first one is returned; use
+    /// [`RelayDataClient::delivered_payload_by_hash`] to get the payload of
+    /// a specific block.
+    pub async fn delivered_payload(
+        &self,
+        block: U64,
+    ) -> Result<Option<DeliveredPayload>, RelayDataError> {
+        let payloads: Vec<DeliveredPayload> = self
+            .get(&format!(
+                "relay/v1/data/bidtraces/proposer_payload_delivered?block_number={}",
+                block
+            ))
+            .await?;
+        Ok(payloads
+            .into_iter()
+            .find(|payload| payload.block_number == block))
+    }
+
+    /// Get the payload the relay delivered for the block with the given
+    /// hash (if any).
+    ///
+    /// Returns `None` if the block was not built through the relay.
+    pub async fn delivered_payload_by_hash(
+        &self,
+        block_hash: H256,
+    ) -> Result<Option<DeliveredPayload>, RelayDataError> {
+        let payloads: Vec<DeliveredPayload> = self
+            .get(&format!(
+                "relay/v1/data/bidtraces/proposer_payload_delivered?block_hash={:?}",
+                block_hash
+            ))
+            .await?;
+        Ok(payloads
+            .into_iter()
+            .find(|payload| payload.block_hash == block_hash))
+    }
+
+    async fn get<R: serde::de::DeserializeOwned>(&self, path: &str) -> Result<R, RelayDataError> {
+        let res = self
+            .transport
+            .get(&self.url.join(path)?, &[("accept", "application/json")])
+            .await
+            .map_err(RelayDataError::TransportError)?;
+        if !(200..300).contains(&res.status) {
+            return Err(RelayDataError::StatusError {
+                status: res.status,
+                text: res.body,
+            });
+        }
+
+        let text = res.body;
+        serde_json::from_str(&text).map_err(|err| RelayDataError::ResponseSerdeJson { err, text })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{MockTransport, StaticTransport};
+
+    #[test]
+    fn validator_registration_deserialize() {
+        let registrations: Vec<ValidatorRegistration> = serde_json::from_str(
+            r#"[{
+                "slot": "7442370",
+                "validator_index": "210554",
+                "entry": {
+                    "message": {
+                        "fee_recipient": "0x388c818ca8b9251b393131c08a736a67ccb19297",
+                        "gas_limit": "30000000",
+                        "timestamp": "1689412391",
+                        "pubkey": "0x8b1d678b5df0ab4c7d7e7b0a5e0b3dc1e4bc87305db1c0a22c5ff2455e5cdcd4f3a044e0d6ffa2a699ac2e3ea8df9ec8"
+                    },
+                    "signature": "0x01"
+                }
+            }]"#,
+        )
+        .unwrap();
+
+        assert_eq!(registrations.len(), 1);
+        assert_eq!(registrations[0].slot, U64::from(7442370));
+        assert_eq!(registrations[0].validator_index, U64::from(210554));
+        assert_eq!(
+            registrations[0].entry.message.gas_limit,
+            U64::from(30000000)
+        );
+    }
+
+    #[test]
+    fn delivered_payload_deserialize() {
+        let payloads: Vec<DeliveredPayload> = serde_json::from_str(
+            r#"[{
+                "slot": "7442370",
+                "parent_hash": "0x0000000000000000000000000000000000000000000000000000000000000001",
+                "block_hash": "0x0000000000000000000000000000000000000000000000000000000000000002",
+                "builder_pubkey": "0xa1dead",
+                "proposer_pubkey": "0xb2beef",
+                "proposer_fee_recipient": "0x388c818ca8b9251b393131c08a736a67ccb19297",
+                "gas_limit": "30000000",
+                "gas_used": "12345678",
+                "value": "52275751055170308",
+                "block_number": "17707895",
+                "num_tx": "150"
+            }]"#,
+        )
+        .unwrap();
+
+        assert_eq!(payloads.len(), 1);
+        assert_eq!(payloads[0].block_number, U64::from(17707895));
+        assert_eq!(payloads[0].value, U256::from(52275751055170308u64));
+        assert_eq!(
+            payloads[0].builder_pubkey,
+            Bytes::from(vec![0xa1, 0xde, 0xad])
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_use_transport() {
+        let transport = MockTransport::new(serde_json::json!([]));
+        let client = RelayDataClient::with_transport(
+            Url::parse("https://relay.example").unwrap(),
+            transport.clone(),
+        );
+
+        assert!(client.registered_validators().await.unwrap().is_empty());
+        let requests = transport.take_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].url.as_str(),
+            "https://relay.example/relay/v1/builder/validators"
+        );
+        assert_eq!(requests[0].header("accept"), Some("application/json"));
+
+        let mut client = client;
+        client.set_transport(StaticTransport(404, "not found"));
+        assert!(matches!(
+            client.registered_validators().await,
+            Err(RelayDataError::StatusError { status: 404, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn delivered_payload_by_hash() {
+        let payload = |block_hash: u64| {
+            serde_json::json!({
+                "slot": "1",
+                "parent_hash": H256::from_low_u64_be(9),
+                "block_hash": H256::from_low_u64_be(block_hash),
+                "builder_pubkey": "0xa1",
+                "proposer_pubkey": "0xb2",
+                "proposer_fee_recipient": "0x388c818ca8b9251b393131c08a736a67ccb19297",
+                "gas_limit": "30000000",
+                "gas_used": "21000",
+                "value": "1",
+                "block_number": "10",
+                "num_tx": "1"
+            })
+        };
+        let transport = MockTransport::new(serde_json::json!([payload(99), payload(10)]));
+        let client = RelayDataClient::with_transport(
+            Url::parse("https://relay.example").unwrap(),
+            transport.clone(),
+        );
+
+        let delivered = client
+            .delivered_payload_by_hash(H256::from_low_u64_be(10))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(delivered.block_hash, H256::from_low_u64_be(10));
+        assert_eq!(
+            transport.requests()[0].url.query(),
+            Some(format!("block_hash={:?}", H256::from_low_u64_be(10)).as_str())
+        );
+        assert!(client
+            .delivered_payload_by_hash(H256::from_low_u64_be(11))
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::error::ErrorKind;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::Duration,
+};
+use url::Url;
+
+/// The number of recent requests relay statistics are computed over.
+const WINDOW: usize = 1000;
+
+/// Latency and success statistics of the recent requests to a relay.
+///
+/// Statistics are computed over the last 1000 requests to the relay,
+/// excluding time spent waiting for the rate limit of the relay.
+///
+/// See [`Relay::stats`](crate::Relay::stats).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayStats {
+    /// The URL of the relay.
+    pub url: Url,
+    /// The number of requests.
+    pub requests: u64,
+    /// The number of requests that succeeded.
+    pub successes: u64,
+    /// The number of requests that failed, by the kind of error.
+    pub errors: HashMap<ErrorKind, u64>,
+    /// The latencies of the requests, sorted from fastest to slowest.
+    latencies: Vec<Duration>,
+}
+
+impl RelayStats {
+    /// Get the fraction of requests that succeeded, between 0 and 1.
+    ///
+    /// Returns `None` if there were no requests.
+    pub fn acceptance_rate(&self) -> Option<f64> {
+        if self.requests == 0 {
+            return None;
+        }
+        Some(self.successes as f64 / self.requests as f64)
+    }
+
+    /// Get the given percentile (0-100) of request latencies.
+    ///
+    /// Returns `None` if there were no requests.
+    pub fn latency_percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * (self.latencies.len() - 1) as f64)
+            .round() as usize;
+        self.latencies.get(rank).copied()
+    }
+
+    /// Get the number of requests that failed with the given kind of
+    /// error.
+    pub fn error_count(&self, kind: ErrorKind) -> u64 {
+        self.errors.get(&kind).copied().unwrap_or_default()
+    }
+}
+
+/// Records the outcomes of requests to a relay.
+#[derive(Debug, Default)]
+pub(crate) struct RelayStatsRecorder {
+    samples: Mutex<VecDeque<(Duration, Option<ErrorKind>)>>,
+}
+
+impl RelayStatsRecorder {
+    /// Record a request with the given latency, and the kind of error if
+    /// it failed.
+    pub(crate) fn record(&self, latency: Duration, error: Option<ErrorKind>) {
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() == WINDOW {
+            samples.pop_front();
+        }
+        samples.push_back((latency, error));
+    }
+
+    pub(crate) fn stats(&self, url: &Url) -> RelayStats {
+        let samples = self.samples.lock().unwrap();
+        let mut errors = HashMap::new();
+        for kind in samples.iter().filter_map(|(_, error)| *error) {
+            *errors.entry(kind).or_default() += 1;
+        }
+        let mut latencies: Vec<_> = samples.iter().map(|(latency, _)| *latency).collect();
+        latencies.sort();
+
+        RelayStats {
+            url: url.clone(),
+            requests: samples.len() as u64,
+            successes: samples.iter().filter(|(_, error)| error.is_none()).count() as u64,
+            errors,
+            latencies,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relay_stats() {
+        let url = Url::parse("http://localhost:8545").unwrap();
+        let recorder = RelayStatsRecorder::default();
+        let stats = recorder.stats(&url);
+        assert_eq!(stats.acceptance_rate(), None);
+        assert_eq!(stats.latency_percentile(50.0), None);
+
+        for ms in 1..=8 {
+            recorder.record(Duration::from_millis(ms), None);
+        }
+        recorder.record(Duration::from_millis(100), Some(ErrorKind::Network));
+        recorder.record(Duration::from_millis(9), Some(ErrorKind::Relay));
+
+        let stats = recorder.stats(&url);
+        assert_eq!(stats.requests, 10);
+        assert_eq!(stats.acceptance_rate(), Some(0.8));
+        assert_eq!(stats.error_count(ErrorKind::Network), 1);
+        assert_eq!(stats.error_count(ErrorKind::Server), 0);
+        assert_eq!(
+            stats.latency_percentile(0.0),
+            Some(Duration::from_millis(1))
+        );
+        assert_eq!(
+            stats.latency_percentile(50.0),
+            Some(Duration::from_millis(6))
+        );
+        assert_eq!(
+            stats.latency_percentile(100.0),
+            Some(Duration::from_millis(100))
+        );
+    }
+}
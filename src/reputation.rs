@@ -0,0 +1,219 @@
+use crate::{
+    middleware::{FlashbotsMiddleware, FlashbotsMiddlewareError},
+    user::UserStats,
+};
+use ethers::{core::types::U256, providers::Middleware, signers::Signer};
+use std::time::Duration;
+
+/// The default interval between checks of a [`ReputationWatcher`].
+const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A change in the reputation of a searcher identity.
+///
+/// See [`ReputationWatcher`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReputationAlert {
+    /// The searcher identity became high priority.
+    HighPriorityGained,
+    /// The searcher identity is no longer high priority.
+    HighPriorityLost,
+    /// The validator payments of the last 7 days fell below the threshold.
+    PaymentsBelowThreshold {
+        /// The validator payments of the last 7 days.
+        payments: U256,
+        /// The threshold.
+        threshold: U256,
+    },
+    /// The validator payments of the last 7 days are no longer below the
+    /// threshold.
+    PaymentsRecovered {
+        /// The validator payments of the last 7 days.
+        payments: U256,
+        /// The threshold.
+        threshold: U256,
+    },
+}
+
+/// A watcher that alerts when the reputation of your searcher identity
+/// changes.
+///
+/// Alerts are raised when the identity gains or loses high priority, or
+/// when the validator payments of the last 7 days fall below (or recover
+/// above) a threshold set using [`ReputationWatcher::set_payments_threshold`].
+///
+/// The first check only establishes whether the identity is high
+/// priority, without raising an alert. Payments below the threshold are
+/// reported on the first check.
+///
+/// Alerts can be forwarded to a channel from the callback of
+/// [`ReputationWatcher::run`]:
+///
+/// ```no_run
+/// # use ethers::{providers::{Provider, Http}, signers::LocalWallet, utils::parse_ether};
+/// # use ethers_flashbots::{FlashbotsMiddleware, ReputationWatcher};
+/// # async fn watch(client: FlashbotsMiddleware<Provider<Http>, LocalWallet>) -> Result<(), Box<dyn std::error::Error>> {
+/// let (sender, alerts) = tokio::sync::mpsc::unbounded_channel();
+/// let mut watcher = ReputationWatcher::new().set_payments_threshold(parse_ether("0.1")?);
+/// watcher
+///     .run(&client, |alert| {
+///         let _ = sender.send(alert);
+///     })
+///     .await?;
+/// # drop(alerts);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReputationWatcher {
+    payments_threshold: Option<U256>,
+    interval: Duration,
+    high_priority: Option<bool>,
+    below_threshold: bool,
+}
+
+impl Default for ReputationWatcher {
+    fn default() -> Self {
+        Self {
+            payments_threshold: None,
+            interval: DEFAULT_CHECK_INTERVAL,
+            high_priority: None,
+            below_threshold: false,
+        }
+    }
+}
+
+impl ReputationWatcher {
+    /// Creates a watcher that checks the reputation every minute, without
+    /// a payments threshold.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get the threshold for validator payments of the last 7 days (if
+    /// any).
+    pub fn payments_threshold(&self) -> Option<U256> {
+        self.payments_threshold
+    }
+
+    /// Set the threshold for validator payments of the last 7 days, in
+    /// wei.
+    pub fn set_payments_threshold(mut self, threshold: U256) -> Self {
+        self.payments_threshold = Some(threshold);
+        self
+    }
+
+    /// Get the interval between checks in [`ReputationWatcher::run`].
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Set the interval between checks in [`ReputationWatcher::run`].
+    pub fn set_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Check the given stats against the previously checked stats, and
+    /// return the resulting alerts.
+    pub fn check(&mut self, stats: &UserStats) -> Vec<ReputationAlert> {
+        let mut alerts = Vec::new();
+
+        match self.high_priority.replace(stats.is_high_priority) {
+            Some(false) if stats.is_high_priority => {
+                alerts.push(ReputationAlert::HighPriorityGained)
+            }
+            Some(true) if !stats.is_high_priority => alerts.push(ReputationAlert::HighPriorityLost),
+            _ => {}
+        }
+
+        if let Some(threshold) = self.payments_threshold {
+            let payments = stats.last_7d_validator_payments;
+            let below_threshold = payments < threshold;
+            match (self.below_threshold, below_threshold) {
+                (false, true) => alerts.push(ReputationAlert::PaymentsBelowThreshold {
+                    payments,
+                    threshold,
+                }),
+                (true, false) => alerts.push(ReputationAlert::PaymentsRecovered {
+                    payments,
+                    threshold,
+                }),
+                _ => {}
+            }
+            self.below_threshold = below_threshold;
+        }
+
+        alerts
+    }
+
+    /// Check the reputation of the searcher identity of the middleware at
+    /// the check interval, calling `on_alert` for each alert.
+    ///
+    /// This only returns if fetching the user stats fails.
+    pub async fn run<M: Middleware, S: Signer>(
+        &mut self,
+        middleware: &FlashbotsMiddleware<M, S>,
+        mut on_alert: impl FnMut(ReputationAlert),
+    ) -> Result<(), FlashbotsMiddlewareError<M, S>> {
+        loop {
+            let stats = middleware.get_user_stats().await?;
+            self.check(&stats).into_iter().for_each(&mut on_alert);
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(is_high_priority: bool, payments: u64) -> UserStats {
+        UserStats {
+            is_high_priority,
+            all_time_validator_payments: U256::zero(),
+            all_time_gas_simulated: U256::zero(),
+            last_7d_validator_payments: payments.into(),
+            last_7d_gas_simulated: U256::zero(),
+            last_1d_validator_payments: U256::zero(),
+            last_1d_gas_simulated: U256::zero(),
+        }
+    }
+
+    #[test]
+    fn reputation_alerts() {
+        let mut watcher = ReputationWatcher::new().set_payments_threshold(100.into());
+
+        assert_eq!(watcher.check(&stats(true, 200)), vec![]);
+        assert_eq!(
+            watcher.check(&stats(false, 50)),
+            vec![
+                ReputationAlert::HighPriorityLost,
+                ReputationAlert::PaymentsBelowThreshold {
+                    payments: 50.into(),
+                    threshold: 100.into()
+                }
+            ]
+        );
+        assert_eq!(watcher.check(&stats(false, 40)), vec![]);
+        assert_eq!(
+            watcher.check(&stats(true, 100)),
+            vec![
+                ReputationAlert::HighPriorityGained,
+                ReputationAlert::PaymentsRecovered {
+                    payments: 100.into(),
+                    threshold: 100.into()
+                }
+            ]
+        );
+
+        let mut watcher = ReputationWatcher::new().set_payments_threshold(100.into());
+        assert_eq!(
+            watcher.check(&stats(false, 0)),
+            vec![ReputationAlert::PaymentsBelowThreshold {
+                payments: 0.into(),
+                threshold: 100.into()
+            }]
+        );
+    }
+}
@@ -0,0 +1,871 @@
+use crate::{
+    bundle::{BundleHash, BundleRequest},
+    middleware::{BroadcasterMiddleware, FlashbotsMiddleware, FlashbotsMiddlewareError},
+    pending_bundle::{PendingBundle, PendingBundleError},
+    relay_data::{DeliveredPayload, RelayDataClient},
+    scheduler::{SlotScheduler, SLOTS_PER_EPOCH},
+};
+use ethers::{
+    core::{
+        types::{Block, BlockId, BlockNumber, Bytes, H256, U64},
+        utils::keccak256,
+    },
+    providers::Middleware,
+    signers::Signer,
+};
+use futures_core::stream::Stream;
+use futures_util::stream::StreamExt;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Mutex,
+    time::{Duration, UNIX_EPOCH},
+};
+use thiserror::Error;
+use url::Url;
+use uuid::Uuid;
+
+/// The interval at which the resubmitter polls for new blocks.
+const DEFAULT_BLOCK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A resubmission manager that sends a bundle to consecutive blocks
+/// until it is included.
+///
+/// The bundle is first sent targeting its own target block (or the next
+/// block if it has none). If it was not included, it is re-targeted to
+/// the following block and sent again, up to a maximum number of blocks.
+///
+/// Optionally, blocks whose proposer is not registered with any of a set
+/// of MEV-Boost relays can be skipped (see
+/// [`BundleResubmitter::skip_unregistered_proposers`]), since such
+/// proposers build their own blocks and will never include a bundle.
+///
+/// Bundles can be sent to a single relay using a [`FlashbotsMiddleware`]
+/// (see [`BundleResubmitter::new`]), or to multiple builders using a
+/// [`BroadcasterMiddleware`] (see [`BundleResubmitter::broadcast`]).
+#[derive(Debug)]
+pub struct BundleResubmitter<'a, M, S> {
+    middleware: Sender<'a, M, S>,
+    max_blocks: u64,
+    poll_interval: Duration,
+    lookahead: u64,
+    proposer_check: Option<ProposerCheck>,
+    delivery_check: Option<DeliveryCheck>,
+    block_stream: bool,
+}
+
+/// A copy of a bundle sent for a block, with its replacement uuid.
+type Submission<'a, M> = (
+    U64,
+    Option<Uuid>,
+    PendingBundle<'a, <M as Middleware>::Provider>,
+);
+
+/// The middleware bundles are sent through.
+#[derive(Debug)]
+enum Sender<'a, M, S> {
+    Relay(&'a FlashbotsMiddleware<M, S>),
+    Broadcast(&'a BroadcasterMiddleware<M, S>),
+}
+
+#[derive(Debug)]
+struct ProposerCheck {
+    relays: Vec<RelayDataClient>,
+    scheduler: SlotScheduler,
+    /// The slots with a registered proposer for each relay, along with
+    /// the epoch they were fetched in.
+    registrations: Mutex<HashMap<usize, (u64, HashSet<U64>)>>,
+}
+
+impl ProposerCheck {
+    /// Check whether the proposer of the given slot is registered with the
+    /// relay at the given index, fetching the registrations at most once
+    /// per epoch.
+    ///
+    /// Returns `None` if the relay did not answer.
+    async fn is_registered(&self, index: usize, epoch: u64, slot: U64) -> Option<bool> {
+        let cached = self
+            .registrations
+            .lock()
+            .unwrap()
+            .get(&index)
+            .filter(|(fetched, _)| *fetched == epoch)
+            .map(|(_, slots)| slots.contains(&slot));
+        if cached.is_some() {
+            return cached;
+        }
+
+        let slots: HashSet<U64> = self.relays[index]
+            .registered_validators()
+            .await
+            .ok()?
+            .into_iter()
+            .map(|registration| registration.slot)
+            .collect();
+        let registered = slots.contains(&slot);
+        self.registrations
+            .lock()
+            .unwrap()
+            .insert(index, (epoch, slots));
+        Some(registered)
+    }
+}
+
+#[derive(Debug)]
+struct DeliveryCheck {
+    relays: Vec<RelayDataClient>,
+    builder_pubkeys: Vec<Bytes>,
+}
+
+/// The MEV-Boost relay and payload through which the block including a
+/// bundle was delivered.
+#[derive(Debug, Clone)]
+pub struct PayloadDelivery {
+    /// The URL of the relay that delivered the payload.
+    pub relay: Url,
+    /// The delivered payload.
+    pub payload: DeliveredPayload,
+    /// Whether the payload was built by one of the builders the bundle
+    /// was sent to.
+    ///
+    /// If not, the transactions of the bundle were likely included through
+    /// the public mempool, or through another builder.
+    pub sent_to_builder: bool,
+}
+
+/// The outcome of resubmitting a bundle.
+#[derive(Debug, Clone, Default)]
+pub struct ResubmissionOutcome {
+    /// The block the bundle was included in, if it was included.
+    pub included_block: Option<U64>,
+    /// The bundle hash reported by the relay for the submission
+    /// that was included (if any).
+    pub bundle_hash: Option<BundleHash>,
+    /// The blocks the bundle was submitted for.
+    pub submitted_blocks: Vec<U64>,
+    /// The blocks that were skipped because their proposer
+    /// does not run MEV-Boost.
+    pub skipped_blocks: Vec<U64>,
+    /// The blocks for which copies of the bundle were cancelled after it
+    /// was included.
+    pub cancelled_blocks: Vec<U64>,
+    /// The payload through which the block including the bundle was
+    /// delivered, if it was found.
+    ///
+    /// See [`BundleResubmitter::confirm_delivery`].
+    pub delivery: Option<PayloadDelivery>,
+}
+
+/// Errors for bundle resubmissions.
+#[derive(Error, Debug)]
+pub enum ResubmissionError<M: Middleware, S: Signer> {
+    /// An error occurred while sending the bundle.
+    #[error(transparent)]
+    FlashbotsMiddlewareError(#[from] FlashbotsMiddlewareError<M, S>),
+    /// An error occurred while waiting for the bundle to be included.
+    #[error(transparent)]
+    PendingBundleError(#[from] PendingBundleError),
+    /// The latest block is not available from the provider.
+    #[error("The latest block is not available")]
+    LatestBlockUnavailable,
+}
+
+impl<'a, M: Middleware, S: Signer> Sender<'a, M, S> {
+    /// The middleware used to query the chain.
+    fn chain(&self) -> &'a M {
+        match *self {
+            Sender::Relay(middleware) => middleware.inner(),
+            Sender::Broadcast(middleware) => middleware.inner(),
+        }
+    }
+
+    /// Send the bundle, returning a pending bundle for it.
+    ///
+    /// When broadcasting, the bundle is sent if at least one builder
+    /// accepted it, and the error of the first builder is returned
+    /// otherwise.
+    async fn send_bundle(
+        &self,
+        bundle: &BundleRequest,
+    ) -> Result<PendingBundle<'a, M::Provider>, FlashbotsMiddlewareError<M, S>> {
+        match self {
+            Sender::Relay(middleware) => middleware.send_bundle(bundle).await,
+            Sender::Broadcast(middleware) => {
+                let mut first_err = None;
+                for res in middleware.send_bundle(bundle).await? {
+                    match res {
+                        Ok(pending_bundle) => return Ok(pending_bundle),
+                        Err(err) => {
+                            first_err.get_or_insert(err);
+                        }
+                    }
+                }
+                Err(first_err.unwrap_or(FlashbotsMiddlewareError::UnsupportedBundle))
+            }
+        }
+    }
+
+    /// Send a cancellation, succeeding if at least one builder accepted it.
+    async fn send_cancellation(&self, cancellation: &BundleRequest) -> bool {
+        match self {
+            Sender::Relay(middleware) => middleware.send_cancellation(cancellation).await.is_ok(),
+            Sender::Broadcast(middleware) => middleware
+                .send_cancellation(cancellation)
+                .await
+                .is_ok_and(|results| results.iter().any(Result::is_ok)),
+        }
+    }
+}
+
+impl<'a, M: Middleware, S: Signer> BundleResubmitter<'a, M, S> {
+    /// Creates a new resubmitter that sends bundles through the given
+    /// middleware, targeting at most `max_blocks` blocks.
+    pub fn new(middleware: &'a FlashbotsMiddleware<M, S>, max_blocks: u64) -> Self {
+        Self::with_sender(Sender::Relay(middleware), max_blocks)
+    }
+
+    /// Creates a new resubmitter that broadcasts bundles to the builders of
+    /// the given middleware, targeting at most `max_blocks` blocks.
+    ///
+    /// A submission succeeds if at least one of the builders accepted the
+    /// bundle.
+    pub fn broadcast(middleware: &'a BroadcasterMiddleware<M, S>, max_blocks: u64) -> Self {
+        Self::with_sender(Sender::Broadcast(middleware), max_blocks)
+    }
+
+    fn with_sender(middleware: Sender<'a, M, S>, max_blocks: u64) -> Self {
+        Self {
+            middleware,
+            max_blocks,
+            poll_interval: DEFAULT_BLOCK_POLL_INTERVAL,
+            lookahead: 0,
+            proposer_check: None,
+            delivery_check: None,
+            block_stream: false,
+        }
+    }
+
+    /// Get the maximum number of blocks the bundle is submitted for.
+    pub fn max_blocks(&self) -> u64 {
+        self.max_blocks
+    }
+
+    /// Set the interval at which new blocks are polled for.
+    ///
+    /// This is also the interval at which the pending bundles check
+    /// whether their target block has been mined.
+    pub fn set_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Skip target blocks whose proposer is not registered with any of the
+    /// given MEV-Boost relays.
+    ///
+    /// The scheduler is used to map block numbers to slots. The
+    /// registrations are fetched from each relay at most once per epoch.
+    ///
+    /// Blocks are not skipped if the registrations can not be fetched from
+    /// any of the relays, or if their slot is beyond the next epoch, since
+    /// relays only know the proposers of the current and the next epoch.
+    pub fn skip_unregistered_proposers(
+        mut self,
+        relays: Vec<RelayDataClient>,
+        scheduler: SlotScheduler,
+    ) -> Self {
+        self.proposer_check = Some(ProposerCheck {
+            relays,
+            scheduler,
+            registrations: Mutex::new(HashMap::new()),
+        });
+        self
+    }
+
+    /// After the bundle is included, look up the payload delivered for the
+    /// block on the given MEV-Boost relays, and record whether it was built
+    /// by one of the given builders.
+    ///
+    /// The builders should be the public keys of the builders the bundle
+    /// was sent to. Builders sign their payloads with BLS keys that are not
+    /// part of the endpoints in [`builders`](crate::builders), and that may
+    /// change over time, so the keys have to be looked up on the relays
+    /// (e.g. on their data API) and passed here.
+    ///
+    /// The relays are queried in order, and the first payload for the block
+    /// that included the bundle is recorded in
+    /// [`ResubmissionOutcome::delivery`]. Payloads for other blocks at the
+    /// same height, e.g. blocks that were reorged out, are ignored.
+    pub fn confirm_delivery(
+        mut self,
+        relays: Vec<RelayDataClient>,
+        builder_pubkeys: Vec<Bytes>,
+    ) -> Self {
+        self.delivery_check = Some(DeliveryCheck {
+            relays,
+            builder_pubkeys,
+        });
+        self
+    }
+
+    /// Get the number of blocks after the current target block the bundle
+    /// is submitted for ahead of time.
+    pub fn lookahead(&self) -> u64 {
+        self.lookahead
+    }
+
+    /// Submit the bundle for the given number of blocks after the current
+    /// target block ahead of time, instead of waiting for each target
+    /// block to be mined before submitting for the next block.
+    ///
+    /// Each copy is assigned its own replacement uuid, derived from the
+    /// replacement uuid of the bundle (or its transactions, if it has none)
+    /// and the target block, so the copies do not replace each other. Once
+    /// the bundle is included, the copies targeting later blocks are
+    /// cancelled using their replacement uuids.
+    pub fn set_lookahead(mut self, blocks: u64) -> Self {
+        self.lookahead = blocks;
+        self
+    }
+
+    /// Whether new blocks are detected using the block stream of the
+    /// provider.
+    pub fn block_stream(&self) -> bool {
+        self.block_stream
+    }
+
+    /// Detect new blocks using the block stream of the provider (see
+    /// [`Middleware::watch_blocks`]) instead of waiting for the pending
+    /// bundle to poll for its target block.
+    ///
+    /// The inclusion of the bundle is checked when a new head is seen, and
+    /// the bundle is re-targeted and sent again right away. The inclusion
+    /// is checked like the pending bundle checks it, including its
+    /// inclusion check, confirmations, reorg window and hooks.
+    ///
+    /// The block filter is polled at the poll interval of the resubmitter
+    /// (see [`BundleResubmitter::set_poll_interval`]), so new heads are
+    /// seen up to one poll interval after they are mined.
+    pub fn set_block_stream(mut self, enabled: bool) -> Self {
+        self.block_stream = enabled;
+        self
+    }
+
+    /// Send the bundle to consecutive blocks until it is included, or until
+    /// the maximum number of blocks has been reached.
+    ///
+    /// If the bundle is included, copies of the bundle submitted for later
+    /// blocks (see [`BundleResubmitter::set_lookahead`]) are cancelled.
+    pub async fn run(
+        &self,
+        bundle: &BundleRequest,
+    ) -> Result<ResubmissionOutcome, ResubmissionError<M, S>> {
+        let mut outcome = ResubmissionOutcome::default();
+        let mut target = match bundle.block() {
+            Some(block) => block,
+            None => {
+                self.middleware
+                    .chain()
+                    .get_block_number()
+                    .await
+                    .map_err(FlashbotsMiddlewareError::MiddlewareError)?
+                    + 1
+            }
+        };
+        let last = target + self.max_blocks;
+
+        let mut heads = if self.block_stream {
+            Some(
+                self.middleware
+                    .chain()
+                    .watch_blocks()
+                    .await
+                    .map_err(FlashbotsMiddlewareError::MiddlewareError)?
+                    .interval(self.poll_interval),
+            )
+        } else {
+            None
+        };
+
+        let mut pending = VecDeque::new();
+        let mut next = target;
+        loop {
+            // Submit the bundle for all blocks within the lookahead
+            while next < last && next <= target + self.lookahead {
+                if self.proposer_registered(next).await? {
+                    let mut copy = bundle.clone_for_block(next);
+                    if self.lookahead > 0 {
+                        copy = copy.set_uuid(block_uuid(bundle, next));
+                    }
+                    let pending_bundle = self
+                        .middleware
+                        .send_bundle(&copy)
+                        .await?
+                        .interval(self.poll_interval);
+                    outcome.submitted_blocks.push(next);
+                    pending.push_back((next, *copy.uuid(), pending_bundle));
+                } else {
+                    outcome.skipped_blocks.push(next);
+                }
+                next += U64::one();
+            }
+
+            let (block, _, pending_bundle) = match pending.pop_front() {
+                Some(pending) => pending,
+                None if next < last => {
+                    // All blocks within the lookahead were skipped
+                    self.wait_for_block(next - 1).await?;
+                    target = next;
+                    continue;
+                }
+                None => break,
+            };
+
+            let included = match &mut heads {
+                Some(heads) => {
+                    self.check_on_next_head(heads, block, &pending_bundle)
+                        .await?
+                }
+                None => match pending_bundle.await {
+                    Ok(bundle_hash) => Some(bundle_hash),
+                    Err(PendingBundleError::BundleNotIncluded) => None,
+                    Err(err) => return Err(err.into()),
+                },
+            };
+            if let Some(bundle_hash) = included {
+                outcome.included_block = Some(block);
+                outcome.bundle_hash = bundle_hash;
+                self.cancel(pending, &mut outcome).await;
+                outcome.delivery = self.delivery(block).await;
+                return Ok(outcome);
+            }
+
+            target = block + 1;
+        }
+
+        Ok(outcome)
+    }
+
+    /// Wait for a new head at or after the target block of the pending
+    /// bundle, and check whether the bundle was included.
+    ///
+    /// The inclusion is checked like the pending bundle does (see
+    /// [`PendingBundle::inclusion_check`]), so it is checked again on the
+    /// next head while the target block is not confirmed yet.
+    ///
+    /// Returns the bundle hash if the bundle was included.
+    async fn check_on_next_head(
+        &self,
+        heads: &mut (impl Stream<Item = H256> + Unpin),
+        block: U64,
+        pending_bundle: &PendingBundle<'a, M::Provider>,
+    ) -> Result<Option<Option<BundleHash>>, ResubmissionError<M, S>> {
+        loop {
+            match heads.next().await {
+                Some(head) => match self.get_block(head).await? {
+                    Some(head) if head.number.is_some_and(|number| number >= block) => {}
+                    _ => continue,
+                },
+                // The block filter stopped, so poll for the target block instead
+                None => tokio::time::sleep(self.poll_interval).await,
+            }
+
+            match pending_bundle.check().await {
+                Ok(Some(bundle_hash)) => return Ok(Some(bundle_hash)),
+                Ok(None) => continue,
+                Err(PendingBundleError::BundleNotIncluded) => return Ok(None),
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    /// Cancel the copies of an included bundle that target later blocks.
+    ///
+    /// Cancellations are best-effort: blocks for which the cancellation
+    /// failed are not added to the outcome.
+    async fn cancel(
+        &self,
+        pending: VecDeque<Submission<'a, M>>,
+        outcome: &mut ResubmissionOutcome,
+    ) {
+        for (block, uuid, _) in pending {
+            let uuid = match uuid {
+                Some(uuid) => uuid,
+                None => continue,
+            };
+            let cancellation = BundleRequest::cancellation(uuid).set_block(block);
+            if self.middleware.send_cancellation(&cancellation).await {
+                outcome.cancelled_blocks.push(block);
+            }
+        }
+    }
+
+    /// Look up the payload delivered for the given block.
+    ///
+    /// Relays that fail to answer are skipped, as are payloads for other
+    /// blocks at the same height.
+    async fn delivery(&self, block: U64) -> Option<PayloadDelivery> {
+        let check = self.delivery_check.as_ref()?;
+        let block_hash = self.get_block(block).await.ok()??.hash?;
+
+        for relay in &check.relays {
+            match relay.delivered_payload_by_hash(block_hash).await {
+                Ok(Some(payload)) => {
+                    return Some(PayloadDelivery {
+                        relay: relay.url().clone(),
+                        sent_to_builder: check.builder_pubkeys.contains(&payload.builder_pubkey),
+                        payload,
+                    });
+                }
+                _ => continue,
+            }
+        }
+
+        None
+    }
+
+    /// Check whether the proposer of the given block runs MEV-Boost.
+    async fn proposer_registered(&self, block: U64) -> Result<bool, ResubmissionError<M, S>> {
+        let check = match &self.proposer_check {
+            Some(check) => check,
+            None => return Ok(true),
+        };
+
+        let latest = self
+            .get_block(BlockNumber::Latest)
+            .await?
+            .ok_or(ResubmissionError::LatestBlockUnavailable)?;
+        let latest_number = latest
+            .number
+            .ok_or(ResubmissionError::LatestBlockUnavailable)?;
+
+        // Assume that none of the slots between the latest block and the
+        // target block are missed.
+        let latest_slot = check
+            .scheduler
+            .slot_at(UNIX_EPOCH + Duration::from_secs(latest.timestamp.as_u64()));
+        let slot = U64::from(latest_slot) + block.saturating_sub(latest_number);
+
+        // Relays only know the proposers of the current and the next epoch,
+        // so proposers further ahead are assumed to be registered.
+        let epoch = latest_slot / SLOTS_PER_EPOCH;
+        if slot.as_u64() / SLOTS_PER_EPOCH > epoch + 1 {
+            return Ok(true);
+        }
+
+        let mut any_answered = false;
+        for index in 0..check.relays.len() {
+            if let Some(registered) = check.is_registered(index, epoch, slot).await {
+                if registered {
+                    return Ok(true);
+                }
+                any_answered = true;
+            }
+        }
+
+        Ok(!any_answered)
+    }
+
+    /// Get a block from the chain.
+    async fn get_block(
+        &self,
+        block: impl Into<BlockId> + Send + Sync,
+    ) -> Result<Option<Block<H256>>, ResubmissionError<M, S>> {
+        Ok(self
+            .middleware
+            .chain()
+            .get_block(block)
+            .await
+            .map_err(FlashbotsMiddlewareError::MiddlewareError)?)
+    }
+
+    /// Wait until the given block has been mined.
+    async fn wait_for_block(&self, block: U64) -> Result<(), ResubmissionError<M, S>> {
+        while self
+            .middleware
+            .chain()
+            .get_block_number()
+            .await
+            .map_err(FlashbotsMiddlewareError::MiddlewareError)?
+            < block
+        {
+            tokio::time::sleep(self.poll_interval).await;
+        }
+
+        Ok(())
+    }
+}
+
+/// Derive the replacement uuid of the copy of a bundle that targets the
+/// given block.
+fn block_uuid(bundle: &BundleRequest, block: U64) -> Uuid {
+    let mut data: Vec<u8> = match bundle.uuid() {
+        Some(uuid) => uuid.as_bytes().to_vec(),
+        None => bundle
+            .transaction_hashes()
+            .iter()
+            .flat_map(|hash| hash.to_fixed_bytes())
+            .collect(),
+    };
+    data.extend_from_slice(&block.as_u64().to_be_bytes());
+    let hash = keccak256(data);
+    let mut uuid = [0; 16];
+    uuid.copy_from_slice(&hash[..16]);
+    Uuid::from_bytes(uuid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "reqwest-transport")]
+    use crate::fixtures::{json_response, serve};
+    use crate::fixtures::{wallet, MockTransport};
+    use ethers::{
+        core::types::{TxHash, U256},
+        providers::{MockProvider, Provider},
+        signers::LocalWallet,
+    };
+    use serde_json::Value;
+    use std::sync::Arc;
+
+    fn middleware() -> (
+        FlashbotsMiddleware<Provider<MockProvider>, LocalWallet>,
+        MockProvider,
+        Arc<MockTransport>,
+    ) {
+        let (provider, mock) = Provider::mocked();
+        let signer = wallet();
+        let mut middleware = FlashbotsMiddleware::new(
+            provider,
+            Url::parse("http://localhost:8545").unwrap(),
+            signer,
+        );
+        let transport = MockTransport::bundles();
+        middleware.relay_mut().set_transport(transport.clone());
+        (middleware, mock, transport)
+    }
+
+    fn sent_bundles(transport: &MockTransport) -> Vec<Value> {
+        transport
+            .requests()
+            .into_iter()
+            .map(|request| request.body["params"][0].clone())
+            .collect()
+    }
+
+    fn block(number: u64, transactions: Vec<TxHash>) -> Block<TxHash> {
+        Block {
+            number: Some(number.into()),
+            hash: Some(H256::from_low_u64_be(number)),
+            timestamp: (12 * (number - 9)).into(),
+            transactions,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn lookahead() {
+        let (middleware, mock, transport) = middleware();
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(10.into());
+        mock.push(block(10, bundle.transaction_hashes())).unwrap();
+
+        let outcome = BundleResubmitter::new(&middleware, 3)
+            .set_poll_interval(Duration::from_millis(1))
+            .set_lookahead(2)
+            .run(&bundle)
+            .await
+            .unwrap();
+        assert_eq!(outcome.included_block, Some(10.into()));
+        assert_eq!(outcome.bundle_hash, Some(BundleHash::from_low_u64_be(1)));
+        assert_eq!(
+            outcome.submitted_blocks,
+            vec![10.into(), 11.into(), 12.into()]
+        );
+        assert_eq!(outcome.cancelled_blocks, vec![11.into(), 12.into()]);
+
+        // Each copy has its own replacement uuid, and the copies for later
+        // blocks are cancelled by their uuid
+        let bundles = sent_bundles(&transport);
+        assert_eq!(bundles.len(), 5);
+        let uuids: Vec<&Value> = bundles[..3]
+            .iter()
+            .map(|bundle| &bundle["replacementUuid"])
+            .collect();
+        assert!(uuids.iter().all(|uuid| uuid.is_string()));
+        assert_ne!(uuids[0], uuids[1]);
+        assert_ne!(uuids[1], uuids[2]);
+        assert_ne!(uuids[0], uuids[2]);
+        for (cancellation, copy) in bundles[3..].iter().zip(&bundles[1..3]) {
+            assert_eq!(cancellation["txs"], Value::Array(vec![]));
+            assert_eq!(cancellation["blockNumber"], copy["blockNumber"]);
+            assert_eq!(cancellation["replacementUuid"], copy["replacementUuid"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn resubmit_without_lookahead() {
+        let (middleware, mock, transport) = middleware();
+        let uuid = Uuid::from_u128(1);
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(10.into())
+            .set_uuid(uuid);
+        // Responses are returned in reverse order
+        mock.push(block(11, bundle.transaction_hashes())).unwrap();
+        mock.push(block(10, vec![])).unwrap();
+
+        let outcome = BundleResubmitter::new(&middleware, 3)
+            .set_poll_interval(Duration::from_millis(1))
+            .run(&bundle)
+            .await
+            .unwrap();
+        assert_eq!(outcome.included_block, Some(11.into()));
+        assert_eq!(outcome.submitted_blocks, vec![10.into(), 11.into()]);
+        assert!(outcome.cancelled_blocks.is_empty());
+
+        // Without lookahead, the replacement uuid of the bundle is kept
+        let bundles = sent_bundles(&transport);
+        assert_eq!(bundles.len(), 2);
+        assert_eq!(bundles[0]["blockNumber"], "0xa");
+        assert_eq!(bundles[1]["blockNumber"], "0xb");
+        assert!(bundles
+            .iter()
+            .all(|bundle| bundle["replacementUuid"] == uuid.to_string()));
+    }
+
+    #[tokio::test]
+    async fn block_stream() {
+        let (middleware, mock, transport) = middleware();
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(10.into());
+        // Responses are returned in reverse order. The head before the
+        // target block is skipped, and the inclusion is checked in the
+        // target block itself.
+        mock.push(block(10, bundle.transaction_hashes())).unwrap();
+        mock.push(block(10, bundle.transaction_hashes())).unwrap();
+        mock.push(block(9, vec![])).unwrap();
+        mock.push::<Vec<H256>, _>(vec![H256::from_low_u64_be(9), H256::from_low_u64_be(10)])
+            .unwrap();
+        mock.push(U256::one()).unwrap();
+
+        let outcome = BundleResubmitter::new(&middleware, 3)
+            .set_poll_interval(Duration::from_millis(1))
+            .set_block_stream(true)
+            .run(&bundle)
+            .await
+            .unwrap();
+        assert_eq!(outcome.included_block, Some(10.into()));
+        assert_eq!(outcome.bundle_hash, Some(BundleHash::from_low_u64_be(1)));
+        assert_eq!(outcome.submitted_blocks, vec![10.into()]);
+        assert_eq!(transport.requests().len(), 1);
+
+        mock.assert_request("eth_newBlockFilter", Vec::<Value>::new())
+            .unwrap();
+        mock.assert_request("eth_getFilterChanges", [U256::one()])
+            .unwrap();
+        mock.assert_request("eth_getBlockByHash", (H256::from_low_u64_be(9), false))
+            .unwrap();
+        mock.assert_request("eth_getBlockByHash", (H256::from_low_u64_be(10), false))
+            .unwrap();
+        mock.assert_request("eth_getBlockByNumber", (U64::from(10), false))
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "reqwest-transport")]
+    async fn skip_blocks_and_confirm_delivery() {
+        let (middleware, mock, transport) = middleware();
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(10.into());
+
+        // Block 9 is in slot 0, so only the proposer of block 11 (in slot
+        // 2) is registered
+        let (relay, _) = serve(vec![
+            json_response("200 OK", r#"[{"slot":"2","validator_index":"1","entry":{"message":{"fee_recipient":"0x388c818ca8b9251b393131c08a736a67ccb19297","gas_limit":"30000000","timestamp":"0","pubkey":"0x01"},"signature":"0x01"}}]"#),
+            json_response("200 OK", &format!(
+                r#"[{{"slot":"2","parent_hash":"{:?}","block_hash":"{:?}","builder_pubkey":"0xa1","proposer_pubkey":"0xb2","proposer_fee_recipient":"0x388c818ca8b9251b393131c08a736a67ccb19297","gas_limit":"30000000","gas_used":"21000","value":"1","block_number":"11","num_tx":"1"}}]"#,
+                H256::from_low_u64_be(10),
+                H256::from_low_u64_be(11)
+            )),
+        ])
+        .await;
+        // Responses are returned in reverse order
+        mock.push(block(11, bundle.transaction_hashes())).unwrap();
+        mock.push(block(11, bundle.transaction_hashes())).unwrap();
+        mock.push(block(10, vec![])).unwrap();
+        mock.push(U64::from(10)).unwrap();
+        mock.push(block(9, vec![])).unwrap();
+
+        let outcome = BundleResubmitter::new(&middleware, 2)
+            .set_poll_interval(Duration::from_millis(1))
+            .skip_unregistered_proposers(
+                vec![RelayDataClient::new(relay.clone())],
+                SlotScheduler::new(0),
+            )
+            .confirm_delivery(
+                vec![RelayDataClient::new(relay.clone())],
+                vec![Bytes::from(vec![0xa1])],
+            )
+            .run(&bundle)
+            .await
+            .unwrap();
+        assert_eq!(outcome.skipped_blocks, vec![10.into()]);
+        assert_eq!(outcome.submitted_blocks, vec![11.into()]);
+        assert_eq!(outcome.included_block, Some(11.into()));
+        assert_eq!(transport.requests().len(), 1);
+
+        let delivery = outcome.delivery.unwrap();
+        assert_eq!(delivery.relay, relay);
+        assert_eq!(delivery.payload.block_number, U64::from(11));
+        assert!(delivery.sent_to_builder);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "reqwest-transport")]
+    async fn confirm_delivery_of_including_block() {
+        let (middleware, mock, _) = middleware();
+        let bundle = BundleRequest::new()
+            .push_transaction(Bytes::from(vec![0x1]))
+            .set_block(10.into());
+
+        let payload = |block_hash: H256, builder_pubkey: &str| {
+            json_response(
+                "200 OK",
+                &format!(
+                    r#"[{{"slot":"1","parent_hash":"{:?}","block_hash":"{:?}","builder_pubkey":"{}","proposer_pubkey":"0xb2","proposer_fee_recipient":"0x388c818ca8b9251b393131c08a736a67ccb19297","gas_limit":"30000000","gas_used":"21000","value":"1","block_number":"10","num_tx":"1"}}]"#,
+                    H256::from_low_u64_be(9),
+                    block_hash,
+                    builder_pubkey
+                ),
+            )
+        };
+        // The first relay delivered a block at the same height that was
+        // reorged out
+        let (reorged, _) = serve(vec![payload(H256::from_low_u64_be(99), "0xa1")]).await;
+        let (relay, _) = serve(vec![payload(H256::from_low_u64_be(10), "0xa2")]).await;
+        // Responses are returned in reverse order
+        mock.push(block(10, bundle.transaction_hashes())).unwrap();
+        mock.push(block(10, bundle.transaction_hashes())).unwrap();
+
+        let outcome = BundleResubmitter::new(&middleware, 1)
+            .set_poll_interval(Duration::from_millis(1))
+            .confirm_delivery(
+                vec![
+                    RelayDataClient::new(reorged),
+                    RelayDataClient::new(relay.clone()),
+                ],
+                vec![Bytes::from(vec![0xa1])],
+            )
+            .run(&bundle)
+            .await
+            .unwrap();
+        assert_eq!(outcome.included_block, Some(10.into()));
+
+        let delivery = outcome.delivery.unwrap();
+        assert_eq!(delivery.relay, relay);
+        assert_eq!(delivery.payload.block_hash, H256::from_low_u64_be(10));
+        assert!(!delivery.sent_to_builder);
+    }
+}
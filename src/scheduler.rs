@@ -0,0 +1,234 @@
+use std::{
+    convert::TryFrom,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The genesis time of the Ethereum mainnet beacon chain, in seconds
+/// since the UNIX epoch.
+pub const MAINNET_GENESIS_TIME: u64 = 1606824023;
+
+/// The duration of a beacon chain slot.
+pub const SLOT_DURATION: Duration = Duration::from_secs(12);
+
+/// The number of slots in a beacon chain epoch.
+pub(crate) const SLOTS_PER_EPOCH: u64 = 32;
+
+/// A scheduler that times bundle submissions relative to the beacon
+/// chain slot cadence.
+///
+/// Builders finalize their blocks right around the slot boundary, so
+/// submitting a bundle shortly before the boundary gives it the freshest
+/// state while still making it into the block. The scheduler computes
+/// these submission times given the genesis time of the chain and a lead
+/// time, i.e. how long before the slot boundary the submission should be
+/// made.
+///
+/// # Example
+///
+/// ```no_run
+/// use ethers_flashbots::{SlotScheduler, MAINNET_GENESIS_TIME};
+/// use std::time::Duration;
+///
+/// # async fn foo() {
+/// let scheduler =
+///     SlotScheduler::new(MAINNET_GENESIS_TIME).set_lead(Duration::from_millis(500));
+///
+/// // Wait until 500ms before the next slot starts
+/// let slot = scheduler.wait_for_next_submission().await;
+/// println!("Submitting bundles for slot {}", slot);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SlotScheduler {
+    genesis_time: u64,
+    slot_duration: Duration,
+    lead: Duration,
+}
+
+impl SlotScheduler {
+    /// Creates a new scheduler for a chain with the given genesis time,
+    /// in seconds since the UNIX epoch.
+    ///
+    /// The slot duration defaults to 12 seconds and the lead time defaults
+    /// to zero.
+    pub fn new(genesis_time: u64) -> Self {
+        Self {
+            genesis_time,
+            slot_duration: SLOT_DURATION,
+            lead: Duration::ZERO,
+        }
+    }
+
+    /// Get the genesis time of the chain, in seconds since the UNIX epoch.
+    pub fn genesis_time(&self) -> u64 {
+        self.genesis_time
+    }
+
+    /// Get the slot duration.
+    pub fn slot_duration(&self) -> Duration {
+        self.slot_duration
+    }
+
+    /// Set the slot duration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot_duration` is zero.
+    pub fn set_slot_duration(mut self, slot_duration: Duration) -> Self {
+        assert!(!slot_duration.is_zero(), "slot duration must not be zero");
+        self.slot_duration = slot_duration;
+        self
+    }
+
+    /// Get the lead time, i.e. how long before the slot boundary
+    /// submissions are made.
+    pub fn lead(&self) -> Duration {
+        self.lead
+    }
+
+    /// Set the lead time, i.e. how long before the slot boundary
+    /// submissions are made.
+    pub fn set_lead(mut self, lead: Duration) -> Self {
+        self.lead = lead;
+        self
+    }
+
+    /// Get the slot that is in progress at the given time.
+    ///
+    /// Times before genesis are considered to be in slot 0.
+    pub fn slot_at(&self, time: SystemTime) -> u64 {
+        let since_genesis = time
+            .duration_since(self.genesis())
+            .unwrap_or(Duration::ZERO);
+
+        let slot = since_genesis.as_nanos() / self.slot_duration.as_nanos();
+        u64::try_from(slot).unwrap_or(u64::MAX)
+    }
+
+    /// Get the time at which the given slot starts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the slot cannot be represented as a
+    /// [`SystemTime`].
+    pub fn slot_start(&self, slot: u64) -> SystemTime {
+        self.slot_offset(slot)
+            .and_then(|offset| self.genesis().checked_add(offset))
+            .expect("slot start is out of range")
+    }
+
+    /// Get the time at which bundles for the given slot should be submitted.
+    pub fn submission_time(&self, slot: u64) -> SystemTime {
+        self.slot_start(slot) - self.lead
+    }
+
+    /// Get the next slot whose submission time is after the given time,
+    /// along with that submission time.
+    pub fn next_submission(&self, time: SystemTime) -> (u64, SystemTime) {
+        let mut slot = self.slot_at(time) + 1;
+        while self.submission_time(slot) <= time {
+            slot += 1;
+        }
+
+        (slot, self.submission_time(slot))
+    }
+
+    /// Wait until the submission time of the given slot.
+    ///
+    /// Returns immediately if the submission time has already passed.
+    pub async fn wait_for_slot(&self, slot: u64) {
+        let delay = self
+            .submission_time(slot)
+            .duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO);
+
+        tokio::time::sleep(delay).await
+    }
+
+    /// Wait until the next submission time, returning the slot
+    /// that the submission is for.
+    pub async fn wait_for_next_submission(&self) -> u64 {
+        let (slot, _) = self.next_submission(SystemTime::now());
+        self.wait_for_slot(slot).await;
+        slot
+    }
+
+    /// The time between genesis and the start of the given slot, if it
+    /// does not overflow.
+    fn slot_offset(&self, slot: u64) -> Option<Duration> {
+        let nanos = self
+            .slot_duration
+            .as_nanos()
+            .checked_mul(u128::from(slot))?;
+        let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+        Some(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+    }
+
+    fn genesis(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.genesis_time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_timing() {
+        let scheduler = SlotScheduler::new(1000).set_lead(Duration::from_millis(500));
+
+        assert_eq!(scheduler.slot_at(UNIX_EPOCH), 0);
+        assert_eq!(scheduler.slot_at(UNIX_EPOCH + Duration::from_secs(1000)), 0);
+        assert_eq!(scheduler.slot_at(UNIX_EPOCH + Duration::from_secs(1025)), 2);
+        assert_eq!(
+            scheduler.slot_start(2),
+            UNIX_EPOCH + Duration::from_secs(1024)
+        );
+        assert_eq!(
+            scheduler.submission_time(2),
+            UNIX_EPOCH + Duration::from_millis(1_023_500)
+        );
+    }
+
+    #[test]
+    fn next_submission() {
+        let scheduler = SlotScheduler::new(1000).set_lead(Duration::from_millis(500));
+
+        // In the middle of slot 1, the next submission is for slot 2
+        let (slot, time) = scheduler.next_submission(UNIX_EPOCH + Duration::from_secs(1018));
+        assert_eq!(slot, 2);
+        assert_eq!(time, UNIX_EPOCH + Duration::from_millis(1_023_500));
+
+        // Within the lead time of slot 2, the next submission is for slot 3
+        let (slot, _) = scheduler.next_submission(UNIX_EPOCH + Duration::from_millis(1_023_800));
+        assert_eq!(slot, 3);
+    }
+
+    #[test]
+    fn short_slots() {
+        let scheduler = SlotScheduler::new(1000).set_slot_duration(Duration::from_micros(250));
+
+        assert_eq!(
+            scheduler.slot_at(UNIX_EPOCH + Duration::from_secs(1001)),
+            4000
+        );
+        assert_eq!(
+            scheduler.slot_start(4000),
+            UNIX_EPOCH + Duration::from_secs(1001)
+        );
+
+        // Slots beyond `u32::MAX` are not truncated
+        let scheduler = SlotScheduler::new(0);
+        let slot = u64::from(u32::MAX) + 1;
+        assert_eq!(
+            scheduler.slot_start(slot),
+            UNIX_EPOCH + Duration::from_secs(slot * 12)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "slot duration must not be zero")]
+    fn zero_slot_duration() {
+        SlotScheduler::new(1000).set_slot_duration(Duration::ZERO);
+    }
+}
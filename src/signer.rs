@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use ethers::{
+    core::types::{
+        transaction::{eip2718::TypedTransaction, eip712::Eip712},
+        Address, Signature,
+    },
+    signers::Signer,
+};
+use std::sync::Arc;
+
+/// A signer that is shared by several relays or middlewares.
+///
+/// Relays need their own handle to the signer, so the broadcaster clones
+/// the signer once per relay. `SharedSigner` wraps a signer in an [`Arc`],
+/// so signers that cannot (or should not) be cloned, such as HSM or
+/// KMS-backed signers, can be used by any number of relays.
+///
+/// ```
+/// use ethers::signers::{LocalWallet, Signer};
+/// use ethers_flashbots::{Relay, SharedSigner};
+/// use url::Url;
+///
+/// let wallet: LocalWallet = "380eb0f3d505f087e438eca80bc4df9a7faa24f868e69fc0440261a0fc0567dc"
+///     .parse()
+///     .unwrap();
+/// let signer = SharedSigner::new(wallet);
+/// let flashbots = Relay::new(Url::parse("https://relay.flashbots.net").unwrap(), Some(signer.clone()));
+/// let titan = Relay::new(Url::parse("https://rpc.titanbuilder.xyz").unwrap(), Some(signer));
+/// ```
+#[derive(Debug)]
+pub struct SharedSigner<S> {
+    inner: Arc<S>,
+    chain_id: Option<u64>,
+}
+
+impl<S> SharedSigner<S> {
+    /// Wraps the signer so it can be shared.
+    pub fn new(signer: S) -> Self {
+        Arc::new(signer).into()
+    }
+
+    /// Get a reference to the shared signer.
+    pub fn inner(&self) -> &Arc<S> {
+        &self.inner
+    }
+}
+
+impl<S> Clone for SharedSigner<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            chain_id: self.chain_id,
+        }
+    }
+}
+
+impl<S> From<Arc<S>> for SharedSigner<S> {
+    fn from(inner: Arc<S>) -> Self {
+        Self {
+            inner,
+            chain_id: None,
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Signer> Signer for SharedSigner<S> {
+    type Error = S::Error;
+
+    async fn sign_message<M: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: M,
+    ) -> Result<Signature, Self::Error> {
+        self.inner.sign_message(message).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        self.inner.sign_transaction(tx).await
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        self.inner.sign_typed_data(payload).await
+    }
+
+    fn address(&self) -> Address {
+        self.inner.address()
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id.unwrap_or_else(|| self.inner.chain_id())
+    }
+
+    /// Sets the chain id of this handle.
+    ///
+    /// The shared signer is not changed, so transactions without a chain
+    /// id are still signed for the chain of the shared signer.
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = Some(chain_id.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::wallet;
+
+    #[tokio::test]
+    async fn shared_signer() {
+        let wallet = wallet();
+        let signer = SharedSigner::new(wallet.clone());
+        let clone = signer.clone().with_chain_id(5u64);
+        assert_eq!(Arc::strong_count(signer.inner()), 2);
+        assert_eq!(clone.address(), wallet.address());
+        assert_eq!(clone.chain_id(), 5);
+        assert_eq!(signer.chain_id(), wallet.chain_id());
+        assert_eq!(
+            clone.sign_message("message").await.unwrap(),
+            wallet.sign_message("message").await.unwrap()
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use crate::bundle::SimulatedBundle;
+use ethers::core::types::{U256, U64};
+
+/// A common interface for bundle simulation results.
+///
+/// This is implemented for both [`SimulatedBundle`] (from
+/// [`eth_callBundle`][call_bundle]) and
+/// [`MevShareSimulation`](crate::mev_share::MevShareSimulation) (from
+/// [`mev_simBundle`][sim_bundle]), so strategies can evaluate
+/// simulations without depending on the simulation backend.
+///
+/// [call_bundle]: https://docs.flashbots.net/flashbots-auction/advanced/rpc-endpoint#eth_callbundle
+/// [sim_bundle]: https://docs.flashbots.net/flashbots-auction/advanced/rpc-endpoint#mev_simbundle
+pub trait SimulationResult {
+    /// Whether the simulation succeeded, i.e. no non-revertible item failed
+    /// or reverted.
+    ///
+    /// Results that do not record which items are allowed to revert treat
+    /// every failed or reverted item as a failure. This is the case for
+    /// [`SimulatedBundle`], see [`SimulatedBundle::is_success_for`].
+    fn is_success(&self) -> bool;
+
+    /// The error of the first item that failed or reverted (if any).
+    fn error(&self) -> Option<&str>;
+
+    /// The block the bundle was simulated on top of.
+    fn simulation_block(&self) -> U64;
+
+    /// The total amount of gas used by the bundle.
+    fn gas_used(&self) -> U256;
+
+    /// The value the bundle pays to the block builder, i.e. gas fees and
+    /// coinbase tips.
+    fn coinbase_profit(&self) -> U256;
+
+    /// The indices of the items in the bundle that failed or reverted.
+    fn reverted_items(&self) -> Vec<usize>;
+
+    /// The effective gas price of the bundle, i.e.
+    /// `coinbase_profit / gas_used`.
+    ///
+    /// Returns `None` if the bundle used no gas.
+    fn effective_gas_price(&self) -> Option<U256> {
+        self.coinbase_profit().checked_div(self.gas_used())
+    }
+}
+
+impl SimulationResult for SimulatedBundle {
+    fn is_success(&self) -> bool {
+        !self.has_errors()
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.first_error()
+            .and_then(|tx| tx.error.as_deref().or(tx.revert.as_deref()))
+    }
+
+    fn simulation_block(&self) -> U64 {
+        self.simulation_block
+    }
+
+    fn gas_used(&self) -> U256 {
+        self.gas_used
+    }
+
+    fn coinbase_profit(&self) -> U256 {
+        self.coinbase_diff
+    }
+
+    fn reverted_items(&self) -> Vec<usize> {
+        self.transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, tx)| tx.is_reverted())
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bundle::BundleRequest, fixtures::SIMULATED_BUNDLE};
+
+    #[test]
+    fn simulated_bundle_simulation_result() {
+        let bundle: SimulatedBundle = serde_json::from_str(SIMULATED_BUNDLE).unwrap();
+
+        let result: &dyn SimulationResult = &bundle;
+        assert!(!result.is_success());
+        assert_eq!(result.error(), Some("execution reverted"));
+        assert_eq!(result.simulation_block(), 5221585u64.into());
+        assert_eq!(result.gas_used(), 42000u64.into());
+        assert_eq!(result.coinbase_profit(), 20000000000126000u64.into());
+        assert_eq!(result.reverted_items(), vec![0]);
+        assert_eq!(result.effective_gas_price(), Some(476190476193u64.into()));
+
+        // Allowed reverts are not failures
+        let reverted = bundle.transactions[0].hash;
+        let request = BundleRequest::new().set_revertible_transaction_hashes(vec![reverted]);
+        assert!(bundle.is_success_for(&request));
+        assert!(!bundle.is_success_for(&BundleRequest::new()));
+    }
+}
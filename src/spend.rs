@@ -0,0 +1,275 @@
+use crate::bundle::BundleRequest;
+use ethers::core::types::{Address, TxHash, U256};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The default window for the spend limit per time window.
+const DEFAULT_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Limits on the amount bundles may spend.
+///
+/// The spend of a transaction is the most it can cost its sender, i.e.
+/// its value (including coinbase payments) plus its gas limit times its
+/// maximum fee per gas. Only transactions from the given senders are
+/// counted, so transactions from the mempool do not count towards the
+/// limits. If no senders are given, all transactions are counted.
+///
+/// Transactions are counted once per time window, even if they are sent
+/// in multiple bundles (e.g. when resubmitting a bundle for the next
+/// block), and are counted once a relay accepted them, even if they are
+/// never included. Transactions of bundles that could not be sent are not
+/// counted. Transactions that cannot be decoded are not counted.
+///
+/// Bundles that exceed the limits are rejected before they are sent with
+/// [`FlashbotsMiddlewareError::SpendLimitExceeded`](crate::FlashbotsMiddlewareError::SpendLimitExceeded).
+///
+/// ```
+/// use ethers::{core::types::Address, utils::parse_ether};
+/// use ethers_flashbots::SpendLimit;
+/// use std::time::Duration;
+///
+/// let limit = SpendLimit::new(vec![Address::zero()])
+///     .set_max_per_bundle(parse_ether("0.5").unwrap())
+///     .set_max_per_window(parse_ether("2").unwrap(), Duration::from_secs(600));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpendLimit {
+    senders: Vec<Address>,
+    max_per_bundle: Option<U256>,
+    max_per_window: Option<U256>,
+    window: Duration,
+}
+
+impl SpendLimit {
+    /// Creates a spend limit without any limits for transactions from the
+    /// given senders.
+    pub fn new(senders: Vec<Address>) -> Self {
+        Self {
+            senders,
+            max_per_bundle: None,
+            max_per_window: None,
+            window: DEFAULT_WINDOW,
+        }
+    }
+
+    /// Get the senders whose transactions are counted.
+    pub fn senders(&self) -> &[Address] {
+        &self.senders
+    }
+
+    /// Get the maximum spend of a single bundle, in wei (if any).
+    pub fn max_per_bundle(&self) -> Option<U256> {
+        self.max_per_bundle
+    }
+
+    /// Set the maximum spend of a single bundle, in wei.
+    pub fn set_max_per_bundle(mut self, max: U256) -> Self {
+        self.max_per_bundle = Some(max);
+        self
+    }
+
+    /// Get the maximum spend per time window, in wei (if any).
+    pub fn max_per_window(&self) -> Option<U256> {
+        self.max_per_window
+    }
+
+    /// Get the time window of [`SpendLimit::max_per_window`].
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Set the maximum spend of all bundles sent within the given time
+    /// window, in wei.
+    pub fn set_max_per_window(mut self, max: U256, window: Duration) -> Self {
+        self.max_per_window = Some(max);
+        self.window = window;
+        self
+    }
+}
+
+/// Enforces a [`SpendLimit`].
+#[derive(Debug)]
+pub(crate) struct SpendGuard {
+    limit: SpendLimit,
+    /// The time and spend of the transactions sent within the window.
+    spends: Mutex<HashMap<TxHash, (Instant, U256)>>,
+}
+
+impl SpendGuard {
+    pub(crate) fn new(limit: SpendLimit) -> Self {
+        Self {
+            limit,
+            spends: Default::default(),
+        }
+    }
+
+    pub(crate) fn limit(&self) -> &SpendLimit {
+        &self.limit
+    }
+
+    /// Reserve the spend of a bundle that is about to be sent.
+    ///
+    /// Returns the spend and the exceeded limit if the bundle exceeds a
+    /// limit, in which case nothing is reserved. Otherwise the spend counts
+    /// towards the limits until the reservation is released, i.e. dropped
+    /// without being committed.
+    pub(crate) fn reserve(
+        &self,
+        bundle: &BundleRequest,
+    ) -> Result<SpendReservation<'_>, (U256, U256)> {
+        let txs: Vec<(TxHash, U256)> = bundle
+            .transactions()
+            .iter()
+            .zip(bundle.transaction_hashes())
+            .filter_map(|(tx, hash)| Some((tx.decode().ok()?, hash)))
+            .filter(|(tx, _)| {
+                self.limit.senders.is_empty() || self.limit.senders.contains(&tx.from)
+            })
+            .map(|(tx, hash)| {
+                let fee_per_gas = tx.max_fee_per_gas.or(tx.gas_price).unwrap_or_default();
+                let spend = tx.gas.saturating_mul(fee_per_gas).saturating_add(tx.value);
+                (hash, spend)
+            })
+            .collect();
+
+        if let Some(limit) = self.limit.max_per_bundle {
+            let spend = txs
+                .iter()
+                .fold(U256::zero(), |acc, (_, spend)| acc.saturating_add(*spend));
+            if spend > limit {
+                return Err((spend, limit));
+            }
+        }
+
+        let mut spends = self.spends.lock().unwrap();
+        spends.retain(|_, (sent_at, _)| sent_at.elapsed() < self.limit.window);
+        let new_txs: Vec<_> = txs
+            .into_iter()
+            .filter(|(hash, _)| !spends.contains_key(hash))
+            .collect();
+        if let Some(limit) = self.limit.max_per_window {
+            let spend = spends
+                .values()
+                .map(|(_, spend)| *spend)
+                .chain(new_txs.iter().map(|(_, spend)| *spend))
+                .fold(U256::zero(), U256::saturating_add);
+            if spend > limit {
+                return Err((spend, limit));
+            }
+        }
+
+        let now = Instant::now();
+        let hashes = new_txs.iter().map(|(hash, _)| *hash).collect();
+        spends.extend(
+            new_txs
+                .into_iter()
+                .map(|(hash, spend)| (hash, (now, spend))),
+        );
+        Ok(SpendReservation {
+            guard: self,
+            hashes,
+            committed: false,
+        })
+    }
+}
+
+/// The spend of a bundle that is being sent, see [`SpendGuard::reserve`].
+///
+/// The spend is released when the reservation is dropped, unless it was
+/// committed because the bundle was sent.
+#[derive(Debug)]
+pub(crate) struct SpendReservation<'a> {
+    guard: &'a SpendGuard,
+    hashes: Vec<TxHash>,
+    committed: bool,
+}
+
+impl SpendReservation<'_> {
+    /// Keep the reserved spend, because the bundle was sent.
+    pub(crate) fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for SpendReservation<'_> {
+    fn drop(&mut self) {
+        if self.committed || self.hashes.is_empty() {
+            return;
+        }
+        let mut spends = self.guard.spends.lock().unwrap();
+        for hash in &self.hashes {
+            spends.remove(hash);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::types::Transaction;
+
+    fn tx(from: Address, value: u64) -> Transaction {
+        Transaction {
+            from,
+            gas: 10.into(),
+            gas_price: Some(2.into()),
+            value: value.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn spend_limit() {
+        let (sender, other) = (Address::repeat_byte(0x1), Address::repeat_byte(0x2));
+        let guard = SpendGuard::new(
+            SpendLimit::new(vec![sender])
+                .set_max_per_bundle(100.into())
+                .set_max_per_window(150.into(), Duration::from_secs(60)),
+        );
+
+        // Transactions from other senders are not counted
+        let bundle = BundleRequest::new()
+            .push_transaction(tx(sender, 60))
+            .push_transaction(tx(other, 1000));
+        guard.reserve(&bundle).unwrap().commit();
+        // Resubmitted transactions are only counted once
+        guard.reserve(&bundle).unwrap().commit();
+
+        let bundle = BundleRequest::new().push_transaction(tx(sender, 61));
+        assert_eq!(
+            guard.reserve(&bundle).unwrap_err(),
+            (161.into(), 150.into())
+        );
+
+        let bundle = BundleRequest::new()
+            .push_transaction(tx(sender, 50))
+            .push_transaction(tx(sender, 51));
+        assert_eq!(
+            guard.reserve(&bundle).unwrap_err(),
+            (141.into(), 100.into())
+        );
+    }
+
+    #[test]
+    fn released_spend() {
+        let sender = Address::repeat_byte(0x1);
+        let guard = SpendGuard::new(
+            SpendLimit::new(vec![sender]).set_max_per_window(100.into(), Duration::from_secs(60)),
+        );
+        let bundle = BundleRequest::new().push_transaction(tx(sender, 60));
+        let other = BundleRequest::new().push_transaction(tx(sender, 61));
+
+        // Reserved spend counts towards the limit until it is released
+        let reservation = guard.reserve(&bundle).unwrap();
+        assert_eq!(guard.reserve(&other).unwrap_err(), (161.into(), 100.into()));
+        drop(reservation);
+        guard.reserve(&other).unwrap().commit();
+        assert_eq!(
+            guard.reserve(&bundle).unwrap_err(),
+            (161.into(), 100.into())
+        );
+    }
+}
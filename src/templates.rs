@@ -0,0 +1,99 @@
+//! Templates for common bundle shapes.
+//!
+//! Each template returns a bundle targeting the given block, which can be
+//! customized further before it is sent (e.g. by setting simulation
+//! parameters or a replacement uuid).
+//!
+//! ```
+//! use ethers::core::types::Bytes;
+//! use ethers_flashbots::templates;
+//!
+//! # let (approval, swap) = (Bytes::from(vec![0x1]), Bytes::from(vec![0x2]));
+//! let bundle = templates::approve_and_swap(approval, swap, 17_000_000.into())
+//!     .set_min_timestamp(1_700_000_000)
+//!     .set_max_timestamp(1_700_000_060);
+//! assert_eq!(bundle.transactions().len(), 2);
+//! ```
+use crate::{
+    bundle::{BundleRequest, BundleTransaction},
+    mev_share::MevShareBundle,
+};
+use ethers::core::types::{TxHash, U64};
+
+/// A bundle with a single transaction, which keeps the transaction out of
+/// the public mempool.
+pub fn private_send<T: Into<BundleTransaction>>(tx: T, block: U64) -> BundleRequest {
+    BundleRequest::new().push_transaction(tx).set_block(block)
+}
+
+/// A MEV-Share bundle that backruns the transaction with the given hash
+/// with your transaction.
+///
+/// See [`MevShareBundle::backrun`].
+pub fn backrun<T: Into<BundleTransaction>>(
+    target_tx_hash: TxHash,
+    tx: T,
+    block: U64,
+) -> MevShareBundle {
+    MevShareBundle::backrun(target_tx_hash, tx, block)
+}
+
+/// A bundle with a token approval followed by a swap that spends the
+/// approved tokens.
+///
+/// Neither transaction may revert, so the approval is never included
+/// without the swap.
+pub fn approve_and_swap<A, T>(approval: A, swap: T, block: U64) -> BundleRequest
+where
+    A: Into<BundleTransaction>,
+    T: Into<BundleTransaction>,
+{
+    BundleRequest::new()
+        .push_transaction(approval)
+        .push_transaction(swap)
+        .set_block(block)
+}
+
+/// A bundle with legs that must all succeed, followed by a tail of
+/// transactions that may revert.
+///
+/// This suits e.g. an arbitrage whose profit is swept by the tail, or
+/// opportunistic follow-up transactions that should not invalidate the
+/// bundle.
+pub fn multi_leg<L, T>(
+    legs: impl IntoIterator<Item = L>,
+    tail: impl IntoIterator<Item = T>,
+    block: U64,
+) -> BundleRequest
+where
+    L: Into<BundleTransaction>,
+    T: Into<BundleTransaction>,
+{
+    let mut bundle = BundleRequest::new().set_block(block);
+    for tx in legs {
+        bundle.add_transaction(tx);
+    }
+    for tx in tail {
+        bundle.add_revertible_transaction(tx);
+    }
+    bundle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::types::Bytes;
+
+    #[test]
+    fn multi_leg_bundle() {
+        let bundle = multi_leg(
+            [Bytes::from(vec![0x1]), Bytes::from(vec![0x2])],
+            [Bytes::from(vec![0x3])],
+            10.into(),
+        );
+        let hashes = bundle.transaction_hashes();
+        assert_eq!(hashes.len(), 3);
+        assert_eq!(bundle.revertible_transaction_hashes(), &vec![hashes[2]]);
+        assert_eq!(bundle.block(), Some(10.into()));
+    }
+}
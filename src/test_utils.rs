@@ -0,0 +1,404 @@
+//! Utilities for end-to-end tests of searcher strategies.
+//!
+//! This module is only available with the `test-utils` feature. It
+//! provides a [`FakeRelay`] that records the requests sent to it, and a
+//! [`TestHarness`] that runs an [anvil](https://github.com/foundry-rs/foundry)
+//! node together with a fake relay, so strategies can be tested without
+//! sending bundles to real relays.
+//!
+//! ```no_run
+//! use ethers::signers::{LocalWallet, Signer};
+//! use ethers_flashbots::{test_utils::TestHarness, BundleRequest};
+//!
+//! # async fn test() -> Result<(), Box<dyn std::error::Error>> {
+//! let harness = TestHarness::spawn().await?;
+//! let bundle_signer: LocalWallet = harness.anvil().keys()[0].clone().into();
+//! let client = harness.middleware(bundle_signer.clone());
+//!
+//! // Run your strategy...
+//! # let bundle = BundleRequest::new().set_block(1.into());
+//! client.send_bundle(&bundle).await?;
+//!
+//! let request = &harness.relay().requests_for("eth_sendBundle")[0];
+//! assert_eq!(request.signer(), Some(bundle_signer.address()));
+//! assert_eq!(request.bundle().unwrap()["blockNumber"], "0x1");
+//! # Ok(())
+//! # }
+//! ```
+use crate::{jsonrpc::JsonRpcError, FlashbotsMiddleware};
+use ethers::{
+    core::{
+        types::{Address, Signature, H256},
+        utils::{keccak256, Anvil, AnvilInstance},
+    },
+    providers::{Http, Provider},
+    signers::Signer,
+};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    io,
+    panic::{self, AssertUnwindSafe},
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use url::Url;
+
+/// A request received by a [`FakeRelay`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    /// The JSON-RPC method of the request.
+    pub method: String,
+    /// The JSON-RPC request.
+    pub request: Value,
+    /// The raw body of the request.
+    pub body: String,
+    /// The headers of the request, with lowercase names.
+    pub headers: Vec<(String, String)>,
+}
+
+impl RecordedRequest {
+    /// Get the value of the header with the given name (if any).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get the parameters of the request.
+    pub fn params(&self) -> &Value {
+        &self.request["params"]
+    }
+
+    /// Get the first parameter of the request, e.g. the bundle of an
+    /// `eth_sendBundle` request.
+    pub fn bundle(&self) -> Option<&Value> {
+        self.params().get(0)
+    }
+
+    /// Get the address that signed the request, if the request has a
+    /// valid Flashbots signature header.
+    pub fn signer(&self) -> Option<Address> {
+        let (address, signature) = self.header("x-flashbots-signature")?.split_once(':')?;
+        let address = Address::from_str(address).ok()?;
+        let signature = Signature::from_str(signature.trim_start_matches("0x")).ok()?;
+
+        // The signature is over the hash of the exact request body
+        let message = format!("0x{:x}", H256::from(keccak256(self.body.as_bytes())));
+        match signature.recover(message) {
+            Ok(signer) if signer == address => Some(signer),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct FakeRelayState {
+    requests: Vec<RecordedRequest>,
+    responses: HashMap<String, Result<Value, JsonRpcError>>,
+}
+
+/// A fake relay HTTP server that records requests.
+///
+/// By default, the relay responds to `eth_sendBundle` and `mev_sendBundle`
+/// requests with a zero bundle hash, and to other requests with a `null`
+/// result. Responses can be set per method using [`FakeRelay::set_result`]
+/// and [`FakeRelay::set_error`].
+///
+/// The server is stopped when the Tokio runtime shuts down.
+#[derive(Debug, Clone)]
+pub struct FakeRelay {
+    url: Url,
+    state: Arc<Mutex<FakeRelayState>>,
+}
+
+impl FakeRelay {
+    /// Starts a fake relay on a random local port.
+    pub async fn spawn() -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = Url::parse(&format!("http://{}", listener.local_addr()?))
+            .expect("local addresses are valid URLs");
+        let state = Arc::new(Mutex::new(FakeRelayState::default()));
+
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = server_state.clone();
+                tokio::spawn(async move {
+                    // Errors only affect the connection of the request
+                    let _ = handle_connection(stream, &state).await;
+                });
+            }
+        });
+
+        Ok(Self { url, state })
+    }
+
+    /// Get the URL of the relay.
+    pub fn url(&self) -> Url {
+        self.url.clone()
+    }
+
+    /// Get all requests received by the relay, in the order they were
+    /// received.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Get all requests with the given method received by the relay.
+    pub fn requests_for(&self, method: &str) -> Vec<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|request| request.method == method)
+            .collect()
+    }
+
+    /// Clear the received requests.
+    pub fn clear_requests(&self) {
+        self.state.lock().unwrap().requests.clear();
+    }
+
+    /// Set the result returned for requests with the given method.
+    pub fn set_result(&self, method: impl Into<String>, result: Value) {
+        self.state
+            .lock()
+            .unwrap()
+            .responses
+            .insert(method.into(), Ok(result));
+    }
+
+    /// Set the JSON-RPC error returned for requests with the given
+    /// method.
+    pub fn set_error(&self, method: impl Into<String>, error: JsonRpcError) {
+        self.state
+            .lock()
+            .unwrap()
+            .responses
+            .insert(method.into(), Err(error));
+    }
+}
+
+/// Reads a single HTTP request from the stream, records it and responds.
+async fn handle_connection(
+    stream: TcpStream,
+    state: &Mutex<FakeRelayState>,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    // Skip the request line
+    let mut line = String::new();
+    reader.read_line(&mut line).await?;
+
+    let mut headers = Vec::new();
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        reader.read_line(&mut line).await?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let name = name.trim().to_ascii_lowercase();
+            let value = value.trim().to_string();
+            if name == "content-length" {
+                content_length = value.parse().unwrap_or_default();
+            }
+            headers.push((name, value));
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+    let body = String::from_utf8_lossy(&body).into_owned();
+    let request: Value = serde_json::from_str(&body).unwrap_or(Value::Null);
+    let method = request["method"].as_str().unwrap_or_default().to_string();
+
+    let response = {
+        let mut state = state.lock().unwrap();
+        let mut response = match state.responses.get(&method) {
+            Some(Ok(result)) => json!({ "result": result }),
+            Some(Err(error)) => json!({ "error": error }),
+            None => match method.as_str() {
+                "eth_sendBundle" | "mev_sendBundle" => {
+                    json!({ "result": { "bundleHash": H256::zero() } })
+                }
+                _ => json!({ "result": null }),
+            },
+        };
+        response["id"] = request["id"].clone();
+        response["jsonrpc"] = "2.0".into();
+
+        state.requests.push(RecordedRequest {
+            method,
+            request,
+            body,
+            headers,
+        });
+        response.to_string()
+    };
+
+    let stream = reader.get_mut();
+    stream
+        .write_all(
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .as_bytes(),
+        )
+        .await?;
+    stream.shutdown().await
+}
+
+/// An anvil node and a fake relay for end-to-end tests.
+///
+/// Requires [anvil](https://github.com/foundry-rs/foundry) to be
+/// installed. The anvil node is stopped when the harness is dropped.
+pub struct TestHarness {
+    anvil: AnvilInstance,
+    provider: Provider<Http>,
+    relay: FakeRelay,
+}
+
+impl std::fmt::Debug for TestHarness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestHarness")
+            .field("anvil", &self.anvil.endpoint())
+            .field("provider", &self.provider)
+            .field("relay", &self.relay)
+            .finish()
+    }
+}
+
+impl TestHarness {
+    /// Starts an anvil node and a fake relay.
+    pub async fn spawn() -> std::io::Result<Self> {
+        Self::spawn_with(Anvil::new()).await
+    }
+
+    /// Starts the given anvil node and a fake relay.
+    ///
+    /// This can be used to e.g. fork a network. Returns an error if the
+    /// anvil node could not be started, e.g. because anvil is not
+    /// installed.
+    pub async fn spawn_with(anvil: Anvil) -> std::io::Result<Self> {
+        let relay = FakeRelay::spawn().await?;
+        // Spawning anvil blocks until the node is listening, and panics if
+        // it fails to start
+        let anvil = tokio::task::spawn_blocking(move || {
+            panic::catch_unwind(AssertUnwindSafe(|| anvil.spawn())).map_err(|err| {
+                let message = err
+                    .downcast_ref::<String>()
+                    .map(String::as_str)
+                    .or_else(|| err.downcast_ref::<&str>().copied())
+                    .unwrap_or("unknown error");
+                io::Error::other(format!("could not start anvil: {}", message))
+            })
+        })
+        .await
+        .map_err(io::Error::other)??;
+        let provider =
+            Provider::<Http>::try_from(anvil.endpoint()).expect("anvil endpoints are valid URLs");
+
+        Ok(Self {
+            anvil,
+            provider,
+            relay,
+        })
+    }
+
+    /// Get the anvil node, e.g. to get its funded keys.
+    pub fn anvil(&self) -> &AnvilInstance {
+        &self.anvil
+    }
+
+    /// Get a provider connected to the anvil node.
+    pub fn provider(&self) -> Provider<Http> {
+        self.provider.clone()
+    }
+
+    /// Get the fake relay.
+    pub fn relay(&self) -> &FakeRelay {
+        &self.relay
+    }
+
+    /// Creates a Flashbots middleware on top of the anvil node, sending
+    /// bundles to the fake relay.
+    pub fn middleware<S: Signer>(&self, signer: S) -> FlashbotsMiddleware<Provider<Http>, S> {
+        FlashbotsMiddleware::new(self.provider(), self.relay.url(), signer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::wallet;
+    use crate::{BundleRequest, Relay, RelayError};
+    use ethers::{core::types::U64, signers::LocalWallet};
+
+    #[tokio::test]
+    async fn fake_relay() {
+        let relay = FakeRelay::spawn().await.unwrap();
+        let signer = wallet();
+        let client = Relay::new(relay.url(), Some(signer.clone()));
+
+        let bundle = BundleRequest::new().set_block(10.into());
+        let response: Option<Value> = client.request("eth_sendBundle", [&bundle]).await.unwrap();
+        assert_eq!(response, Some(json!({ "bundleHash": H256::zero() })));
+
+        relay.set_result("eth_blockNumber", json!("0x1"));
+        let block: Option<U64> = client.request("eth_blockNumber", ()).await.unwrap();
+        assert_eq!(block, Some(1.into()));
+
+        relay.set_error(
+            "eth_callBundle",
+            JsonRpcError {
+                code: JsonRpcError::INVALID_PARAMS,
+                message: "missing simulation block".to_string(),
+                data: None,
+            },
+        );
+        let err = client
+            .request::<_, Value>("eth_callBundle", [&bundle])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RelayError::JsonRpcError { .. }));
+
+        let requests = relay.requests_for("eth_sendBundle");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].signer(), Some(signer.address()));
+        assert_eq!(requests[0].bundle().unwrap()["blockNumber"], "0xa");
+        assert_eq!(relay.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn harness_without_anvil() {
+        let err = TestHarness::spawn_with(Anvil::at("/nonexistent/anvil"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("could not start anvil"));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires anvil"]
+    async fn harness() {
+        let harness = TestHarness::spawn().await.unwrap();
+        let bundle_signer: LocalWallet = harness.anvil().keys()[0].clone().into();
+        let client = harness.middleware(bundle_signer.clone());
+
+        let bundle = BundleRequest::new().set_block(1.into());
+        client.send_bundle(&bundle).await.unwrap();
+
+        let requests = harness.relay().requests_for("eth_sendBundle");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].signer(), Some(bundle_signer.address()));
+    }
+}
@@ -0,0 +1,302 @@
+use crate::{
+    error::ErrorKind,
+    transport::{HttpTransport, TransportError},
+    utils::{deserialize_u256, deserialize_u64},
+};
+use ethers::core::types::{U256, U64};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use thiserror::Error;
+use url::Url;
+
+/// The URL of the Flashbots blocks API, which lists the bundles included
+/// in recent mainnet blocks.
+pub const FLASHBOTS_BLOCKS_API_URL: &str = "https://blocks.flashbots.net";
+
+/// The number of recent blocks considered when suggesting a tip.
+const TIP_HISTORY_BLOCKS: u64 = 100;
+
+/// Errors for tip suggestions.
+#[derive(Error, Debug)]
+pub enum TipError {
+    /// The percentile is not between 0 and 100.
+    #[error("Invalid percentile {0}, expected a value between 0 and 100")]
+    InvalidPercentile(f64),
+    /// The request to the blocks API failed in the transport.
+    #[error(transparent)]
+    TransportError(TransportError),
+    /// The blocks API responded with an error status.
+    #[error("Blocks API responded with status {status}: {text}")]
+    StatusError {
+        /// The status code of the response.
+        status: u16,
+        text: String,
+    },
+    /// The response of the blocks API could not be deserialized.
+    #[error("Deserialization error: {err}. Response: {text}")]
+    ResponseSerdeJson {
+        err: serde_json::Error,
+        text: String,
+    },
+    /// No recently included bundle used a comparable amount of gas.
+    #[error("No recently included bundle used a comparable amount of gas")]
+    NoComparableBundles,
+}
+
+impl TipError {
+    /// Get the kind of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            TipError::InvalidPercentile(_) => ErrorKind::InvalidRequest,
+            TipError::TransportError(_) => ErrorKind::Network,
+            TipError::StatusError { status, .. } if *status >= 500 => ErrorKind::Server,
+            TipError::StatusError { .. } | TipError::ResponseSerdeJson { .. } => {
+                ErrorKind::InvalidResponse
+            }
+            TipError::NoComparableBundles => ErrorKind::Unavailable,
+        }
+    }
+}
+
+/// A bundle that was included in a recent block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IncludedBundle {
+    /// The block the bundle was included in.
+    pub block: U64,
+    /// The gas used by the transactions of the bundle.
+    pub gas_used: U256,
+    /// The effective gas price of the bundle, including coinbase
+    /// payments, i.e. the total paid to the block builder divided by the
+    /// gas used.
+    pub gas_price: U256,
+}
+
+/// A suggested coinbase payment for a bundle, based on the gas prices of
+/// recently included bundles.
+///
+/// See [`FlashbotsMiddleware::suggest_tip`](crate::FlashbotsMiddleware::suggest_tip).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TipSuggestion {
+    /// The percentile of the gas prices of comparable bundles the
+    /// suggestion is based on.
+    pub percentile: f64,
+    /// The suggested effective gas price, i.e. the percentile of the gas
+    /// prices of comparable bundles.
+    pub gas_price: U256,
+    /// The amount of gas the suggestion was computed for.
+    pub gas_used: U256,
+    /// The suggested total payment to the coinbase,
+    /// i.e. `gas_price * gas_used`.
+    pub payment: U256,
+    /// The number of comparable bundles the suggestion is based on.
+    pub samples: usize,
+}
+
+impl TipSuggestion {
+    /// Computes a tip suggestion from the given included bundles, using the
+    /// given percentile (0-100) of the gas prices of the bundles with a
+    /// comparable gas usage.
+    ///
+    /// Bundles are comparable if they used between half and twice the
+    /// given amount of gas. Returns `None` if no bundle is comparable.
+    pub fn from_included_bundles(
+        bundles: &[IncludedBundle],
+        gas_used: U256,
+        percentile: f64,
+    ) -> Option<Self> {
+        let mut gas_prices: Vec<U256> = bundles
+            .iter()
+            .filter(|bundle| bundle.gas_used * 2 >= gas_used && bundle.gas_used <= gas_used * 2)
+            .map(|bundle| bundle.gas_price)
+            .collect();
+        if gas_prices.is_empty() {
+            return None;
+        }
+        gas_prices.sort();
+
+        // The nearest-rank percentile
+        let rank = (percentile / 100.0 * gas_prices.len() as f64).ceil() as usize;
+        let gas_price = gas_prices[rank.clamp(1, gas_prices.len()) - 1];
+
+        Some(Self {
+            percentile,
+            gas_price,
+            gas_used,
+            payment: gas_price * gas_used,
+            samples: gas_prices.len(),
+        })
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct BlocksResponse {
+    blocks: Vec<Block>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Block {
+    transactions: Vec<BlockTransaction>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BlockTransaction {
+    #[serde(deserialize_with = "deserialize_u64")]
+    block_number: U64,
+    bundle_index: u64,
+    bundle_type: String,
+    #[serde(deserialize_with = "deserialize_u256")]
+    gas_used: U256,
+    /// The gas price of the transaction, including its coinbase transfers.
+    #[serde(deserialize_with = "deserialize_u256")]
+    gas_price: U256,
+}
+
+/// Groups the transactions of the blocks API response into bundles.
+///
+/// Miner payouts are listed as bundles by the API, and are ignored.
+fn included_bundles(response: BlocksResponse) -> Vec<IncludedBundle> {
+    let mut bundles = BTreeMap::new();
+    for tx in response
+        .blocks
+        .into_iter()
+        .flat_map(|block| block.transactions)
+    {
+        if tx.bundle_type == "miner_payout" {
+            continue;
+        }
+        let (gas_used, paid) = bundles
+            .entry((tx.block_number, tx.bundle_index))
+            .or_insert((U256::zero(), U256::zero()));
+        *gas_used += tx.gas_used;
+        *paid += tx.gas_price * tx.gas_used;
+    }
+
+    bundles
+        .into_iter()
+        .filter(|(_, (gas_used, _))| !gas_used.is_zero())
+        .map(|((block, _), (gas_used, paid))| IncludedBundle {
+            block,
+            gas_used,
+            gas_price: paid / gas_used,
+        })
+        .collect()
+}
+
+/// Suggests a coinbase payment for a bundle using the given gas, from the
+/// bundles included in recent blocks according to the Flashbots blocks
+/// API.
+pub(crate) async fn suggest_tip(
+    transport: &dyn HttpTransport,
+    gas_used: U256,
+    percentile: f64,
+) -> Result<TipSuggestion, TipError> {
+    if !(0.0..=100.0).contains(&percentile) {
+        return Err(TipError::InvalidPercentile(percentile));
+    }
+
+    let mut url = Url::parse(FLASHBOTS_BLOCKS_API_URL)
+        .and_then(|url| url.join("v1/blocks"))
+        .expect("the blocks API URL is valid");
+    url.query_pairs_mut()
+        .append_pair("limit", &TIP_HISTORY_BLOCKS.to_string());
+
+    let res = transport
+        .get(&url, &[("accept", "application/json")])
+        .await
+        .map_err(TipError::TransportError)?;
+    if !(200..300).contains(&res.status) {
+        return Err(TipError::StatusError {
+            status: res.status,
+            text: res.body,
+        });
+    }
+    let text = res.body;
+    let response: BlocksResponse =
+        serde_json::from_str(&text).map_err(|err| TipError::ResponseSerdeJson { err, text })?;
+
+    TipSuggestion::from_included_bundles(&included_bundles(response), gas_used, percentile)
+        .ok_or(TipError::NoComparableBundles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::MockTransport;
+    use serde_json::json;
+
+    fn bundle(gas_used: u64, gas_price: u64) -> IncludedBundle {
+        IncludedBundle {
+            block: 1.into(),
+            gas_used: gas_used.into(),
+            gas_price: gas_price.into(),
+        }
+    }
+
+    #[test]
+    fn tip_from_included_bundles() {
+        let bundles = vec![
+            bundle(21000, 3_000_000_000),
+            bundle(30000, 1_000_000_000),
+            bundle(40000, 2_000_000_000),
+            // Not comparable
+            bundle(500000, 100_000_000_000),
+            bundle(10000, 100_000_000_000),
+        ];
+
+        let tip = TipSuggestion::from_included_bundles(&bundles, 21000.into(), 50.0).unwrap();
+        assert_eq!(tip.samples, 3);
+        assert_eq!(tip.gas_price, U256::from(2_000_000_000u64));
+        assert_eq!(tip.payment, U256::from(42_000_000_000_000u64));
+
+        let tip = TipSuggestion::from_included_bundles(&bundles, 21000.into(), 0.0).unwrap();
+        assert_eq!(tip.gas_price, U256::from(1_000_000_000u64));
+        let tip = TipSuggestion::from_included_bundles(&bundles, 21000.into(), 100.0).unwrap();
+        assert_eq!(tip.gas_price, U256::from(3_000_000_000u64));
+    }
+
+    #[test]
+    fn tip_without_comparable_bundles() {
+        assert_eq!(
+            TipSuggestion::from_included_bundles(&[bundle(500000, 1)], 21000.into(), 50.0),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn suggest_tip_from_blocks_api() {
+        let transport = MockTransport::new(json!({
+            "blocks": [{
+                "block_number": 17000000,
+                "transactions": [
+                    {"block_number": 17000000, "bundle_index": 0, "bundle_type": "flashbots", "gas_used": 10000, "gas_price": "1000000000"},
+                    {"block_number": 17000000, "bundle_index": 0, "bundle_type": "flashbots", "gas_used": 10000, "gas_price": "3000000000"},
+                    {"block_number": 17000000, "bundle_index": 1, "bundle_type": "miner_payout", "gas_used": 21000, "gas_price": "0"}
+                ]
+            }],
+            "latest_block_number": 17000000
+        }));
+
+        let tip = suggest_tip(transport.as_ref(), 21000.into(), 50.0)
+            .await
+            .unwrap();
+        assert_eq!(tip.samples, 1);
+        assert_eq!(tip.gas_price, U256::from(2_000_000_000u64));
+        assert_eq!(
+            transport.requests()[0].url.as_str(),
+            "https://blocks.flashbots.net/v1/blocks?limit=100"
+        );
+    }
+
+    #[tokio::test]
+    async fn suggest_tip_invalid_percentile() {
+        let transport = MockTransport::new(json!({ "blocks": [] }));
+
+        for percentile in [-1.0, 100.5, f64::NAN] {
+            assert!(matches!(
+                suggest_tip(transport.as_ref(), 21000.into(), percentile).await,
+                Err(TipError::InvalidPercentile(_))
+            ));
+        }
+        assert!(transport.requests().is_empty());
+    }
+}
@@ -0,0 +1,263 @@
+use async_trait::async_trait;
+#[cfg(feature = "reqwest-transport")]
+use reqwest::{Client, Error as ReqwestError};
+use std::{fmt::Debug, sync::Arc};
+use thiserror::Error;
+use url::Url;
+
+/// An error returned by an [`HttpTransport`].
+pub type TransportError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A response to an HTTP request sent by an [`HttpTransport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    /// The status code of the response.
+    pub status: u16,
+    /// The body of the response.
+    pub body: String,
+}
+
+/// The HTTP client used by a [`Relay`](crate::Relay) to send requests.
+///
+/// It is also used for the `GET` requests of the
+/// [`RelayDataClient`](crate::RelayDataClient), for
+/// [webhooks](crate::InclusionHook::webhook) and for other Flashbots APIs.
+///
+/// This is implemented for [`reqwest::Client`], which is used by default,
+/// if the `reqwest-transport` feature is enabled (the default, and implied
+/// by the `openssl` and `rustls` features). Other HTTP stacks can be used
+/// by implementing this trait and setting the transport using
+/// [`Relay::with_transport`](crate::Relay::with_transport) or
+/// [`Relay::set_transport`](crate::Relay::set_transport).
+///
+/// Transports should return an error if the request could not be sent or
+/// the response could not be read, and return the response otherwise,
+/// regardless of its status code.
+#[async_trait]
+pub trait HttpTransport: Debug + Send + Sync {
+    /// Send a `POST` request with the given headers and body.
+    async fn post(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+        body: String,
+    ) -> Result<HttpResponse, TransportError>;
+
+    /// Send a `POST` request with the given headers and body, failing with
+    /// [`ResponseTooLarge`] if the response body exceeds the given size in
+    /// bytes.
+    ///
+    /// By default, the response is read in full using
+    /// [`HttpTransport::post`] before its size is checked. Transports
+    /// should override this to stop reading oversized responses early.
+    async fn post_with_limit(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+        body: String,
+        max_response_size: usize,
+    ) -> Result<HttpResponse, TransportError> {
+        let res = self.post(url, headers, body).await?;
+        if res.body.len() > max_response_size {
+            return Err(Box::new(ResponseTooLarge {
+                limit: max_response_size,
+            }));
+        }
+        Ok(res)
+    }
+
+    /// Send a `GET` request with the given headers.
+    async fn get(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+    ) -> Result<HttpResponse, TransportError>;
+}
+
+/// The response body exceeded the maximum size.
+///
+/// See [`HttpTransport::post_with_limit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("The response exceeds the maximum size of {limit} bytes")]
+pub struct ResponseTooLarge {
+    /// The maximum size of the response body, in bytes.
+    pub limit: usize,
+}
+
+#[cfg(feature = "reqwest-transport")]
+#[async_trait]
+impl HttpTransport for Client {
+    async fn post(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+        body: String,
+    ) -> Result<HttpResponse, TransportError> {
+        self.post_with_limit(url, headers, body, usize::MAX).await
+    }
+
+    async fn post_with_limit(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+        body: String,
+        max_response_size: usize,
+    ) -> Result<HttpResponse, TransportError> {
+        let mut req = Client::post(self, url.as_ref()).body(body);
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+
+        let mut res = req.send().await?;
+        let too_large = || {
+            Box::new(ResponseTooLarge {
+                limit: max_response_size,
+            })
+        };
+        if matches!(res.content_length(), Some(len) if len > max_response_size as u64) {
+            return Err(too_large());
+        }
+
+        // The body is read in chunks, so oversized responses without a
+        // content length are not buffered in full
+        let status = res.status().as_u16();
+        let mut body = Vec::new();
+        while let Some(chunk) = res.chunk().await? {
+            if body.len() + chunk.len() > max_response_size {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let body = String::from_utf8_lossy(&body).into_owned();
+        Ok(HttpResponse { status, body })
+    }
+
+    async fn get(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+    ) -> Result<HttpResponse, TransportError> {
+        let mut req = Client::get(self, url.as_ref());
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+
+        let res = req.send().await?;
+        let status = res.status().as_u16();
+        let body = res.text().await?;
+        Ok(HttpResponse { status, body })
+    }
+}
+
+#[async_trait]
+impl<T: HttpTransport + ?Sized> HttpTransport for Arc<T> {
+    async fn post(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+        body: String,
+    ) -> Result<HttpResponse, TransportError> {
+        (**self).post(url, headers, body).await
+    }
+
+    async fn post_with_limit(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+        body: String,
+        max_response_size: usize,
+    ) -> Result<HttpResponse, TransportError> {
+        (**self)
+            .post_with_limit(url, headers, body, max_response_size)
+            .await
+    }
+
+    async fn get(
+        &self,
+        url: &Url,
+        headers: &[(&str, &str)],
+    ) -> Result<HttpResponse, TransportError> {
+        (**self).get(url, headers).await
+    }
+}
+
+/// The transport used when the `reqwest-transport` feature is disabled and
+/// no transport has been set.
+#[cfg(not(feature = "reqwest-transport"))]
+#[derive(Debug)]
+struct MissingTransport;
+
+/// No HTTP transport has been set for the relay.
+///
+/// This is returned for every request of relays without a transport when
+/// the `reqwest-transport` feature is disabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("No HTTP transport is set for the relay")]
+pub struct NoTransport;
+
+#[cfg(not(feature = "reqwest-transport"))]
+#[async_trait]
+impl HttpTransport for MissingTransport {
+    async fn post(
+        &self,
+        _url: &Url,
+        _headers: &[(&str, &str)],
+        _body: String,
+    ) -> Result<HttpResponse, TransportError> {
+        Err(Box::new(NoTransport))
+    }
+
+    async fn get(
+        &self,
+        _url: &Url,
+        _headers: &[(&str, &str)],
+    ) -> Result<HttpResponse, TransportError> {
+        Err(Box::new(NoTransport))
+    }
+}
+
+/// The transport used by relays unless another transport is set.
+pub(crate) fn default_transport() -> Arc<dyn HttpTransport> {
+    #[cfg(feature = "reqwest-transport")]
+    return Arc::new(Client::new());
+    #[cfg(not(feature = "reqwest-transport"))]
+    return Arc::new(MissingTransport);
+}
+
+/// Converts a transport error into a `reqwest` error, if it is one.
+#[cfg(feature = "reqwest-transport")]
+pub(crate) fn into_reqwest_error(err: TransportError) -> Result<ReqwestError, TransportError> {
+    err.downcast::<ReqwestError>().map(|err| *err)
+}
+
+#[cfg(all(test, feature = "reqwest-transport"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::{json_response, serve};
+
+    #[tokio::test]
+    async fn server_errors_are_responses() {
+        let (url, _) = serve(vec![json_response("503 Service Unavailable", "busy")]).await;
+
+        let res = HttpTransport::post(&Client::new(), &url, &[], String::new())
+            .await
+            .unwrap();
+        assert_eq!(res.status, 503);
+        assert_eq!(res.body, "busy");
+    }
+
+    #[tokio::test]
+    async fn get_request() {
+        let (url, requests) = serve(vec![json_response("200 OK", "[]")]).await;
+
+        let res = HttpTransport::get(&Client::new(), &url, &[("accept", "application/json")])
+            .await
+            .unwrap();
+        assert_eq!(res.status, 200);
+        assert_eq!(res.body, "[]");
+
+        let request = requests.await.unwrap().remove(0);
+        assert!(request.starts_with("GET / "));
+        assert!(request.contains("accept: application/json"));
+    }
+}
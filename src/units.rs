@@ -0,0 +1,70 @@
+use ethers::core::{
+    types::{I256, U256},
+    utils::{format_units, ParseUnits},
+};
+
+/// Human-readable formatting of wei amounts.
+///
+/// This is implemented for the amounts in simulation results and stats,
+/// e.g. [`SimulatedBundle::coinbase_diff`](crate::SimulatedBundle::coinbase_diff)
+/// and [`SimulatedBundle::profit`](crate::SimulatedBundle::profit).
+///
+/// Amounts are formatted exactly, without rounding, and trailing zeros
+/// are removed.
+pub trait WeiExt {
+    /// Format the amount in gwei, e.g. `1.5` for `1500000000` wei.
+    fn as_gwei(&self) -> String;
+
+    /// Format the amount in ether with a unit, e.g. `0.02 ETH` for
+    /// `20000000000000000` wei.
+    fn format_eth(&self) -> String;
+}
+
+fn format_trimmed(amount: impl Into<ParseUnits>, units: &str) -> String {
+    // Both gwei and ether have few enough decimals to never overflow.
+    let formatted = format_units(amount, units).expect("units are valid");
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+impl WeiExt for U256 {
+    fn as_gwei(&self) -> String {
+        format_trimmed(*self, "gwei")
+    }
+
+    fn format_eth(&self) -> String {
+        format!("{} ETH", format_trimmed(*self, "ether"))
+    }
+}
+
+impl WeiExt for I256 {
+    fn as_gwei(&self) -> String {
+        format_trimmed(*self, "gwei")
+    }
+
+    fn format_eth(&self) -> String {
+        format!("{} ETH", format_trimmed(*self, "ether"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_wei() {
+        assert_eq!(U256::from(1500000000u64).as_gwei(), "1.5");
+        assert_eq!(U256::from(476190476193u64).as_gwei(), "476.190476193");
+        assert_eq!(U256::from(2000000000u64).as_gwei(), "2");
+        assert_eq!(U256::zero().as_gwei(), "0");
+        assert_eq!(U256::from(20000000000000000u64).format_eth(), "0.02 ETH");
+        assert_eq!(
+            U256::from(20000000000126000u64).format_eth(),
+            "0.020000000000126 ETH"
+        );
+        assert_eq!(I256::from(-1500000000i64).as_gwei(), "-1.5");
+        assert_eq!(I256::from(-20000000000000000i64).format_eth(), "-0.02 ETH");
+    }
+}
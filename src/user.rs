@@ -3,7 +3,7 @@ use ethers::core::types::U256;
 use serde::Deserialize;
 
 /// Represents stats for a searcher.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct UserStats {
     /// Whether the searcher is high priority or not.
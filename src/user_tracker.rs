@@ -0,0 +1,248 @@
+use crate::{
+    middleware::{FlashbotsMiddleware, FlashbotsMiddlewareError},
+    user::UserStats,
+};
+use chrono::{DateTime, Utc};
+use ethers::{core::types::U256, providers::Middleware, signers::Signer};
+use std::{collections::VecDeque, time::Duration};
+
+/// The default window of samples kept by a [`UserStatsTracker`].
+const DEFAULT_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The default interval between samples of a [`UserStatsTracker`].
+const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A tracker of the stats of your searcher identity over time.
+///
+/// The tracker periodically samples
+/// [`FlashbotsMiddleware::get_user_stats`] and keeps the samples within a
+/// rolling window (24 hours by default), so the growth of your reputation
+/// can be measured, e.g. as validator payments per hour.
+///
+/// ```no_run
+/// # use ethers::{providers::{Provider, Http}, signers::LocalWallet};
+/// # use ethers_flashbots::{FlashbotsMiddleware, UserStatsTracker, WeiExt};
+/// # async fn track(client: FlashbotsMiddleware<Provider<Http>, LocalWallet>) -> Result<(), Box<dyn std::error::Error>> {
+/// let mut tracker = UserStatsTracker::new();
+/// tracker
+///     .run(&client, |tracker| {
+///         if let Some(delta) = tracker.delta() {
+///             println!(
+///                 "{} per hour",
+///                 delta.validator_payments_per_hour().format_eth()
+///             );
+///         }
+///     })
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct UserStatsTracker {
+    window: Duration,
+    interval: Duration,
+    samples: VecDeque<(DateTime<Utc>, UserStats)>,
+}
+
+/// The change in the stats of a searcher between two samples.
+///
+/// See [`UserStatsTracker::delta`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserStatsDelta {
+    /// The time between the samples.
+    pub duration: Duration,
+    /// The payments made to validators between the samples.
+    pub validator_payments: U256,
+    /// The gas simulated in bundles between the samples.
+    pub gas_simulated: U256,
+}
+
+impl UserStatsDelta {
+    /// Get the validator payments per hour.
+    pub fn validator_payments_per_hour(&self) -> U256 {
+        self.rate(self.validator_payments, HOUR)
+    }
+
+    /// Get the validator payments per day.
+    pub fn validator_payments_per_day(&self) -> U256 {
+        self.rate(self.validator_payments, DAY)
+    }
+
+    /// Get the gas simulated per day.
+    pub fn gas_simulated_per_day(&self) -> U256 {
+        self.rate(self.gas_simulated, DAY)
+    }
+
+    /// Scales an amount over the duration of the delta to the given period.
+    fn rate(&self, amount: U256, period: Duration) -> U256 {
+        let duration = self.duration.as_millis();
+        if duration == 0 {
+            return U256::zero();
+        }
+        amount.saturating_mul(period.as_millis().into()) / duration
+    }
+}
+
+impl Default for UserStatsTracker {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_WINDOW,
+            interval: DEFAULT_SAMPLE_INTERVAL,
+            samples: VecDeque::new(),
+        }
+    }
+}
+
+impl UserStatsTracker {
+    /// Creates a tracker with a window of 24 hours, sampling every 5
+    /// minutes.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get the window of samples kept by the tracker.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Set the window of samples kept by the tracker.
+    pub fn set_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Get the interval between samples in [`UserStatsTracker::run`].
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Set the interval between samples in [`UserStatsTracker::run`].
+    pub fn set_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Get the samples within the window, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = &(DateTime<Utc>, UserStats)> {
+        self.samples.iter()
+    }
+
+    /// Get the most recent stats (if any).
+    pub fn latest(&self) -> Option<&UserStats> {
+        self.samples.back().map(|(_, stats)| stats)
+    }
+
+    /// Record stats sampled at the given time.
+    ///
+    /// Samples that fall outside of the window of the newest sample are
+    /// dropped.
+    pub fn record(&mut self, time: DateTime<Utc>, stats: UserStats) {
+        self.samples.push_back((time, stats));
+
+        let newest = self.samples.back().map(|(time, _)| *time).unwrap_or(time);
+        while let Some((oldest, _)) = self.samples.front() {
+            match (newest - *oldest).to_std() {
+                Ok(age) if age > self.window => self.samples.pop_front(),
+                _ => break,
+            };
+        }
+    }
+
+    /// Get the change in stats between the oldest and the newest sample
+    /// in the window.
+    ///
+    /// Returns `None` if there are less than two samples.
+    pub fn delta(&self) -> Option<UserStatsDelta> {
+        if self.samples.len() < 2 {
+            return None;
+        }
+        let (start, first) = self.samples.front()?;
+        let (end, last) = self.samples.back()?;
+
+        Some(UserStatsDelta {
+            duration: (*end - *start).to_std().unwrap_or_default(),
+            validator_payments: last
+                .all_time_validator_payments
+                .saturating_sub(first.all_time_validator_payments),
+            gas_simulated: last
+                .all_time_gas_simulated
+                .saturating_sub(first.all_time_gas_simulated),
+        })
+    }
+
+    /// Sample the stats of the searcher identity of the middleware.
+    pub async fn sample<M: Middleware, S: Signer>(
+        &mut self,
+        middleware: &FlashbotsMiddleware<M, S>,
+    ) -> Result<&UserStats, FlashbotsMiddlewareError<M, S>> {
+        let stats = middleware.get_user_stats().await?;
+        self.record(Utc::now(), stats);
+        Ok(self.latest().expect("a sample was just recorded"))
+    }
+
+    /// Sample the stats of the searcher identity of the middleware at the
+    /// sample interval, calling `on_sample` after each sample.
+    ///
+    /// This only returns if sampling fails.
+    pub async fn run<M: Middleware, S: Signer>(
+        &mut self,
+        middleware: &FlashbotsMiddleware<M, S>,
+        mut on_sample: impl FnMut(&Self),
+    ) -> Result<(), FlashbotsMiddlewareError<M, S>> {
+        loop {
+            self.sample(middleware).await?;
+            on_sample(self);
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn stats(payments: u64, gas: u64) -> UserStats {
+        UserStats {
+            is_high_priority: true,
+            all_time_validator_payments: payments.into(),
+            all_time_gas_simulated: gas.into(),
+            last_7d_validator_payments: U256::zero(),
+            last_7d_gas_simulated: U256::zero(),
+            last_1d_validator_payments: U256::zero(),
+            last_1d_gas_simulated: U256::zero(),
+        }
+    }
+
+    #[test]
+    fn user_stats_delta() {
+        let start = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let mut tracker = UserStatsTracker::new().set_window(Duration::from_secs(3 * 60 * 60));
+        assert_eq!(tracker.delta(), None);
+
+        tracker.record(start, stats(1000, 10));
+        assert_eq!(tracker.delta(), None);
+
+        tracker.record(start + chrono::Duration::minutes(30), stats(1500, 20));
+        let delta = tracker.delta().unwrap();
+        assert_eq!(delta.duration, Duration::from_secs(30 * 60));
+        assert_eq!(delta.validator_payments, 500.into());
+        assert_eq!(delta.validator_payments_per_hour(), 1000.into());
+        assert_eq!(delta.validator_payments_per_day(), 24000.into());
+        assert_eq!(delta.gas_simulated_per_day(), 480.into());
+
+        // The first sample falls out of the window
+        tracker.record(start + chrono::Duration::minutes(195), stats(2000, 30));
+        assert_eq!(tracker.samples().count(), 2);
+        let delta = tracker.delta().unwrap();
+        assert_eq!(delta.duration, Duration::from_secs(165 * 60));
+        assert_eq!(delta.validator_payments, 500.into());
+        assert_eq!(
+            tracker.latest().unwrap().all_time_validator_payments,
+            2000.into()
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use ethers::core::types::{H160, U256, U64};
-use serde::{de, Deserialize};
+use serde::{de, Deserialize, Serializer};
 use serde_json::Value;
 use std::str::FromStr;
 
@@ -66,3 +66,15 @@ where
         _ => return Err(de::Error::custom("expected a hexadecimal string")),
     })
 }
+
+pub fn serialize_optional_h160<S>(address: &Option<H160>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    // Relays use `0x` for contract creations, so do the same to make
+    // sure serialized transactions can be deserialized again.
+    match address {
+        Some(address) => serializer.serialize_str(&format!("{:?}", address)),
+        None => serializer.serialize_str("0x"),
+    }
+}